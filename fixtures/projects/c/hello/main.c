@@ -0,0 +1,7 @@
+#include <stdio.h>
+#include "list.h"
+
+int main() {
+    printf("%d\n", size());
+    return 0;
+}
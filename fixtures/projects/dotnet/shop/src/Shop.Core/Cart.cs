@@ -0,0 +1,6 @@
+namespace Shop.Core
+{
+    public class Cart
+    {
+    }
+}
@@ -0,0 +1,10 @@
+using Xunit;
+
+namespace Shop.Tests
+{
+    public class CartTest
+    {
+        [Fact]
+        public void ShouldCreate() => Assert.NotNull(new Shop.Core.Cart());
+    }
+}
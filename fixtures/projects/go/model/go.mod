@@ -0,0 +1,3 @@
+module github.com/example/model
+
+go 1.15
@@ -0,0 +1,15 @@
+module github.com/example/shop
+
+go 1.15
+
+require (
+	github.com/gin-gonic/gin v1.6.3
+	github.com/example/model v0.0.0-00010101000000-000000000000
+	github.com/google/uuid v1.1.2 // indirect
+)
+
+replace github.com/example/model => ../model
+
+replace (
+	github.com/google/uuid v1.1.2 => github.com/example/uuid v1.1.3
+)
@@ -0,0 +1,4 @@
+package com.example.shop.core;
+
+public class Cart {
+}
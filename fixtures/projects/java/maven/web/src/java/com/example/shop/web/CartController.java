@@ -0,0 +1,7 @@
+package com.example.shop.web;
+
+import com.example.shop.core.Cart;
+
+public class CartController {
+    private final Cart cart = new Cart();
+}
@@ -0,0 +1,7 @@
+package com.shop;
+
+import com.shop.core.Cart;
+
+public class App {
+    private Cart cart;
+}
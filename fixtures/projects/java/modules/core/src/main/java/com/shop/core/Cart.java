@@ -0,0 +1,4 @@
+package com.shop.core;
+
+public class Cart {
+}
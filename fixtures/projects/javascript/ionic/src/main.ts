@@ -0,0 +1,3 @@
+import { enableProdMode } from '@angular/core';
+
+enableProdMode();
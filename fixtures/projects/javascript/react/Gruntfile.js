@@ -0,0 +1,3 @@
+module.exports = function (grunt) {
+  grunt.registerTask('build', []);
+};
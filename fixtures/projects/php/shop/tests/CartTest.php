@@ -0,0 +1,14 @@
+<?php
+
+namespace Shop\Tests;
+
+use PHPUnit\Framework\TestCase;
+use Shop\Cart;
+
+class CartTest extends TestCase
+{
+    public function testCreate(): void
+    {
+        $this->assertNotNull(new Cart());
+    }
+}
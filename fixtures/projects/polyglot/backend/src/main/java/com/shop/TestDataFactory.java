@@ -0,0 +1,7 @@
+package com.shop;
+
+public class TestDataFactory {
+    public static String greeting() {
+        return Util.greeting();
+    }
+}
@@ -0,0 +1,6 @@
+package com.shop
+
+object Util {
+    @JvmStatic
+    fun greeting() = "hello"
+}
@@ -0,0 +1 @@
+describe("index", () => {});
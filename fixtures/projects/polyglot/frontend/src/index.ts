@@ -0,0 +1,3 @@
+import leftPad from 'left-pad';
+
+console.log(leftPad('shop', 8));
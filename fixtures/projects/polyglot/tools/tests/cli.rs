@@ -0,0 +1,2 @@
+#[test]
+fn should_run() {}
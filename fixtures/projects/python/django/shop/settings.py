@@ -0,0 +1 @@
+INSTALLED_APPS = ["django.contrib.admin"]
@@ -0,0 +1,11 @@
+from setuptools import find_packages, setup
+
+setup(
+    name="shop-web",
+    packages=find_packages(),
+    install_requires=[
+        "click>=7.0",
+        "flask",
+    ],
+    tests_require=["pytest-flask"],
+)
@@ -0,0 +1,14 @@
+use super::model::Item;
+
+pub const VERSION: &str = "1";
+
+#[derive(Debug)]
+pub struct Cart {
+    pub items: Vec<Item>,
+}
+
+impl Cart {
+    pub fn new(item: Item) -> Self {
+        Cart { items: vec![item] }
+    }
+}
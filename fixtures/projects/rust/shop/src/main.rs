@@ -0,0 +1,16 @@
+mod cart;
+mod model;
+
+use crate::{
+    cart::{self, Cart},
+    model::Item,
+};
+use model::Price;
+use std::collections::HashMap;
+
+fn main() {
+    let mut items: HashMap<String, Price> = HashMap::new();
+    items.insert(String::from("apple"), Price(1));
+    let cart = Cart::new(Item::new("apple"));
+    println!("{:?} {}", cart, cart::VERSION);
+}
@@ -0,0 +1,14 @@
+#[derive(Debug)]
+pub struct Item {
+    pub name: String,
+}
+
+impl Item {
+    pub fn new(name: &str) -> Self {
+        Item {
+            name: name.to_string(),
+        }
+    }
+}
+
+pub struct Price(pub u32);
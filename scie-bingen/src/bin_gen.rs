@@ -1,4 +1,4 @@
-use scie_model::artifact::CodeFile;
+use scie_model::artifact::{CodeFile, Workspace};
 use std::fs::File;
 use std::io::Write;
 
@@ -18,6 +18,19 @@ impl BinGen {
         };
     }
 
+    /// the workspace with its modules, packages and files
+    pub fn workspace(workspace: &Workspace, path: &str) {
+        let encoded: Vec<u8> = bincode::serialize(workspace).unwrap();
+        let mut file = File::create(path).unwrap();
+        let _ = file.write_all(&encoded);
+    }
+
+    pub fn jsonify_workspace(workspace: &Workspace, path: &str) {
+        let json_str = serde_json::to_string_pretty(workspace).unwrap();
+        let mut file = File::create(path).unwrap();
+        let _ = file.write_all(json_str.as_bytes());
+    }
+
     pub fn jsonify(files: Vec<CodeFile>, path: &str) {
         let json_str = serde_json::to_string_pretty(&files).unwrap();
         let bytes = json_str.as_bytes();
@@ -33,14 +46,19 @@ impl BinGen {
 #[cfg(test)]
 mod tests {
     use crate::bin_gen::BinGen;
-    use scie_model::artifact::CodeFile;
+    use scie_model::artifact::{CodeFile, SourceKind};
 
     #[test]
     fn should_build_code_gen_file() {
         let code_file = CodeFile {
             path: "path".to_string(),
             name: "name".to_string(),
+            language: "java".to_string(),
             elements: vec![],
+            symbols: vec![],
+            imports: vec![],
+            kind: SourceKind::Main,
+            is_test: false,
         };
         BinGen::code_files(vec![code_file], "demo.bin");
     }
@@ -8,7 +8,7 @@ impl ExtFile {
         let mut packages = vec![];
         let walk_dir = WalkDir::new(path);
 
-        let filtered_entries = walk_dir.max_depth(2).into_iter();
+        let filtered_entries = walk_dir.max_depth(2).sort_by_file_name().into_iter();
         for entry in filtered_entries {
             let entry = entry.unwrap();
             let path = entry.path();
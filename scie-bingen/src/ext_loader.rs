@@ -0,0 +1,271 @@
+use crate::ext_file::ExtFile;
+use crate::language_gen::{ExtConflict, ExtEntry, LangExtGen};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum ExtSource {
+    /// a dir of extensions, such as `extensions/` or `~/.vscode/extensions`
+    Dir(PathBuf),
+    /// a packaged extension, which is a zip with the extension in `extension/`
+    Vsix(PathBuf),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct ExtLoadReport {
+    /// the extension which wins each file extension, and each file name
+    pub winners: BTreeMap<String, ExtEntry>,
+    pub conflicts: Vec<ExtConflict>,
+    /// sources or packages which can't be loaded, with the reason
+    pub errors: Vec<String>,
+}
+
+/// Merge extensions from more places into one `LangExtGen`.
+///
+/// Precedence: sources are loaded in the order they are added, after the `builtin` languages,
+/// which are the ones of the embedded bundle for `scie_bundle::ext_loader`, and a later one
+/// wins a file extension claimed by an earlier one. In a dir, extensions are loaded by the
+/// sorted path, so the result is the same for every run.
+pub struct ExtLoader {
+    /// languages which the sources are merged onto
+    pub builtin: LangExtGen,
+    /// dir of the packages which `builtin` is made of, such as `extensions/`, `LangCheck`
+    /// validates them with the sources
+    pub builtin_dir: Option<PathBuf>,
+    pub sources: Vec<ExtSource>,
+    /// where `.vsix` files are extracted to
+    pub cache_dir: PathBuf,
+}
+
+impl ExtLoader {
+    pub fn with_builtin(builtin: LangExtGen) -> Self {
+        ExtLoader {
+            builtin,
+            builtin_dir: None,
+            sources: vec![],
+            cache_dir: env::temp_dir().join("scie-vsix"),
+        }
+    }
+
+    pub fn add_dir(&mut self, path: PathBuf) {
+        self.sources.push(ExtSource::Dir(path));
+    }
+
+    pub fn add_vsix(&mut self, path: PathBuf) {
+        self.sources.push(ExtSource::Vsix(path));
+    }
+
+    /// `.vsix` file, or dir of extensions
+    pub fn add_path(&mut self, path: PathBuf) {
+        let is_vsix = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("vsix"));
+        if is_vsix {
+            self.add_vsix(path);
+        } else {
+            self.add_dir(path);
+        }
+    }
+
+    pub fn load(&self) -> (LangExtGen, ExtLoadReport) {
+        let (packages, errors) = self.packages();
+        let (langs, mut report) = self.merge_packages(&packages);
+        report.errors.splice(0..0, errors);
+
+        (langs, report)
+    }
+
+    /// `package.json` files of all sources, in the order of precedence
+    pub fn packages(&self) -> (Vec<PathBuf>, Vec<String>) {
+        let mut packages = vec![];
+        let mut errors = vec![];
+        for source in self.sources.iter() {
+            match self.package_files(source) {
+                Ok(files) => packages.extend(files),
+                Err(err) => errors.push(err),
+            }
+        }
+
+        (packages, errors)
+    }
+
+    /// `package.json` files of `builtin_dir`, in the order of `LangExtGen::from_path`
+    pub fn builtin_packages(&self) -> Vec<PathBuf> {
+        match &self.builtin_dir {
+            Some(dir) if dir.is_dir() => ExtFile::walk_dir(dir.display().to_string()),
+            _ => vec![],
+        }
+    }
+
+    /// packages onto the `builtin` languages
+    pub fn merge_packages(&self, packages: &[PathBuf]) -> (LangExtGen, ExtLoadReport) {
+        ExtLoader::merge_onto(self.builtin.clone(), packages)
+    }
+
+    /// packages onto `langs`, a later package wins the earlier ones
+    pub fn merge_onto(mut langs: LangExtGen, packages: &[PathBuf]) -> (LangExtGen, ExtLoadReport) {
+        let mut report = ExtLoadReport::default();
+
+        for package in packages {
+            let mut package_langs = LangExtGen::new();
+            if let Err(err) = package_langs.add_package(package) {
+                report
+                    .errors
+                    .push(format!("{}: {}", package.display(), err));
+                continue;
+            }
+
+            report.conflicts.extend(langs.merge(package_langs));
+        }
+
+        for (key, entry) in langs.ext_map.iter().chain(langs.filename_map.iter()) {
+            report.winners.insert(key.clone(), entry.clone());
+        }
+
+        (langs, report)
+    }
+
+    fn package_files(&self, source: &ExtSource) -> Result<Vec<PathBuf>, String> {
+        match source {
+            ExtSource::Dir(path) => {
+                if !path.is_dir() {
+                    return Err(format!("{}: not a dir", path.display()));
+                }
+
+                Ok(ExtFile::walk_dir(path.display().to_string()))
+            }
+            ExtSource::Vsix(path) => {
+                let target = self.extract_vsix(path)?;
+                Ok(vec![target.join("package.json")])
+            }
+        }
+    }
+
+    fn extract_vsix(&self, path: &Path) -> Result<PathBuf, String> {
+        let name = path
+            .file_stem()
+            .ok_or(format!("{}: no file name", path.display()))?;
+        let target = self.cache_dir.join(name);
+        if target.exists() {
+            fs::remove_dir_all(&target).map_err(|err| err.to_string())?;
+        }
+
+        let file = File::open(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let mut zip =
+            ZipArchive::new(file).map_err(|err| format!("{}: {}", path.display(), err))?;
+        for index in 0..zip.len() {
+            let mut entry = zip
+                .by_index(index)
+                .map_err(|err| format!("{}: {}", path.display(), err))?;
+            if entry.is_dir() {
+                continue;
+            }
+            // `None` for names out of the target, such as `../`
+            let relative = match entry
+                .enclosed_name()
+                .and_then(|name| name.strip_prefix("extension").ok())
+            {
+                Some(relative) => target.join(relative),
+                None => continue,
+            };
+
+            if let Some(parent) = relative.parent() {
+                fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+            }
+            File::create(&relative)
+                .and_then(|mut output| io::copy(&mut entry, &mut output))
+                .map_err(|err| format!("{}: {}", relative.display(), err))?;
+        }
+
+        Ok(target)
+    }
+}
+
+impl ExtLoadReport {
+    /// `key -> winner (language)`, with the overridden ones
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        for (key, winner) in self.winners.iter() {
+            output.push_str(&format!("{} -> {} ({})\n", key, winner.path, winner.name));
+            for conflict in self
+                .conflicts
+                .iter()
+                .filter(|conflict| &conflict.key == key)
+            {
+                output.push_str(&format!(
+                    "    overrides {} ({})\n",
+                    conflict.loser.path, conflict.loser.name
+                ));
+            }
+        }
+
+        for err in self.errors.iter() {
+            output.push_str(&format!("error: {}\n", err));
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ext_loader::ExtLoader;
+    use crate::language_gen::LangExtGen;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    fn fixtures_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("extensions")
+    }
+
+    #[test]
+    fn should_let_user_dir_win_builtin() {
+        let mut loader = ExtLoader::with_builtin(LangExtGen::default());
+        loader.add_path(fixtures_dir().join("user"));
+
+        let (langs, report) = loader.load();
+        assert!(langs.ext_map[".yml"]
+            .path
+            .ends_with("acme.vscode-yaml-1.0.0"));
+        assert!(langs.ext_map[".java"].path.ends_with("java"));
+
+        let conflict = report
+            .conflicts
+            .iter()
+            .find(|conflict| conflict.key == ".yml")
+            .unwrap();
+        assert!(conflict.loser.path.ends_with("yaml"));
+        assert!(report.render().contains("overrides"));
+    }
+
+    #[test]
+    fn should_load_vsix() {
+        let cache = tempdir().unwrap();
+        let mut loader = ExtLoader::with_builtin(LangExtGen::default());
+        loader.cache_dir = cache.path().to_path_buf();
+        loader.add_path(fixtures_dir().join("vsix").join("scie-toml-0.0.1.vsix"));
+
+        let (langs, report) = loader.load();
+        assert!(report.errors.is_empty());
+        assert_eq!("toml", langs.ext_map[".toml"].name);
+        assert_eq!("toml", langs.filename_map["Cargo.lock"].name);
+        assert!(PathBuf::from(&langs.config_paths["toml"]).exists());
+    }
+
+    #[test]
+    fn should_report_missing_source() {
+        let mut loader = ExtLoader::with_builtin(LangExtGen::new());
+        loader.add_dir(fixtures_dir().join("not-exists"));
+
+        let (_langs, report) = loader.load();
+        assert_eq!(1, report.errors.len());
+    }
+}
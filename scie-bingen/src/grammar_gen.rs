@@ -1,9 +1,9 @@
-use crate::language_gen::LangExtGen;
+use crate::language_gen::{ExtEntry, LangExtGen};
 use scie_grammar::inter::IRawGrammar;
+use scie_infra::finder::Finder;
 use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
-use std::io::{Read, Write};
-use std::path::Path;
+use std::io::Write;
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct LangConfig {
@@ -30,16 +30,14 @@ impl GrammarGen {
         let config_map = GrammarGen::build_grammar_map();
         let mut grammar_map: HashMap<String, IRawGrammar> = Default::default();
         for (lang, config) in config_map {
-            let path = Path::new(&config.path);
-            let mut file = File::open(path).unwrap();
-            let mut data = String::new();
-            file.read_to_string(&mut data).unwrap();
-
-            match serde_json::from_str(&data) {
-                Ok(x) => {
-                    grammar_map.insert(lang, x);
+            let grammar = Finder::read_code(&LangExtGen::builtin_dir().join(&config.path))
+                .and_then(|code| serde_json::from_str(&code).map_err(|err| err.to_string()));
+            match grammar {
+                Ok(grammar) => {
+                    grammar_map.insert(lang, grammar);
                 }
-                Err(err) => panic!(err),
+                // listed by `LangCheck`
+                Err(err) => println!("grammar: {:?}, err: {:?}", config.path, err),
             };
         }
 
@@ -48,12 +46,31 @@ impl GrammarGen {
 
     pub fn build_grammar_map() -> BTreeMap<String, LangConfig> {
         let langs = LangExtGen::default();
+        GrammarGen::lang_configs(&langs, &langs.ext_map)
+    }
+
+    /// same as `build_grammar_map`, but by exact file names, such as `Makefile`
+    pub fn build_filename_grammar_map() -> BTreeMap<String, LangConfig> {
+        let langs = LangExtGen::default();
+        GrammarGen::lang_configs(&langs, &langs.filename_map)
+    }
+
+    pub fn lang_configs(
+        langs: &LangExtGen,
+        entries: &HashMap<String, ExtEntry>,
+    ) -> BTreeMap<String, LangConfig> {
         let mut raw_grammar_map: BTreeMap<String, LangConfig> = Default::default();
-        for (ext, entry) in langs.ext_map.iter() {
+        for (ext, entry) in entries.iter() {
             let lang = entry.name.clone();
-            let tm_grammar = langs.grammar_map[&lang].clone();
+            let tm_grammar = match langs.grammar_map.get(&lang) {
+                None => continue,
+                Some(grammar) => grammar.clone(),
+            };
 
-            let mut grammar_path = entry.path.clone();
+            let mut grammar_path = match langs.grammar_dirs.get(&lang) {
+                None => entry.path.clone(),
+                Some(dir) => dir.clone(),
+            };
             grammar_path.push_str(&*tm_grammar.path);
             grammar_path = grammar_path.replace("./", "/");
 
@@ -100,14 +117,22 @@ impl GrammarGen {
 #[cfg(test)]
 mod tests {
     use crate::grammar_gen::GrammarGen;
+    use crate::language_gen::LangExtGen;
     use scie_grammar::grammar::{Grammar, StackElement};
 
     #[test]
     fn should_build_default_maps() {
         let map = GrammarGen::build_grammar_map();
-        assert!(map[".zshrc"]
-            .path
-            .contains("extensions/shellscript/syntaxes/shell-unix-bash.tmLanguage.json"));
+        assert_eq!(
+            "shellscript/syntaxes/shell-unix-bash.tmLanguage.json",
+            map[".zshrc"].path
+        );
+    }
+
+    #[test]
+    fn should_build_filename_maps() {
+        let map = GrammarGen::build_filename_grammar_map();
+        assert_eq!("makefile", map["Makefile"].lang);
     }
 
     #[test]
@@ -120,9 +145,9 @@ mod tests {
     #[test]
     fn should_use_grammar_gen_map_build_grammar() {
         let map = GrammarGen::build_grammar_map();
-        let groovy_path = map[".gradle"].path.clone();
+        let groovy_path = LangExtGen::builtin_dir().join(&map[".gradle"].path);
 
-        let mut grammar = Grammar::from_file(&*groovy_path);
+        let mut grammar = Grammar::from_file(groovy_path.to_str().unwrap());
         let mut rule_stack = Some(StackElement::null());
 
         let result = grammar.tokenize_line("println \"hello, world!\"", &mut rule_stack);
@@ -0,0 +1,260 @@
+use crate::grammar_gen::{GrammarGen, LangConfig};
+use crate::language_gen::LangExtGen;
+use scie_grammar::inter::IRawGrammar;
+use scie_infra::finder::Finder;
+use scie_infra::jsonc::strip_jsonc;
+use scie_model::LanguageConfig;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// bump it when the layout of the bundle or the types in it changes
+pub const LANG_BUNDLE_VERSION: u32 = 3;
+
+/// All languages, grammars and language configurations of `extensions/` in one bincode blob,
+/// which is embedded in the binary, so nothing is read from the filesystem at runtime. The
+/// paths of the bundled languages are relative to `extensions/`, the merged ones are kept as
+/// they are loaded.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct LangBundle {
+    pub version: u32,
+    pub langs: LangExtGen,
+    /// file extension to language
+    pub ext_map: BTreeMap<String, LangConfig>,
+    /// exact file name to language
+    pub filename_map: BTreeMap<String, LangConfig>,
+    /// language id to grammar
+    pub grammars: BTreeMap<String, IRawGrammar>,
+    /// language id to `language-configuration.json`
+    pub configs: BTreeMap<String, LanguageConfig>,
+}
+
+/// the bincode form of `LangBundle`, the grammars, the configurations and the languages are
+/// kept as JSON, for the `skip_serializing_if` and `flatten` of their types which bincode can't
+/// read back
+#[derive(Serialize, Deserialize)]
+struct LangBundleData {
+    version: u32,
+    langs: String,
+    ext_map: BTreeMap<String, LangConfig>,
+    filename_map: BTreeMap<String, LangConfig>,
+    grammars: BTreeMap<String, String>,
+    configs: BTreeMap<String, String>,
+}
+
+impl Default for LangBundle {
+    fn default() -> Self {
+        LangBundle::from_path(LangExtGen::builtin_dir())
+    }
+}
+
+impl LangBundle {
+    pub fn from_path(ext_path: PathBuf) -> Self {
+        let langs = LangExtGen::from_path(ext_path.clone());
+        LangBundle::from_lang_ext(&ext_path, langs)
+    }
+
+    /// relative paths of `langs` are read from `ext_path`
+    pub fn from_lang_ext(ext_path: &Path, langs: LangExtGen) -> Self {
+        let ext_map = GrammarGen::lang_configs(&langs, &langs.ext_map);
+        let filename_map = GrammarGen::lang_configs(&langs, &langs.filename_map);
+
+        let mut grammars: BTreeMap<String, IRawGrammar> = Default::default();
+        for config in ext_map.values().chain(filename_map.values()) {
+            if grammars.contains_key(&config.lang) {
+                continue;
+            }
+
+            match LangBundle::read_grammar(&ext_path.join(&config.path)) {
+                Ok(grammar) => {
+                    grammars.insert(config.lang.clone(), grammar);
+                }
+                Err(err) => println!("grammar: {:?}, err: {:?}", config.path, err),
+            }
+        }
+
+        LangBundle {
+            version: LANG_BUNDLE_VERSION,
+            configs: LangBundle::build_configs(ext_path, &langs),
+            langs,
+            ext_map,
+            filename_map,
+            grammars,
+        }
+    }
+
+    /// languages of a loader onto the bundle, such as the merged ones of `ExtLoader`, the
+    /// grammars and configurations which are not the bundled ones are read from the disk, by
+    /// the paths of the extensions which are loaded
+    pub fn merge_langs(&mut self, langs: LangExtGen) -> Vec<String> {
+        let ext_map = GrammarGen::lang_configs(&langs, &langs.ext_map);
+        let filename_map = GrammarGen::lang_configs(&langs, &langs.filename_map);
+
+        let mut errors = vec![];
+        let mut loaded: BTreeSet<String> = Default::default();
+        for config in ext_map.values().chain(filename_map.values()) {
+            let is_bundled = self
+                .ext_map
+                .values()
+                .chain(self.filename_map.values())
+                .any(|bundled| bundled == config);
+            if is_bundled || !loaded.insert(config.lang.clone()) {
+                continue;
+            }
+
+            match LangBundle::read_grammar(Path::new(&config.path)) {
+                Ok(grammar) => {
+                    self.grammars.insert(config.lang.clone(), grammar);
+                }
+                Err(err) => errors.push(format!("{}: {}", config.path, err)),
+            }
+        }
+
+        for (lang, path) in langs.config_paths.iter() {
+            if self.langs.config_paths.get(lang) == Some(path) {
+                continue;
+            }
+            if let Some(config) = LangBundle::read_config(Path::new(path)) {
+                self.configs.insert(lang.clone(), config);
+            }
+        }
+
+        self.ext_map = ext_map;
+        self.filename_map = filename_map;
+        self.langs = langs;
+        errors
+    }
+
+    fn build_configs(ext_path: &Path, langs: &LangExtGen) -> BTreeMap<String, LanguageConfig> {
+        let mut configs: BTreeMap<String, LanguageConfig> = Default::default();
+        for (lang, path) in langs.config_paths.iter() {
+            if let Some(config) = LangBundle::read_config(&ext_path.join(path)) {
+                configs.insert(lang.clone(), config);
+            }
+        }
+
+        configs
+    }
+
+    fn read_grammar(path: &Path) -> Result<IRawGrammar, String> {
+        Finder::read_code(&path.to_path_buf())
+            .and_then(|code| serde_json::from_str(&code).map_err(|err| err.to_string()))
+    }
+
+    fn read_config(path: &Path) -> Option<LanguageConfig> {
+        let code = Finder::read_code(&path.to_path_buf()).ok()?;
+        serde_json::from_str(&strip_jsonc(&code)).ok()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let data = LangBundleData {
+            version: self.version,
+            langs: serde_json::to_string(&self.langs).unwrap(),
+            ext_map: self.ext_map.clone(),
+            filename_map: self.filename_map.clone(),
+            grammars: LangBundle::to_json_map(&self.grammars),
+            configs: LangBundle::to_json_map(&self.configs),
+        };
+        bincode::serialize(&data).unwrap()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<LangBundle, String> {
+        let data: LangBundleData = bincode::deserialize(bytes).map_err(|err| err.to_string())?;
+        if data.version != LANG_BUNDLE_VERSION {
+            return Err(format!(
+                "lang bundle version {} is not supported, expected {}",
+                data.version, LANG_BUNDLE_VERSION
+            ));
+        }
+
+        Ok(LangBundle {
+            version: data.version,
+            langs: serde_json::from_str(&data.langs).map_err(|err| err.to_string())?,
+            ext_map: data.ext_map,
+            filename_map: data.filename_map,
+            grammars: LangBundle::from_json_map(data.grammars)?,
+            configs: LangBundle::from_json_map(data.configs)?,
+        })
+    }
+
+    fn to_json_map<T: Serialize>(map: &BTreeMap<String, T>) -> BTreeMap<String, String> {
+        map.iter()
+            .map(|(lang, value)| (lang.clone(), serde_json::to_string(value).unwrap()))
+            .collect()
+    }
+
+    fn from_json_map<T: DeserializeOwned>(
+        map: BTreeMap<String, String>,
+    ) -> Result<BTreeMap<String, T>, String> {
+        map.into_iter()
+            .map(|(lang, json)| {
+                serde_json::from_str(&json)
+                    .map(|value| (lang.clone(), value))
+                    .map_err(|err| format!("{}: {}", lang, err))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ext_loader::ExtLoader;
+    use crate::lang_bundle::LangBundle;
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_roundtrip_bundle_by_bincode() {
+        let bundle = LangBundle::default();
+        assert!(bundle.grammars.contains_key("java"));
+        assert!(bundle.configs.contains_key("php"));
+
+        let decoded = LangBundle::from_bytes(&bundle.to_bytes()).unwrap();
+        assert_eq!(bundle, decoded);
+    }
+
+    #[test]
+    fn should_keep_repository_of_grammar() {
+        let bundle = LangBundle::default();
+        let decoded = LangBundle::from_bytes(&bundle.to_bytes()).unwrap();
+
+        let repository = decoded.grammars["java"].repository.clone().unwrap();
+        assert!(!repository.map.name_map.is_empty());
+        assert_eq!(
+            Some(String::from("//")),
+            decoded.configs["php"]
+                .comments
+                .clone()
+                .unwrap()
+                .line_comment
+        );
+    }
+
+    #[test]
+    fn should_merge_user_grammars_onto_bundle() {
+        let mut bundle = LangBundle::default();
+        let builtin_yaml = bundle.grammars["yaml"].clone();
+
+        let mut loader = ExtLoader::with_builtin(bundle.langs.clone());
+        loader.add_dir(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .parent()
+                .unwrap()
+                .join("fixtures")
+                .join("extensions")
+                .join("user"),
+        );
+        let (langs, _report) = loader.load();
+
+        assert!(bundle.merge_langs(langs).is_empty());
+        assert!(bundle.ext_map[".yml"]
+            .path
+            .contains("acme.vscode-yaml-1.0.0"));
+        assert_ne!(builtin_yaml, bundle.grammars["yaml"]);
+        assert!(bundle.grammars.contains_key("java"));
+        assert_eq!(
+            "java/syntaxes/java.tmLanguage.json",
+            bundle.ext_map[".java"].path
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use crate::ext_loader::ExtLoader;
+use crate::language_gen::{ExtConflict, LangExtGen};
+use scie_infra::finder::Finder;
+use scie_infra::plist::parse_plist;
+use scie_model::{JsonPackage, TMGrammar};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct GrammarIssue {
+    /// scope name of the grammar which has the issue
+    pub scope_name: String,
+    /// the path, language or scope which can't be resolved
+    pub target: String,
+    pub detail: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct LangCheckReport {
+    /// file extensions and file names claimed by more than one extension
+    pub collisions: Vec<ExtConflict>,
+    /// languages which are declared, but have no grammar
+    pub langs_without_grammar: Vec<String>,
+    /// grammars without `language`, which are only injected or included, and never picked
+    /// by a file extension
+    pub grammars_without_language: Vec<String>,
+    pub missing_grammars: Vec<GrammarIssue>,
+    /// grammars which are neither JSON nor plist, such as YAML ones, they can't be loaded
+    pub unsupported_grammars: Vec<GrammarIssue>,
+    /// `embeddedLanguages` which point to an unknown language
+    pub unresolved_embedded: Vec<GrammarIssue>,
+    /// scopes of `include` and `injectTo` which no grammar provides
+    pub unresolved_scopes: Vec<GrammarIssue>,
+    pub errors: Vec<String>,
+}
+
+struct PackageGrammar {
+    grammar: TMGrammar,
+    path: PathBuf,
+}
+
+/// Validate the languages and grammars of all packages of a loader, `LangExtGen` and
+/// `GrammarGen` take the first usable answer, so the broken ones are only visible here.
+pub struct LangCheck {}
+
+impl LangCheck {
+    /// the packages of `builtin_dir` are checked with the ones of the sources, so collisions
+    /// between the builtin packages are reported too
+    pub fn check(loader: &ExtLoader) -> LangCheckReport {
+        let mut packages = loader.builtin_packages();
+        let (source_packages, errors) = loader.packages();
+        let (_langs, load_report) = if packages.is_empty() {
+            loader.merge_packages(&source_packages)
+        } else {
+            packages.extend(source_packages);
+            ExtLoader::merge_onto(LangExtGen::new(), &packages)
+        };
+
+        let mut report = LangCheckReport {
+            collisions: load_report.conflicts,
+            errors,
+            ..Default::default()
+        };
+        report.errors.extend(load_report.errors);
+
+        let mut declared_langs: BTreeSet<String> = Default::default();
+        let mut grammars: Vec<PackageGrammar> = vec![];
+        for package in packages.iter() {
+            let pkg: JsonPackage = match Finder::read_code(package)
+                .and_then(|code| serde_json::from_str(&code).map_err(|err| err.to_string()))
+            {
+                Ok(pkg) => pkg,
+                Err(_) => continue,
+            };
+
+            let dir = package.parent().unwrap();
+            for lang in pkg.contributes.languages.unwrap_or_default() {
+                declared_langs.insert(lang.id);
+            }
+            for grammar in pkg.contributes.grammars.unwrap_or_default() {
+                grammars.push(PackageGrammar {
+                    path: dir.join(grammar.path.trim_start_matches("./")),
+                    grammar,
+                });
+            }
+        }
+
+        // the builtin languages resolve scopes and languages of the packages too
+        let builtin = &loader.builtin;
+        let scopes: BTreeSet<String> = grammars
+            .iter()
+            .map(|item| &item.grammar)
+            .chain(builtin.grammar_map.values())
+            .map(|grammar| grammar.scope_name.clone())
+            .collect();
+        let grammar_langs: BTreeSet<String> = grammars
+            .iter()
+            .map(|item| &item.grammar)
+            .chain(builtin.grammar_map.values())
+            .filter_map(|grammar| grammar.language.clone())
+            .collect();
+
+        report.langs_without_grammar = declared_langs
+            .iter()
+            .filter(|lang| !grammar_langs.contains(*lang))
+            .cloned()
+            .collect();
+
+        for item in grammars.iter() {
+            let scope_name = &item.grammar.scope_name;
+            if item.grammar.language.is_none() {
+                report.grammars_without_language.push(scope_name.clone());
+            }
+
+            for (embedded_scope, lang) in item.grammar.embedded_languages.iter().flatten() {
+                if !declared_langs.contains(lang) && !grammar_langs.contains(lang) {
+                    report.unresolved_embedded.push(GrammarIssue {
+                        scope_name: scope_name.clone(),
+                        target: lang.clone(),
+                        detail: embedded_scope.clone(),
+                    });
+                }
+            }
+
+            for target in item.grammar.inject_to.iter().flatten() {
+                if !scopes.contains(target) {
+                    report.unresolved_scopes.push(GrammarIssue {
+                        scope_name: scope_name.clone(),
+                        target: target.clone(),
+                        detail: String::from("injectTo"),
+                    });
+                }
+            }
+
+            let value = match LangCheck::read_grammar(&item.path) {
+                Ok(Some(value)) => value,
+                Ok(None) => {
+                    report.unsupported_grammars.push(GrammarIssue {
+                        scope_name: scope_name.clone(),
+                        target: item.path.display().to_string(),
+                        detail: String::from("unsupported format"),
+                    });
+                    continue;
+                }
+                Err(err) => {
+                    report.missing_grammars.push(GrammarIssue {
+                        scope_name: scope_name.clone(),
+                        target: item.path.display().to_string(),
+                        detail: err,
+                    });
+                    continue;
+                }
+            };
+
+            let mut includes: BTreeSet<String> = Default::default();
+            LangCheck::collect_includes(&value, &mut includes);
+            for include in includes {
+                let scope = include.split('#').next().unwrap_or_default();
+                if !scopes.contains(scope) {
+                    report.unresolved_scopes.push(GrammarIssue {
+                        scope_name: scope_name.clone(),
+                        target: scope.to_string(),
+                        detail: format!("include: {}", include),
+                    });
+                }
+            }
+        }
+
+        report
+    }
+
+    /// JSON, or plist XML such as `.tmLanguage`, `None` for other formats
+    fn read_grammar(path: &Path) -> Result<Option<serde_json::Value>, String> {
+        if !path.exists() {
+            return Err(String::from("file not found"));
+        }
+
+        let code = Finder::read_code(&path.to_path_buf())?;
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if is_json {
+            serde_json::from_str(&code)
+                .map(Some)
+                .map_err(|err| err.to_string())
+        } else if code.trim_start().starts_with('<') {
+            parse_plist(&code).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// external includes only, `$self`, `$base` and `#name` are resolved in the grammar
+    fn collect_includes(value: &serde_json::Value, includes: &mut BTreeSet<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, item) in map.iter() {
+                    match (key.as_str(), item) {
+                        ("include", serde_json::Value::String(include)) => {
+                            if !include.starts_with('#') && !include.starts_with('$') {
+                                includes.insert(include.clone());
+                            }
+                        }
+                        _ => LangCheck::collect_includes(item, includes),
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    LangCheck::collect_includes(item, includes);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl LangCheckReport {
+    pub fn is_ok(&self) -> bool {
+        self.collisions.is_empty()
+            && self.missing_grammars.is_empty()
+            && self.unsupported_grammars.is_empty()
+            && self.unresolved_embedded.is_empty()
+            && self.unresolved_scopes.is_empty()
+            && self.errors.is_empty()
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!("collisions: {}\n", self.collisions.len()));
+        for conflict in self.collisions.iter() {
+            output.push_str(&format!(
+                "    {}: {} ({}) overrides {} ({})\n",
+                conflict.key,
+                conflict.winner.name,
+                conflict.winner.path,
+                conflict.loser.name,
+                conflict.loser.path
+            ));
+        }
+
+        output.push_str(&format!(
+            "languages without grammar: {}\n",
+            self.langs_without_grammar.len()
+        ));
+        for lang in self.langs_without_grammar.iter() {
+            output.push_str(&format!("    {}\n", lang));
+        }
+
+        output.push_str(&format!(
+            "grammars without language: {}\n",
+            self.grammars_without_language.len()
+        ));
+        for scope in self.grammars_without_language.iter() {
+            output.push_str(&format!("    {}\n", scope));
+        }
+
+        let sections = vec![
+            ("missing grammar files", &self.missing_grammars),
+            ("unsupported grammar files", &self.unsupported_grammars),
+            ("unresolved embedded languages", &self.unresolved_embedded),
+            ("unresolved scopes", &self.unresolved_scopes),
+        ];
+        for (title, issues) in sections {
+            output.push_str(&format!("{}: {}\n", title, issues.len()));
+            for issue in issues.iter() {
+                output.push_str(&format!(
+                    "    {}: {} ({})\n",
+                    issue.scope_name, issue.target, issue.detail
+                ));
+            }
+        }
+
+        output.push_str(&format!("errors: {}\n", self.errors.len()));
+        for err in self.errors.iter() {
+            output.push_str(&format!("    {}\n", err));
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ext_loader::ExtLoader;
+    use crate::lang_check::LangCheck;
+    use crate::language_gen::LangExtGen;
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_report_broken_packages() {
+        let broken_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("extensions")
+            .join("broken");
+        let mut loader = ExtLoader::with_builtin(LangExtGen::default());
+        loader.builtin_dir = Some(LangExtGen::builtin_dir());
+        loader.add_dir(broken_dir);
+
+        let report = LangCheck::check(&loader);
+        assert!(!report.is_ok());
+
+        let collision = report.collisions.iter().find(|c| c.key == ".java").unwrap();
+        assert_eq!("broken", collision.winner.name);
+        assert_eq!("java", collision.loser.name);
+
+        assert!(report
+            .langs_without_grammar
+            .contains(&String::from("lonely")));
+        assert!(report
+            .grammars_without_language
+            .contains(&String::from("source.broken.embedded")));
+
+        let missing: Vec<&str> = report
+            .missing_grammars
+            .iter()
+            .map(|issue| issue.scope_name.as_str())
+            .collect();
+        assert_eq!(vec!["source.broken"], missing);
+        let unsupported: Vec<&str> = report
+            .unsupported_grammars
+            .iter()
+            .map(|issue| issue.scope_name.as_str())
+            .collect();
+        assert_eq!(vec!["source.broken.flow"], unsupported);
+        assert!(report
+            .unresolved_embedded
+            .iter()
+            .any(|issue| issue.target == "nope"));
+
+        let scopes: Vec<String> = report
+            .unresolved_scopes
+            .iter()
+            .filter(|issue| issue.scope_name == "source.broken.embedded")
+            .map(|issue| issue.target.clone())
+            .collect();
+        assert_eq!(vec!["source.unknown", "source.nothing"], scopes);
+        assert!(report.unresolved_scopes.iter().any(|issue| issue.scope_name
+            == "source.broken.legacy"
+            && issue.target == "source.gone"));
+    }
+}
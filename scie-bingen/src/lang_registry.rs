@@ -0,0 +1,159 @@
+use crate::lang_bundle::LangBundle;
+use scie_grammar::inter::IRawGrammar;
+use scie_model::LanguageConfig;
+
+/// common names which no extension declares, used by fenced code blocks
+static EXTRA_ALIASES: [(&str, &str); 5] = [
+    ("golang", "go"),
+    ("sh", "shellscript"),
+    ("bash", "shellscript"),
+    ("py", "python"),
+    ("rs", "rust"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageInfo<'a> {
+    pub id: String,
+    pub scope_name: Option<String>,
+    pub grammar: Option<&'a IRawGrammar>,
+    pub config: Option<&'a LanguageConfig>,
+    /// file extensions which resolve to the language
+    pub extensions: Vec<String>,
+    /// exact file names which resolve to the language
+    pub filenames: Vec<String>,
+}
+
+/// Name based lookups of the languages in a `LangBundle`, by id, alias, MIME type or scope name.
+pub struct LanguageRegistry<'a> {
+    pub bundle: &'a LangBundle,
+}
+
+impl<'a> LanguageRegistry<'a> {
+    pub fn new(bundle: &'a LangBundle) -> Self {
+        LanguageRegistry { bundle }
+    }
+
+    pub fn by_id(&self, id: &str) -> Option<LanguageInfo<'a>> {
+        let langs = &self.bundle.langs;
+        let known = langs.grammar_map.contains_key(id)
+            || langs.config_paths.contains_key(id)
+            || langs.ext_map.values().any(|entry| entry.name == id)
+            || langs.filename_map.values().any(|entry| entry.name == id);
+        if !known {
+            return None;
+        }
+
+        let mut extensions: Vec<String> = langs
+            .ext_map
+            .iter()
+            .filter(|(_, entry)| entry.name == id)
+            .map(|(ext, _)| ext.clone())
+            .collect();
+        extensions.sort();
+        let mut filenames: Vec<String> = langs
+            .filename_map
+            .iter()
+            .filter(|(_, entry)| entry.name == id)
+            .map(|(name, _)| name.clone())
+            .collect();
+        filenames.sort();
+
+        Some(LanguageInfo {
+            id: id.to_string(),
+            scope_name: langs
+                .grammar_map
+                .get(id)
+                .map(|grammar| grammar.scope_name.clone()),
+            grammar: self.bundle.grammars.get(id),
+            config: self.bundle.configs.get(id),
+            extensions,
+            filenames,
+        })
+    }
+
+    /// case insensitive, such as `JavaScript`, `js` or `golang`
+    pub fn by_alias(&self, alias: &str) -> Option<LanguageInfo<'a>> {
+        let alias = alias.to_lowercase();
+        if let Some(id) = self.bundle.langs.aliases.get(&alias) {
+            return self.by_id(id);
+        }
+
+        EXTRA_ALIASES
+            .iter()
+            .find(|(name, _)| *name == alias)
+            .and_then(|(_, id)| self.by_id(id))
+    }
+
+    pub fn by_mimetype(&self, mimetype: &str) -> Option<LanguageInfo<'a>> {
+        self.bundle
+            .langs
+            .mimetypes
+            .get(mimetype)
+            .and_then(|id| self.by_id(id))
+    }
+
+    pub fn by_scope_name(&self, scope_name: &str) -> Option<LanguageInfo<'a>> {
+        self.bundle
+            .langs
+            .grammar_map
+            .iter()
+            .find(|(_, grammar)| grammar.scope_name == scope_name)
+            .and_then(|(id, _)| self.by_id(id))
+    }
+
+    /// the info string of a fenced code block, such as `rust`, `js` or `ts`: id, then alias,
+    /// then file extension
+    pub fn by_name(&self, name: &str) -> Option<LanguageInfo<'a>> {
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        self.by_id(&name.to_lowercase())
+            .or_else(|| self.by_alias(name))
+            .or_else(|| {
+                self.bundle
+                    .langs
+                    .ext_map
+                    .get(&format!(".{}", name.to_lowercase()))
+                    .and_then(|entry| self.by_id(&entry.name))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lang_bundle::LangBundle;
+    use crate::lang_registry::LanguageRegistry;
+
+    #[test]
+    fn should_lookup_by_alias_and_id() {
+        let bundle = LangBundle::default();
+        let registry = LanguageRegistry::new(&bundle);
+
+        let js = registry.by_alias("JS").unwrap();
+        assert_eq!("javascript", js.id);
+        assert!(js.extensions.contains(&String::from(".js")));
+        assert!(js.grammar.is_some());
+        assert!(js.config.is_some());
+
+        assert_eq!("go", registry.by_alias("golang").unwrap().id);
+        assert_eq!("makefile", registry.by_id("makefile").unwrap().id);
+        assert!(registry
+            .by_id("makefile")
+            .unwrap()
+            .filenames
+            .contains(&String::from("Makefile")));
+        assert!(registry.by_id("not-a-lang").is_none());
+    }
+
+    #[test]
+    fn should_lookup_by_mimetype_and_scope_name() {
+        let bundle = LangBundle::default();
+        let registry = LanguageRegistry::new(&bundle);
+
+        assert_eq!("css", registry.by_mimetype("text/css").unwrap().id);
+        assert_eq!("rust", registry.by_scope_name("source.rust").unwrap().id);
+        assert_eq!("typescript", registry.by_name("ts").unwrap().id);
+    }
+}
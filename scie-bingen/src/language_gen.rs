@@ -1,10 +1,11 @@
 use crate::ext_file::ExtFile;
 use scie_infra::finder::Finder;
+use scie_infra::paths::relative_path;
 use scie_model::{JsonPackage, TMGrammar};
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct ExtEntry {
@@ -15,18 +16,30 @@ pub struct ExtEntry {
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct LangExtGen {
     pub ext_map: HashMap<String, ExtEntry>,
+    /// exact file names, such as `Makefile` or `Dockerfile`
+    pub filename_map: HashMap<String, ExtEntry>,
     pub grammar_map: HashMap<String, TMGrammar>,
+    /// language to the extension dir of its grammar, which may differ from the `ext_map` one
+    pub grammar_dirs: HashMap<String, String>,
+    /// language to the path of its `language-configuration.json`
+    pub config_paths: HashMap<String, String>,
+    /// lowercase alias to language, such as `js` to `javascript`
+    pub aliases: HashMap<String, String>,
+    /// MIME type to language
+    pub mimetypes: HashMap<String, String>,
+}
+
+/// a file extension or file name claimed by more than one extension
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ExtConflict {
+    pub key: String,
+    pub winner: ExtEntry,
+    pub loser: ExtEntry,
 }
 
 impl Default for LangExtGen {
     fn default() -> Self {
-        let root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .unwrap()
-            .to_path_buf();
-        let ext_path = root_dir.join("extensions");
-
-        LangExtGen::from_path(ext_path)
+        LangExtGen::from_path(LangExtGen::builtin_dir())
     }
 }
 
@@ -34,10 +47,23 @@ impl LangExtGen {
     pub fn new() -> Self {
         LangExtGen {
             ext_map: Default::default(),
+            filename_map: Default::default(),
             grammar_map: Default::default(),
+            grammar_dirs: Default::default(),
+            config_paths: Default::default(),
+            aliases: Default::default(),
+            mimetypes: Default::default(),
         }
     }
 
+    /// the `extensions/` dir of the repository
+    pub fn builtin_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("extensions")
+    }
+
     pub fn to_json_file(&self, path: &str) {
         let json_str = serde_json::to_string_pretty(&self).unwrap();
         let bytes = json_str.as_bytes();
@@ -58,51 +84,126 @@ impl LangExtGen {
         };
     }
 
+    /// the paths are relative to `ext_path`, so the bundle doesn't keep the dir it is built in
     pub fn from_path(ext_path: PathBuf) -> LangExtGen {
         let package_files = ExtFile::walk_dir(ext_path.to_str().unwrap().to_string());
         let mut lang_ext_map = LangExtGen::new();
 
         for path in package_files {
-            let package = Finder::read_code(&path).unwrap();
-            let pkg: JsonPackage = match serde_json::from_str(&package) {
-                Ok(x) => x,
-                Err(err) => {
-                    println!("{:?}, {:?}", &path, err);
-                    panic!(err)
+            if let Err(err) = lang_ext_map.add_package(&path) {
+                println!("{:?}, {:?}", &path, err);
+                panic!("{}", err)
+            }
+        }
+
+        lang_ext_map.relative_to(&ext_path);
+        lang_ext_map
+    }
+
+    fn relative_to(&mut self, root: &Path) {
+        for entry in self
+            .ext_map
+            .values_mut()
+            .chain(self.filename_map.values_mut())
+        {
+            entry.path = relative_path(Path::new(&entry.path), root);
+        }
+        for path in self
+            .grammar_dirs
+            .values_mut()
+            .chain(self.config_paths.values_mut())
+        {
+            *path = relative_path(Path::new(path), root);
+        }
+    }
+
+    /// add languages and grammars of one extension by its `package.json`
+    pub fn add_package(&mut self, path: &PathBuf) -> Result<(), String> {
+        let package = Finder::read_code(path)?;
+        let pkg: JsonPackage = serde_json::from_str(&package).map_err(|err| err.to_string())?;
+
+        let mut ext_path = path.parent().unwrap().display().to_string();
+        ext_path = ext_path.replace(".//", "");
+
+        if let Some(grammars) = pkg.contributes.grammars {
+            for grammar in grammars {
+                if let Some(lang) = grammar.language.clone() {
+                    self.grammar_map.insert(lang.clone(), grammar);
+                    self.grammar_dirs.insert(lang, ext_path.clone());
                 }
+            }
+        }
+
+        if pkg.contributes.languages.is_none() {
+            return Ok(());
+        }
+
+        for lang_ext in pkg.contributes.languages.unwrap() {
+            let ext_entry = ExtEntry {
+                name: lang_ext.id.clone(),
+                path: ext_path.clone(),
             };
 
-            if let Some(grammars) = pkg.contributes.grammars {
-                for grammar in grammars {
-                    if let Some(lang) = grammar.language.clone() {
-                        lang_ext_map.grammar_map.insert(lang, grammar);
-                    }
-                }
+            if let Some(config) = lang_ext.configuration {
+                let config_path = path.parent().unwrap().join(config);
+                self.config_paths
+                    .insert(lang_ext.id.clone(), config_path.display().to_string());
+            }
+
+            for alias in lang_ext.aliases.unwrap_or_default() {
+                self.aliases
+                    .insert(alias.to_lowercase(), lang_ext.id.clone());
+            }
+
+            for mimetype in lang_ext.mimetypes.unwrap_or_default() {
+                self.mimetypes.insert(mimetype, lang_ext.id.clone());
             }
 
-            if pkg.contributes.languages.is_none() {
-                continue;
+            if let Some(filenames) = lang_ext.filenames {
+                for filename in filenames {
+                    self.filename_map.insert(filename, ext_entry.clone());
+                }
             }
 
-            for lang_ext in pkg.contributes.languages.unwrap() {
-                if lang_ext.extensions.is_none() {
-                    continue;
+            if let Some(extensions) = lang_ext.extensions {
+                for ext in extensions {
+                    self.ext_map.insert(ext, ext_entry.clone());
                 }
+            }
+        }
 
-                for ext in lang_ext.extensions.unwrap() {
-                    let mut path = path.parent().unwrap().display().to_string();
-                    path = path.replace(".//", "");
+        Ok(())
+    }
 
-                    let ext_entry = ExtEntry {
-                        name: lang_ext.id.clone(),
-                        path,
-                    };
-                    lang_ext_map.ext_map.insert(ext, ext_entry);
+    /// merge `other` into self, `other` wins, and return the entries it overrides
+    pub fn merge(&mut self, other: LangExtGen) -> Vec<ExtConflict> {
+        let mut conflicts = vec![];
+        let maps = vec![
+            (&mut self.ext_map, other.ext_map),
+            (&mut self.filename_map, other.filename_map),
+        ];
+        for (map, other_map) in maps {
+            for (key, entry) in other_map {
+                if let Some(loser) = map.insert(key.clone(), entry.clone()) {
+                    if loser != entry {
+                        conflicts.push(ExtConflict {
+                            key,
+                            winner: entry,
+                            loser,
+                        });
+                    }
                 }
             }
         }
 
-        lang_ext_map
+        self.grammar_map.extend(other.grammar_map);
+        self.grammar_dirs.extend(other.grammar_dirs);
+        self.config_paths.extend(other.config_paths);
+        self.aliases.extend(other.aliases);
+        self.mimetypes.extend(other.mimetypes);
+
+        conflicts.sort_by(|a, b| a.key.cmp(&b.key));
+        conflicts
     }
 }
 
@@ -122,7 +223,11 @@ mod tests {
 
         let languages_map = LangExtGen::from_path(ext_path);
         assert_eq!("css", languages_map.ext_map[".css"].name);
-        assert!(languages_map.ext_map[".css"].path.ends_with("css"));
+        assert_eq!("css", languages_map.ext_map[".css"].path);
+        assert_eq!(
+            "css/language-configuration.json",
+            languages_map.config_paths["css"]
+        );
 
         assert_eq!("source.css", languages_map.grammar_map["css"].scope_name);
         assert_eq!(
@@ -151,6 +256,12 @@ mod tests {
         assert!(path.exists())
     }
 
+    #[test]
+    fn should_build_filename_map() {
+        let languages_map = LangExtGen::default();
+        assert_eq!("makefile", languages_map.filename_map["Makefile"].name);
+    }
+
     #[test]
     fn should_build_bin_data_for_hashmap() {
         let mut map: HashMap<String, ExtEntry> = Default::default();
@@ -8,5 +8,9 @@ extern crate bincode;
 
 pub mod bin_gen;
 pub mod ext_file;
+pub mod ext_loader;
 pub mod grammar_gen;
+pub mod lang_bundle;
+pub mod lang_check;
+pub mod lang_registry;
 pub mod language_gen;
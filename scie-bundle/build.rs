@@ -0,0 +1,20 @@
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+use scie_bingen::lang_bundle::LangBundle;
+
+fn main() {
+    let ext_path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap())
+        .parent()
+        .unwrap()
+        .join("extensions");
+    println!("cargo:rerun-if-changed={}", ext_path.display());
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let bundle = LangBundle::from_path(ext_path);
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("lang_bundle.bin");
+    let mut file = File::create(out_path).unwrap();
+    file.write_all(&bundle.to_bytes()).unwrap();
+}
@@ -0,0 +1,89 @@
+#[macro_use]
+extern crate lazy_static;
+
+use scie_bingen::ext_loader::ExtLoader;
+use scie_bingen::lang_bundle::LangBundle;
+use scie_bingen::lang_registry::LanguageRegistry;
+use scie_bingen::language_gen::LangExtGen;
+
+/// made by `build.rs` from `extensions/`
+pub static LANG_BUNDLE_BYTES: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/lang_bundle.bin"));
+
+lazy_static! {
+    static ref LANG_BUNDLE: LangBundle = LangBundle::from_bytes(LANG_BUNDLE_BYTES).unwrap();
+}
+
+/// the embedded bundle, decoded once at the first call
+pub fn lang_bundle() -> &'static LangBundle {
+    &LANG_BUNDLE
+}
+
+/// lookups by id, alias, MIME type and scope name on the embedded bundle
+pub fn language_registry() -> LanguageRegistry<'static> {
+    LanguageRegistry::new(lang_bundle())
+}
+
+/// a loader onto the languages of the embedded bundle, the sources which are added win them
+pub fn ext_loader() -> ExtLoader {
+    let mut loader = ExtLoader::with_builtin(lang_bundle().langs.clone());
+    loader.builtin_dir = Some(LangExtGen::builtin_dir());
+    loader
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ext_loader, lang_bundle, language_registry};
+    use scie_bingen::lang_check::LangCheck;
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_load_embedded_bundle() {
+        let bundle = lang_bundle();
+        assert_eq!("java", bundle.ext_map[".java"].lang);
+        assert!(bundle.grammars.contains_key("rust"));
+        assert!(bundle.filename_map.contains_key("Makefile"));
+    }
+
+    #[test]
+    fn should_lookup_embedded_language_by_alias() {
+        let info = language_registry().by_alias("golang").unwrap();
+        assert_eq!("go", info.id);
+        assert_eq!(Some(String::from("source.go")), info.scope_name);
+    }
+
+    #[test]
+    fn should_load_user_dir_onto_embedded_bundle() {
+        let mut loader = ext_loader();
+        loader.add_dir(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .parent()
+                .unwrap()
+                .join("fixtures")
+                .join("extensions")
+                .join("user"),
+        );
+
+        let (langs, report) = loader.load();
+        assert!(report.errors.is_empty());
+        assert!(langs.ext_map[".yml"]
+            .path
+            .ends_with("acme.vscode-yaml-1.0.0"));
+        assert_eq!("java", langs.ext_map[".java"].name);
+    }
+
+    #[test]
+    fn should_check_builtin_packages_without_sources() {
+        let report = LangCheck::check(&ext_loader());
+        assert!(!report.is_ok());
+        assert!(report
+            .langs_without_grammar
+            .contains(&String::from("jsx-tags")));
+        assert!(report
+            .unresolved_embedded
+            .iter()
+            .any(|issue| issue.scope_name == "text.html.basic" && issue.target == "smarty"));
+        assert!(report.unresolved_scopes.iter().any(
+            |issue| issue.scope_name == "source.groovy" && issue.target == "text.html.javadoc"
+        ));
+    }
+}
@@ -1,8 +1,14 @@
 use crate::validate::Validate;
 use clap::Clap;
 use scie_bingen::bin_gen::BinGen;
-use scie_core::analyser::Analyser;
-use std::path::Path;
+use scie_bingen::lang_check::LangCheck;
+use scie_bundle::{ext_loader, lang_bundle};
+use scie_core::analyser::{Analyser, GrammarPool};
+use scie_core::graph::{DependencyGraph, GraphLevel};
+use scie_core::stats::{MixedLinePolicy, StatsFormat, StatsReport};
+use scie_detector::dependency::dependency_rule::DependencyRules;
+use scie_model::artifact::CodeFile;
+use std::path::{Path, PathBuf};
 
 pub mod validate;
 
@@ -17,6 +23,30 @@ struct Opts {
     debug: bool,
     #[clap(short, long, default_value = ".")]
     path: String,
+    /// check languages and grammars of the extensions, instead of analysing the path
+    #[clap(long)]
+    lang_report: bool,
+    /// more extension dirs or `.vsix` files for analysing and checking, later ones win
+    #[clap(long)]
+    extensions: Vec<String>,
+    /// print code, comment and blank lines by language, instead of writing tokens
+    #[clap(long)]
+    stats: bool,
+    /// print the dependency graph by `file` or `package`, instead of writing tokens
+    #[clap(long)]
+    deps: Option<String>,
+    /// check imports by the layering rules of a JSON file, instead of writing tokens
+    #[clap(long)]
+    rules: Option<String>,
+    /// output of `--stats`: table, json or csv, of `--deps`: dot or json
+    #[clap(long, default_value = "table")]
+    format: String,
+    /// how `--stats` counts lines with code and comment: code, comment or both
+    #[clap(long, default_value = "code")]
+    mixed: String,
+    /// analyse the test files too, which are left out by default
+    #[clap(long)]
+    include_test: bool,
     #[clap(short, long, parse(from_occurrences))]
     _verbose: i32,
 }
@@ -24,21 +54,107 @@ struct Opts {
 fn main() {
     let opts: Opts = Opts::parse();
 
-    print!("\x1B[2J\x1B[1;1H"); //clear screen and move to first line
-                                // println!("Value for config: {}", opts.config);
-    println!("Using input file: {}", opts.path);
+    let mut loader = ext_loader();
+    for path in opts.extensions.iter() {
+        loader.add_path(PathBuf::from(path));
+    }
+
+    if opts.lang_report {
+        let report = LangCheck::check(&loader);
+        print!("{}", report.render());
+        if !report.is_ok() {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // keep the output of `--stats`, `--deps` and `--rules` clean for pipes
+    let is_report = opts.stats || opts.deps.is_some() || opts.rules.is_some();
+    if !is_report {
+        print!("\x1B[2J\x1B[1;1H"); //clear screen and move to first line
+                                    // println!("Value for config: {}", opts.config);
+        println!("Using input file: {}", opts.path);
+    }
 
     if !Validate::is_valid_path(opts.path.clone()) {
         println!("is invalid path: {:?}", opts.path.clone());
         return;
     }
 
+    let mut grammar_pool = GrammarPool::default();
+    if !opts.extensions.is_empty() {
+        let (langs, report) = loader.load();
+        let (pool, errors) = GrammarPool::with_langs(lang_bundle(), langs);
+        for err in report.errors.iter().chain(errors.iter()) {
+            eprintln!("error: {}", err);
+        }
+        grammar_pool = pool;
+    }
+
     let path = Path::new(&opts.path);
-    let files = Analyser::ident_by_dir(&path.to_path_buf(), opts.debug, true);
+    let workspace = Analyser::workspace_with_pool(
+        &path.to_path_buf(),
+        &mut grammar_pool,
+        opts.include_test,
+        opts.debug,
+        !is_report,
+    );
+    if opts.stats {
+        let format = match opts.format.as_str() {
+            "json" => StatsFormat::Json,
+            "csv" => StatsFormat::Csv,
+            _ => StatsFormat::Table,
+        };
+        let policy = match opts.mixed.as_str() {
+            "comment" => MixedLinePolicy::Comment,
+            "both" => MixedLinePolicy::Both,
+            _ => MixedLinePolicy::Code,
+        };
+
+        let files: Vec<CodeFile> = workspace.code_files().into_iter().cloned().collect();
+        print!("{}", StatsReport::from_files(&files, policy).render(format));
+        return;
+    }
+
+    if let Some(rules) = opts.rules.as_ref() {
+        let rules = match DependencyRules::from_path(Path::new(rules)) {
+            Ok(rules) => rules,
+            Err(err) => {
+                println!("invalid rules: {}", err);
+                std::process::exit(2);
+            }
+        };
+
+        let mut violations = vec![];
+        for project in DependencyGraph::project_dependencies(&workspace).iter() {
+            violations.extend(rules.check(project));
+        }
+        for violation in violations.iter() {
+            println!("{}", violation.render());
+        }
+        if !violations.is_empty() {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(deps) = opts.deps.as_ref() {
+        let level = match deps.as_str() {
+            "package" => GraphLevel::Package,
+            _ => GraphLevel::File,
+        };
+        let graph = DependencyGraph::from_workspace(&workspace, level);
+        match opts.format.as_str() {
+            "json" => println!("{}", graph.to_json()),
+            _ => print!("{}", graph.to_dot()),
+        }
+        return;
+    }
+
     if opts.json {
-        BinGen::jsonify(files.clone(), "scie.json");
+        BinGen::jsonify_workspace(&workspace, "scie.json");
     } else {
-        BinGen::code_files(files, "scie.bin");
+        BinGen::workspace(&workspace, "scie.bin");
     }
 }
 
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde_json::Value;
+
+fn fixture(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .join("fixtures")
+        .join("projects")
+        .join(path)
+}
+
+#[test]
+fn should_print_deps_as_json_only() {
+    // the C grammar includes the external `source.asm`, which is logged while compiling
+    let output = Command::new(env!("CARGO_BIN_EXE_scie-cli"))
+        .args(["--deps", "file", "--format", "json", "-p"])
+        .arg(fixture("c/hello"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let graph: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let edges: Vec<(&str, &str)> = graph["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|edge| (edge["from"].as_str().unwrap(), edge["to"].as_str().unwrap()))
+        .collect();
+    assert_eq!(vec![("main.c", "list.h"), ("main.c", "stdio.h")], edges);
+}
@@ -1,11 +1,15 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use scie_bingen::grammar_gen::GrammarGen;
+use crate::analyser::{
+    GrammarPool, ImportExtractor, SymbolExtractor, TokenCategorizer, WorkspaceAssembler,
+};
+use crate::build::GradleParser;
 use scie_detector::framework_detector::FrameworkDetector;
+use scie_detector::test_classifier::TestClassifier;
 use scie_grammar::grammar::{Grammar, StackElement};
 use scie_infra::finder::Finder;
-use scie_model::artifact::{CodeFile, TokenElement};
+use scie_infra::paths::relative_path;
+use scie_model::artifact::{CodeFile, CodeImport, SourceKind, TokenElement, Workspace};
 
 pub struct Analyser {}
 
@@ -22,68 +26,132 @@ impl Analyser {
         let mut detector = FrameworkDetector::new();
         detector.run(lang.display().to_string());
 
-        let map = GrammarGen::build_output();
+        let mut grammar_pool = GrammarPool::default();
 
-        let mut grammar_map = HashMap::new();
-        if detector.tags.contains_key("workspace.java.gradle") {
-            let groovy_grammar = Grammar::new(map.grammar_map[".groovy"].clone());
-            grammar_map.insert(".gradle", groovy_grammar.clone());
-            grammar_map.insert(".groovy", groovy_grammar);
-
-            let java_grammar = Grammar::new(map.grammar_map[".java"].clone());
-            grammar_map.insert(".java", java_grammar);
+        let files = detector.analysed_files(lang, Finder::walk_filter_files(&lang));
+        if is_debug {
+            if !detector.tags.is_empty() {
+                println!("{:?}", detector.tags);
+            }
         }
 
-        if detector.tags.contains_key("workspace.rust.cargo") {
-            let rust_grammar = Grammar::new(map.grammar_map[".rs"].clone());
-            grammar_map.insert(".rs", rust_grammar);
-        }
+        let code_files = Analyser::process_files(&mut grammar_pool, files, is_debug, is_cli);
+        Analyser::classify_files(&detector, code_files)
+    }
 
-        if detector.tags.contains_key("workspace.go") {
-            let c_grammar = Grammar::new(map.grammar_map[".go"].clone());
-            grammar_map.insert(".go", c_grammar);
-        }
+    /// same as `ident_by_dir`, but group files by module and package
+    pub fn workspace_by_dir(dir: &PathBuf, is_debug: bool, is_cli: bool) -> Workspace {
+        Analyser::workspace_with_tests(dir, false, is_debug, is_cli)
+    }
 
-        if detector.tags.contains_key("workspace.c") {
-            let c_grammar = Grammar::new(map.grammar_map[".c"].clone());
-            grammar_map.insert(".c", c_grammar);
-        }
+    /// same as `workspace_by_dir`, the test files are kept if `include_test`
+    pub fn workspace_with_tests(
+        dir: &PathBuf,
+        include_test: bool,
+        is_debug: bool,
+        is_cli: bool,
+    ) -> Workspace {
+        let mut grammar_pool = GrammarPool::default();
+        Analyser::workspace_with_pool(dir, &mut grammar_pool, include_test, is_debug, is_cli)
+    }
 
-        let files = Finder::walk_filter_files(&lang);
+    /// same as `workspace_with_tests`, by the grammars of `grammar_pool`, such as the one of
+    /// `GrammarPool::with_langs` for user extensions
+    pub fn workspace_with_pool(
+        dir: &PathBuf,
+        grammar_pool: &mut GrammarPool,
+        include_test: bool,
+        is_debug: bool,
+        is_cli: bool,
+    ) -> Workspace {
+        let mut detector = FrameworkDetector::new();
+        detector.include_test = include_test;
+        detector.run(dir.display().to_string());
         if is_debug {
-            if !detector.tags.is_empty() {
-                println!("{:?}", detector.tags);
+            println!("{:?}", detector.tags);
+        }
+
+        let paths = detector.analysed_files(dir, Finder::walk_filter_files(dir));
+        let assembler = WorkspaceAssembler::from_detector(dir, &detector);
+
+        let mut files = Analyser::process_files(grammar_pool, paths, is_debug, is_cli);
+        Analyser::add_gradle_imports(dir, &mut files);
+        assembler.assemble(Analyser::classify_files(&detector, files))
+    }
+
+    /// the `project(':x')` references of `build.gradle` scripts are their imports, so that
+    /// `DependencyGraph` links the modules
+    fn add_gradle_imports(dir: &Path, files: &mut [CodeFile]) {
+        let scripts = GradleParser::parse_project(dir, &files.iter().collect::<Vec<&CodeFile>>());
+        for script in scripts.into_iter() {
+            let file = files
+                .iter_mut()
+                .find(|file| relative_path(Path::new(&file.path), dir) == script.path);
+            if let Some(file) = file {
+                file.imports = script
+                    .module
+                    .imports
+                    .into_iter()
+                    .map(|import| CodeImport::new(import.source, import.line_num))
+                    .collect();
             }
         }
+    }
 
-        Analyser::process_files(&mut grammar_map, files, is_debug, is_cli)
+    /// main, test, resource or generated, by the content roots of the detected modules, and
+    /// the tests of all languages are left out unless `FrameworkDetector.include_test`
+    fn classify_files(detector: &FrameworkDetector, files: Vec<CodeFile>) -> Vec<CodeFile> {
+        let mut classified = vec![];
+        for mut file in files.into_iter() {
+            let path = PathBuf::from(&file.path);
+            file.kind = detector.source_kind(&path);
+            file.is_test = TestClassifier::is_test(
+                &path,
+                file.kind,
+                detector.is_in_source_dirs(&path),
+                &file.elements,
+            );
+            if file.is_test {
+                if !detector.include_test {
+                    continue;
+                }
+                file.kind = SourceKind::Test;
+            }
+
+            classified.push(file);
+        }
+        classified
     }
 
     fn process_files(
-        grammar_map: &mut HashMap<&str, Grammar>,
+        grammar_pool: &mut GrammarPool,
         files: Vec<PathBuf>,
         _is_debug: bool,
         is_cli: bool,
     ) -> Vec<CodeFile> {
+        let categorizer = TokenCategorizer::default();
         let mut parsed_files = vec![];
         for path in files {
-            if path.extension().is_none() {
+            if !path.is_file() {
                 continue;
             }
 
+            let language = match grammar_pool.config_by_path(&path) {
+                None => continue,
+                Some(config) => config.lang.clone(),
+            };
+            let grammar = match grammar_pool.grammar_by_path(&path) {
+                None => continue,
+                Some(grammar) => grammar,
+            };
+
             if is_cli {
                 // todo: add clear current line & set value http://rosettacode.org/wiki/Terminal_control
                 println!("analyses: {:?}", path);
             }
 
-            let lang = Analyser::get_lang_by_path(path.clone());
-            let lang_grammar = grammar_map.get_mut(lang.as_str());
-            if lang_grammar.is_none() {
-                continue;
-            }
-
-            let grammar = lang_grammar.unwrap();
             let mut code_file = CodeFile::new(path.clone());
+            code_file.language = language;
             let code;
             match Finder::read_code(&path) {
                 Ok(str) => {
@@ -93,37 +161,41 @@ impl Analyser {
                     continue;
                 }
             }
-            let mut rule_stack = Some(StackElement::null());
-
-            let mut line_num = 1;
-            for line in code.lines() {
-                let result = grammar.tokenize_line(line, &mut rule_stack);
-                for token in result.tokens {
-                    let start = token.start_index;
-                    let end = token.end_index;
-                    let text: String = String::from(line)
-                        .chars()
-                        .skip(start as usize)
-                        .take((end - start) as usize)
-                        .collect();
-
-                    code_file.elements.push(TokenElement::new(
-                        line_num,
-                        start,
-                        end,
-                        text,
-                        token.scopes,
-                    ));
-                }
-                rule_stack = result.rule_stack;
-                line_num = line_num + 1;
-            }
-
+            code_file.elements = Analyser::tokenize_code(grammar, &code);
+            categorizer.categorize(&mut code_file.elements);
+            code_file.symbols = SymbolExtractor::extract(&code_file.elements);
+            code_file.imports = ImportExtractor::extract(&code_file.elements);
             parsed_files.push(code_file);
         }
 
         parsed_files
     }
+
+    /// the tokens of every line, numbered from 1
+    pub fn tokenize_code(grammar: &mut Grammar, code: &str) -> Vec<TokenElement> {
+        let mut elements = vec![];
+        let mut rule_stack = Some(StackElement::null());
+
+        let mut line_num = 1;
+        for line in code.lines() {
+            let result = grammar.tokenize_line(line, &mut rule_stack);
+            for token in result.tokens {
+                let start = token.start_index;
+                let end = token.end_index;
+                let text: String = String::from(line)
+                    .chars()
+                    .skip(start as usize)
+                    .take((end - start) as usize)
+                    .collect();
+
+                elements.push(TokenElement::new(line_num, start, end, text, token.scopes));
+            }
+            rule_stack = result.rule_stack;
+            line_num = line_num + 1;
+        }
+
+        elements
+    }
 }
 
 #[cfg(test)]
@@ -131,6 +203,8 @@ mod tests {
     use std::path::PathBuf;
 
     use crate::analyser::Analyser;
+    use crate::graph::{DependencyGraph, GraphLevel};
+    use scie_model::artifact::{CodeImport, SourceKind};
 
     #[test]
     fn should_identify_path() {
@@ -155,4 +229,170 @@ mod tests {
 
         let _files = Analyser::ident_by_dir(&lang, false, false);
     }
+
+    #[test]
+    fn should_build_workspace_of_gradle_modules() {
+        let root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+        let dir = root_dir
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("java")
+            .join("simple");
+
+        let workspace = Analyser::workspace_by_dir(&dir, false, false);
+        let modules: Vec<String> = workspace.projects[0]
+            .modules
+            .iter()
+            .map(|module| module.name.clone())
+            .collect();
+        assert_eq!(vec!["simple", "app"], modules);
+        assert_eq!(3, workspace.code_files().len());
+    }
+
+    #[test]
+    fn should_tag_test_and_main_files() {
+        let root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+        let dir = root_dir
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("polyglot")
+            .join("backend");
+
+        let workspace = Analyser::workspace_with_tests(&dir, true, false, false);
+        let kinds: Vec<(&str, SourceKind, bool)> = workspace
+            .code_files()
+            .iter()
+            .map(|file| (file.name.as_str(), file.kind, file.is_test))
+            .collect();
+        assert!(kinds.contains(&("App.java", SourceKind::Main, false)));
+        assert!(kinds.contains(&("AppTest.java", SourceKind::Test, true)));
+        assert!(kinds.contains(&("TestDataFactory.java", SourceKind::Main, false)));
+        assert!(workspace
+            .code_files()
+            .iter()
+            .filter(|file| file.name.ends_with(".java"))
+            .all(|file| file.language == "java"));
+    }
+
+    #[test]
+    fn should_leave_out_tests_unless_included() {
+        let root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+        let dir = root_dir
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("polyglot");
+
+        let files = Analyser::ident_by_dir(&dir, false, false);
+        assert!(files.iter().any(|file| file.name == "App.java"));
+        assert!(files.iter().any(|file| file.name == "index.ts"));
+        assert!(files.iter().all(|file| !file.is_test));
+
+        let workspace = Analyser::workspace_with_tests(&dir, true, false, false);
+        let tests: Vec<&str> = workspace
+            .code_files()
+            .iter()
+            .filter(|file| file.is_test)
+            .map(|file| file.name.as_str())
+            .collect();
+        assert!(tests.contains(&"AppTest.java"));
+        assert!(tests.contains(&"index.test.ts"));
+        assert!(tests.contains(&"cli.rs"));
+    }
+
+    #[test]
+    fn should_not_analyse_dependencies_of_build_roots() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("polyglot");
+
+        let workspace = Analyser::workspace_by_dir(&dir, false, false);
+        let modules: Vec<&str> = workspace
+            .projects
+            .iter()
+            .flat_map(|project| project.modules.iter())
+            .map(|module| module.name.as_str())
+            .collect();
+        assert!(!modules.is_empty());
+        assert!(modules.iter().all(|name| !name.contains("node_modules")));
+        assert!(workspace
+            .code_files()
+            .iter()
+            .all(|file| !file.path.contains("node_modules")));
+    }
+
+    #[test]
+    fn should_link_rust_modules_by_use_trees() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("rust")
+            .join("shop");
+
+        let workspace = Analyser::workspace_by_dir(&dir, false, false);
+        let graph = DependencyGraph::from_workspace(&workspace, GraphLevel::File);
+        let edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("src/cart/mod.rs", "src/model.rs"),
+                ("src/main.rs", "src/cart/mod.rs"),
+                ("src/main.rs", "src/model.rs"),
+                ("src/main.rs", "std")
+            ],
+            edges
+        );
+    }
+
+    #[test]
+    fn should_link_gradle_modules_by_project_references() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("java")
+            .join("modules");
+
+        let workspace = Analyser::workspace_by_dir(&dir, false, false);
+        let script = workspace
+            .code_files()
+            .into_iter()
+            .find(|file| file.path.ends_with("app/build.gradle"))
+            .unwrap();
+        assert_eq!(
+            vec![CodeImport::new(String::from(":core"), 6)],
+            script.imports
+        );
+
+        let graph = DependencyGraph::from_workspace(&workspace, GraphLevel::File);
+        let edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("app/build.gradle", "core/build.gradle"),
+                (
+                    "app/src/main/java/com/shop/App.java",
+                    "core/src/main/java/com/shop/core/Cart.java"
+                ),
+            ],
+            edges
+        );
+    }
 }
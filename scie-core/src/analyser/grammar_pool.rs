@@ -0,0 +1,161 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+
+use scie_bingen::grammar_gen::LangConfig;
+use scie_bingen::lang_bundle::LangBundle;
+use scie_bingen::language_gen::LangExtGen;
+use scie_bundle::lang_bundle;
+use scie_grammar::grammar::Grammar;
+
+/// extensions which should use the grammar of another extension, than the one in `extensions/`
+pub const EXT_OVERRIDES: [(&str, &str); 1] = [(".gradle", ".groovy")];
+
+/// Select grammar by file name or extension from the language bundle, a grammar is only
+/// compiled when the first file of its language comes.
+pub struct GrammarPool<'a> {
+    /// owned when the languages of a loader are merged onto it
+    pub bundle: Cow<'a, LangBundle>,
+    pub overrides: HashMap<String, String>,
+    grammars: HashMap<String, Grammar>,
+}
+
+impl Default for GrammarPool<'static> {
+    /// use the bundle embedded in the binary
+    fn default() -> Self {
+        GrammarPool::new(lang_bundle())
+    }
+}
+
+impl<'a> GrammarPool<'a> {
+    pub fn new(bundle: &'a LangBundle) -> Self {
+        GrammarPool::from_bundle(Cow::Borrowed(bundle))
+    }
+
+    /// the languages of `ExtLoader::load` onto `bundle`, so the grammars of user extensions
+    /// are used, with the errors of the ones which can't be read
+    pub fn with_langs(
+        bundle: &LangBundle,
+        langs: LangExtGen,
+    ) -> (GrammarPool<'static>, Vec<String>) {
+        let mut bundle = bundle.clone();
+        let errors = bundle.merge_langs(langs);
+        (GrammarPool::from_bundle(Cow::Owned(bundle)), errors)
+    }
+
+    fn from_bundle(bundle: Cow<'a, LangBundle>) -> Self {
+        let overrides = EXT_OVERRIDES
+            .iter()
+            .map(|(ext, target)| (ext.to_string(), target.to_string()))
+            .collect();
+
+        GrammarPool {
+            bundle,
+            overrides,
+            grammars: Default::default(),
+        }
+    }
+
+    pub fn add_override(&mut self, ext: &str, target_ext: &str) {
+        self.overrides
+            .insert(ext.to_string(), target_ext.to_string());
+    }
+
+    /// file name first, then the override table, then the extension
+    pub fn config_by_path(&self, path: &Path) -> Option<&LangConfig> {
+        let bundle = &self.bundle;
+        if let Some(file_name) = path.file_name().and_then(|name| name.to_str()) {
+            if let Some(config) = bundle.filename_map.get(file_name) {
+                return Some(config);
+            }
+        }
+
+        let ext = format!(".{}", path.extension()?.to_str()?);
+        let ext = match self.overrides.get(&ext) {
+            None => ext,
+            Some(target) => target.clone(),
+        };
+
+        bundle
+            .ext_map
+            .get(&ext)
+            .or_else(|| bundle.ext_map.get(&ext.to_lowercase()))
+    }
+
+    pub fn grammar_by_path(&mut self, path: &Path) -> Option<&mut Grammar> {
+        let lang = self.config_by_path(path)?.lang.clone();
+        if !self.grammars.contains_key(&lang) {
+            let raw_grammar = self.bundle.grammars.get(&lang)?.clone();
+            self.grammars
+                .insert(lang.clone(), Grammar::new(raw_grammar));
+        }
+
+        self.grammars.get_mut(&lang)
+    }
+
+    /// languages which grammar is compiled
+    pub fn loaded_langs(&self) -> Vec<String> {
+        let mut langs: Vec<String> = self.grammars.keys().cloned().collect();
+        langs.sort();
+        langs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::analyser::GrammarPool;
+    use scie_bundle::{ext_loader, lang_bundle};
+
+    #[test]
+    fn should_select_grammar_by_ext_and_filename() {
+        let pool = GrammarPool::default();
+
+        let python = pool.config_by_path(&PathBuf::from("src/main.py")).unwrap();
+        assert_eq!("python", python.lang);
+
+        let makefile = pool.config_by_path(&PathBuf::from("Makefile")).unwrap();
+        assert_eq!("makefile", makefile.lang);
+
+        let gradle = pool.config_by_path(&PathBuf::from("build.gradle")).unwrap();
+        assert_eq!("groovy", gradle.lang);
+
+        assert!(pool.config_by_path(&PathBuf::from("LICENSE")).is_none());
+    }
+
+    #[test]
+    fn should_load_grammar_lazily() {
+        let mut pool = GrammarPool::default();
+        assert!(pool.loaded_langs().is_empty());
+
+        assert!(pool
+            .grammar_by_path(&PathBuf::from("build.gradle"))
+            .is_some());
+        assert!(pool
+            .grammar_by_path(&PathBuf::from("Hello.groovy"))
+            .is_some());
+        assert_eq!(vec![String::from("groovy")], pool.loaded_langs());
+    }
+
+    #[test]
+    fn should_use_grammars_of_user_extensions() {
+        let mut loader = ext_loader();
+        loader.add_dir(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .parent()
+                .unwrap()
+                .join("fixtures")
+                .join("extensions")
+                .join("user"),
+        );
+        let (langs, _report) = loader.load();
+
+        let (mut pool, errors) = GrammarPool::with_langs(lang_bundle(), langs);
+        assert!(errors.is_empty());
+        let yaml = pool.config_by_path(&PathBuf::from("ci.yml")).unwrap();
+        assert!(yaml.path.contains("acme.vscode-yaml-1.0.0"));
+        assert!(pool.grammar_by_path(&PathBuf::from("ci.yml")).is_some());
+        assert!(pool.config_by_path(&PathBuf::from("Main.java")).is_some());
+    }
+}
@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use crate::analyser::TokenCategorizer;
 use scie_grammar::grammar::{Grammar, StackElement};
 use scie_model::artifact::TokenElement;
 
@@ -31,6 +32,7 @@ impl Identify {
             line_num = line_num + 1
         }
 
+        TokenCategorizer::default().categorize(&mut elements);
         elements
     }
 }
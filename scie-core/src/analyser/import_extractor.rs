@@ -0,0 +1,459 @@
+use std::collections::BTreeMap;
+
+use scie_model::artifact::{CodeImport, TokenCategory, TokenElement};
+
+/// Find the imports of a file by the scopes of its tokens, such as `keyword.other.import` of
+/// Java, `entity.name.import` of Go or `meta.preprocessor.include` of C, the root scope of the
+/// grammar decides how to read them.
+pub struct ImportExtractor {}
+
+impl ImportExtractor {
+    /// elements should be categorized by `TokenCategorizer`
+    pub fn extract(elements: &[TokenElement]) -> Vec<CodeImport> {
+        let root = match elements.first().and_then(|element| element.scopes.first()) {
+            Some(root) => root.as_str(),
+            None => return vec![],
+        };
+
+        match root {
+            "source.java" => ImportExtractor::java(elements),
+            "source.go" => ImportExtractor::go(elements),
+            "source.rust" => ImportExtractor::rust(elements),
+            "source.c" | "source.cpp" => ImportExtractor::c(elements),
+            "source.python" => ImportExtractor::python(elements),
+            "source.js" | "source.js.jsx" | "source.ts" | "source.tsx" => {
+                ImportExtractor::javascript(elements)
+            }
+            _ => vec![],
+        }
+    }
+
+    /// `import static a.b.C.d;`, the path is the content of `meta.import`
+    fn java(elements: &[TokenElement]) -> Vec<CodeImport> {
+        let mut imports = vec![];
+        let mut current: Option<CodeImport> = None;
+        for element in elements.iter() {
+            if ImportExtractor::has_scope(element, "keyword.other.import") {
+                ImportExtractor::push(&mut imports, current.take());
+                current = Some(CodeImport::new(String::new(), element.line_num));
+                continue;
+            }
+
+            if let Some(import) = current.as_mut() {
+                if element.value.contains(';')
+                    || !ImportExtractor::has_scope(element, "meta.import")
+                {
+                    ImportExtractor::push(&mut imports, current.take());
+                } else if ImportExtractor::has_scope(element, "storage.modifier.import")
+                    && element.category != TokenCategory::Comment
+                {
+                    import.source.push_str(element.value.trim());
+                }
+            }
+        }
+
+        ImportExtractor::push(&mut imports, current);
+        imports
+    }
+
+    /// `import "fmt"` or `import ( f "fmt" )`, every path has its own scope
+    fn go(elements: &[TokenElement]) -> Vec<CodeImport> {
+        elements
+            .iter()
+            .filter(|element| ImportExtractor::has_scope(element, "entity.name.import"))
+            .map(|element| CodeImport::new(element.value.clone(), element.line_num))
+            .collect()
+    }
+
+    /// `use std::collections::{HashMap, HashSet};`, as one import for each path of the tree,
+    /// the grammar leaves most of the path unscoped, so `HashMap;` is one token, and the alias
+    /// after `as` is dropped
+    fn rust(elements: &[TokenElement]) -> Vec<CodeImport> {
+        let mut imports = vec![];
+        let mut current: Option<CodeImport> = None;
+        let mut is_alias = false;
+        for element in elements.iter() {
+            if element.category == TokenCategory::Keyword && element.value == "use" {
+                ImportExtractor::push_use_tree(&mut imports, current.take());
+                current = Some(CodeImport::new(String::new(), element.line_num));
+                is_alias = false;
+                continue;
+            }
+
+            let import = match current.as_mut() {
+                Some(import) => import,
+                None => continue,
+            };
+            if element.category == TokenCategory::Comment {
+                continue;
+            }
+            if element.value.trim() == "as" {
+                is_alias = true;
+                continue;
+            }
+
+            let (text, is_end) = match element.value.find(';') {
+                Some(index) => (&element.value[..index], true),
+                None => (element.value.as_str(), false),
+            };
+            for char in text.chars() {
+                match char {
+                    // `{A as B, C}`, the alias ends with the item
+                    ',' | '}' => {
+                        is_alias = false;
+                        import.source.push(char);
+                    }
+                    _ if char.is_whitespace() || is_alias => {}
+                    _ => import.source.push(char),
+                }
+            }
+
+            if is_end {
+                ImportExtractor::push_use_tree(&mut imports, current.take());
+            }
+        }
+
+        ImportExtractor::push_use_tree(&mut imports, current);
+        imports
+    }
+
+    fn push_use_tree(imports: &mut Vec<CodeImport>, import: Option<CodeImport>) {
+        if let Some(import) = import {
+            for path in ImportExtractor::use_paths(&import.source) {
+                imports.push(CodeImport::new(path, import.line_num));
+            }
+        }
+    }
+
+    /// `a::{b, c::{d, self}}` to `a::b`, `a::c::d` and `a::c`
+    fn use_paths(tree: &str) -> Vec<String> {
+        let tree = tree.trim().trim_end_matches(',');
+        let (open, close) = match (tree.find('{'), tree.rfind('}')) {
+            (Some(open), Some(close)) if open < close => (open, close),
+            _ if tree.is_empty() => return vec![],
+            _ => return vec![tree.to_string()],
+        };
+
+        let prefix = tree[..open].trim_end_matches("::");
+        let mut items = vec![];
+        let mut depth = 0;
+        let mut start = open + 1;
+        for (offset, char) in tree[start..close].char_indices() {
+            let index = open + 1 + offset;
+            match char {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    items.push(&tree[start..index]);
+                    start = index + 1;
+                }
+                _ => {}
+            }
+        }
+        items.push(&tree[start..close]);
+
+        let mut paths = vec![];
+        for item in items {
+            for path in ImportExtractor::use_paths(item) {
+                match (path.as_str(), prefix.is_empty()) {
+                    ("self", true) => {}
+                    ("self", false) => paths.push(prefix.to_string()),
+                    (_, true) => paths.push(path),
+                    (_, false) => paths.push(format!("{}::{}", prefix, path)),
+                }
+            }
+        }
+        paths
+    }
+
+    /// `#include <stdio.h>` or `#include "a.h"`, macros are ignored
+    fn c(elements: &[TokenElement]) -> Vec<CodeImport> {
+        let mut lines: BTreeMap<i32, String> = Default::default();
+        for element in elements.iter() {
+            let is_path = ImportExtractor::has_scope(element, "meta.preprocessor.include")
+                && ImportExtractor::has_scope(element, "string")
+                && !ImportExtractor::has_scope(element, "punctuation.definition.string");
+            if is_path {
+                lines
+                    .entry(element.line_num)
+                    .or_default()
+                    .push_str(&element.value);
+            }
+        }
+
+        lines
+            .into_iter()
+            .filter(|(_, source)| !source.trim().is_empty())
+            .map(|(line_num, source)| CodeImport::new(source.trim().to_string(), line_num))
+            .collect()
+    }
+
+    /// `from a.b import c` is `a.b`, `import a.b as c, d` are `a.b` and `d`
+    fn python(elements: &[TokenElement]) -> Vec<CodeImport> {
+        let mut lines: BTreeMap<i32, Vec<&TokenElement>> = Default::default();
+        for element in elements.iter() {
+            lines.entry(element.line_num).or_default().push(element);
+        }
+
+        let mut imports = vec![];
+        for (line_num, tokens) in lines.into_iter() {
+            let start = tokens
+                .iter()
+                .position(|element| ImportExtractor::has_scope(element, "keyword.control.import"));
+            let start = match start {
+                Some(start) => start,
+                None => continue,
+            };
+
+            let is_from = tokens[start].value == "from";
+            let mut current = CodeImport::new(String::new(), line_num);
+            let mut is_alias = false;
+            for element in tokens[start + 1..].iter() {
+                if element.category == TokenCategory::Comment {
+                    break;
+                }
+
+                if ImportExtractor::has_scope(element, "keyword.control.import") {
+                    // `import` of `from a import b`, or `as` of an alias
+                    if is_from {
+                        break;
+                    }
+                    is_alias = true;
+                    continue;
+                }
+
+                for char in element.value.chars() {
+                    match char {
+                        ',' if !is_from => {
+                            let import = std::mem::replace(
+                                &mut current,
+                                CodeImport::new(String::new(), line_num),
+                            );
+                            ImportExtractor::push(&mut imports, Some(import));
+                            is_alias = false;
+                        }
+                        _ if char.is_whitespace() || is_alias => {}
+                        _ => current.source.push(char),
+                    }
+                }
+            }
+
+            ImportExtractor::push(&mut imports, Some(current));
+        }
+
+        imports
+    }
+
+    /// the string right after `import`, `from`, `require(` or `import(`, such as `'./a'` of
+    /// `import { b } from './a'`
+    fn javascript(elements: &[TokenElement]) -> Vec<CodeImport> {
+        let mut imports = vec![];
+        let mut is_pending = false;
+        let mut current: Option<CodeImport> = None;
+        for element in elements.iter() {
+            if ImportExtractor::is_javascript_import(element) {
+                is_pending = true;
+                continue;
+            }
+            if !is_pending {
+                continue;
+            }
+
+            if ImportExtractor::has_scope(element, "punctuation.definition.string") {
+                if current.is_some() {
+                    ImportExtractor::push(&mut imports, current.take());
+                    is_pending = false;
+                }
+                continue;
+            }
+
+            if ImportExtractor::has_scope(element, "string.quoted") {
+                current
+                    .get_or_insert_with(|| CodeImport::new(String::new(), element.line_num))
+                    .source
+                    .push_str(&element.value);
+                continue;
+            }
+
+            let value = element.value.trim();
+            if !value.is_empty() && value != "(" {
+                is_pending = false;
+                current = None;
+            }
+        }
+
+        imports
+    }
+
+    fn is_javascript_import(element: &TokenElement) -> bool {
+        let scopes = [
+            "keyword.control.import",
+            "keyword.control.from",
+            "keyword.control.require",
+            "keyword.operator.expression.import",
+        ];
+        scopes
+            .iter()
+            .any(|scope| ImportExtractor::has_scope(element, scope))
+            || (element.value == "require" && element.category == TokenCategory::FunctionCall)
+    }
+
+    fn push(imports: &mut Vec<CodeImport>, import: Option<CodeImport>) {
+        if let Some(import) = import {
+            if !import.source.is_empty() {
+                imports.push(import);
+            }
+        }
+    }
+
+    /// `prefix` matches the scope of a language, `meta.import` matches `meta.import.java`
+    fn has_scope(element: &TokenElement, prefix: &str) -> bool {
+        element.scopes.iter().any(|scope| {
+            scope == prefix || (scope.starts_with(prefix) && scope[prefix.len()..].starts_with('.'))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyser::test_helper::elements;
+    use crate::analyser::ImportExtractor;
+
+    fn sources(file_name: &str, code: &str) -> Vec<(String, i32)> {
+        ImportExtractor::extract(&elements(file_name, code))
+            .into_iter()
+            .map(|import| (import.source, import.line_num))
+            .collect()
+    }
+
+    #[test]
+    fn should_extract_java_imports() {
+        let code = "package com.phodal;
+
+import java.util.*;
+import static org.junit.Assert.assertEquals;
+
+class Hello {}
+";
+
+        assert_eq!(
+            vec![
+                (String::from("java.util.*"), 3),
+                (String::from("org.junit.Assert.assertEquals"), 4)
+            ],
+            sources("Hello.java", code)
+        );
+    }
+
+    #[test]
+    fn should_extract_go_and_c_imports() {
+        let go = "package main
+
+import (
+\t\"fmt\"
+\tlog \"github.com/sirupsen/logrus\"
+)
+";
+        assert_eq!(
+            vec![
+                (String::from("fmt"), 4),
+                (String::from("github.com/sirupsen/logrus"), 5)
+            ],
+            sources("main.go", go)
+        );
+
+        let c = "#include <stdio.h>
+#include \"lib/list.h\"
+
+int main() {}
+";
+        assert_eq!(
+            vec![
+                (String::from("stdio.h"), 1),
+                (String::from("lib/list.h"), 2)
+            ],
+            sources("main.c", c)
+        );
+    }
+
+    #[test]
+    fn should_extract_rust_uses() {
+        let code = "use std::collections::HashMap;
+use std::collections::{
+    HashSet, BTreeMap,
+};
+use crate::analyser::Analyser as A;
+use super::{Grammar as G, Rule};
+use super::*; // all
+
+fn main() {}
+";
+
+        assert_eq!(
+            vec![
+                (String::from("std::collections::HashMap"), 1),
+                (String::from("std::collections::HashSet"), 2),
+                (String::from("std::collections::BTreeMap"), 2),
+                (String::from("crate::analyser::Analyser"), 5),
+                (String::from("super::Grammar"), 6),
+                (String::from("super::Rule"), 6),
+                (String::from("super::*"), 7)
+            ],
+            sources("main.rs", code)
+        );
+    }
+
+    #[test]
+    fn should_expand_nested_rust_use_trees() {
+        let code = "use crate::{
+    analyser,
+    graph::{self, resolver::{Import, Target}},
+    model::Cart as C,
+};
+use {serde::Serialize, self::stats};
+";
+
+        assert_eq!(
+            vec![
+                (String::from("crate::analyser"), 1),
+                (String::from("crate::graph"), 1),
+                (String::from("crate::graph::resolver::Import"), 1),
+                (String::from("crate::graph::resolver::Target"), 1),
+                (String::from("crate::model::Cart"), 1),
+                (String::from("serde::Serialize"), 6),
+                (String::from("self::stats"), 6)
+            ],
+            sources("main.rs", code)
+        );
+    }
+
+    #[test]
+    fn should_extract_python_imports() {
+        let code = "from ..models import User
+import os.path as p, sys # system
+";
+
+        assert_eq!(
+            vec![
+                (String::from("..models"), 1),
+                (String::from("os.path"), 2),
+                (String::from("sys"), 2)
+            ],
+            sources("views.py", code)
+        );
+    }
+
+    #[test]
+    fn should_extract_javascript_imports_and_requires() {
+        let code = "import { a } from './a'
+const fs = require('fs')
+import name
+log('hi')
+";
+
+        assert_eq!(
+            vec![(String::from("./a"), 1), (String::from("fs"), 2)],
+            sources("index.js", code)
+        );
+    }
+}
@@ -1,5 +1,17 @@
 pub mod analyser;
+pub mod grammar_pool;
 pub mod identify;
+pub mod import_extractor;
+pub mod symbol_extractor;
+#[cfg(test)]
+pub mod test_helper;
+pub mod token_categorizer;
+pub mod workspace_assembler;
 
 pub use analyser::Analyser;
+pub use grammar_pool::GrammarPool;
 pub use identify::Identify;
+pub use import_extractor::ImportExtractor;
+pub use symbol_extractor::SymbolExtractor;
+pub use token_categorizer::TokenCategorizer;
+pub use workspace_assembler::WorkspaceAssembler;
@@ -0,0 +1,477 @@
+use scie_model::artifact::{CodeSymbol, SymbolKind, TokenCategory, TokenElement};
+
+/// grammars which the extractor knows the declarations of
+pub const SYMBOL_LANGUAGES: [&str; 4] = ["source.java", "source.go", "source.rust", "source.c"];
+
+/// a declaration whose body is not started yet
+struct Pending {
+    symbol: CodeSymbol,
+    /// the name comes from an `entity.name.*` scope, instead of a plain identifier
+    from_entity: bool,
+    /// the name is every token until the body, such as `Display for Foo` of an `impl`
+    collecting: bool,
+}
+
+struct OpenSymbol {
+    symbol: CodeSymbol,
+    /// bracket depth inside the body
+    body_depth: usize,
+}
+
+/// Build the outline of a file from categorized tokens: a declaration keyword or an
+/// `entity.name.*` scope starts a symbol, the next `{` opens its body and the matching `}`
+/// closes it, a `;` before the body makes it a declaration without body.
+pub struct SymbolExtractor {
+    depth: usize,
+    line_num: i32,
+    roots: Vec<CodeSymbol>,
+    stack: Vec<OpenSymbol>,
+    keyword: Option<(SymbolKind, i32)>,
+    pending: Option<Pending>,
+    /// Go, `func (r *Receiver) Name()` is a method
+    after_func: bool,
+    has_receiver: bool,
+}
+
+impl SymbolExtractor {
+    fn new() -> Self {
+        SymbolExtractor {
+            depth: 0,
+            line_num: 0,
+            roots: vec![],
+            stack: vec![],
+            keyword: None,
+            pending: None,
+            after_func: false,
+            has_receiver: false,
+        }
+    }
+
+    /// elements should be categorized by `TokenCategorizer`
+    pub fn extract(elements: &[TokenElement]) -> Vec<CodeSymbol> {
+        let is_supported = elements
+            .first()
+            .and_then(|element| element.scopes.first())
+            .is_some_and(|root| SYMBOL_LANGUAGES.contains(&root.as_str()));
+        if !is_supported {
+            return vec![];
+        }
+
+        let mut extractor = SymbolExtractor::new();
+        for element in elements.iter() {
+            extractor.process(element);
+        }
+        extractor.finish()
+    }
+
+    fn process(&mut self, element: &TokenElement) {
+        if element.line_num != self.line_num {
+            self.end_line();
+            self.line_num = element.line_num;
+        }
+
+        if let Some(pending) = self.pending.as_mut().filter(|p| p.collecting) {
+            match element.value.find(['{', ';']) {
+                None => {
+                    pending.symbol.name.push_str(&element.value);
+                    return;
+                }
+                // `Foo {`, the name ends in the token
+                Some(index) => pending.symbol.name.push_str(&element.value[..index]),
+            }
+        }
+
+        match element.category {
+            TokenCategory::Whitespace | TokenCategory::Comment | TokenCategory::String => return,
+            _ => {}
+        }
+        if element
+            .scopes
+            .iter()
+            .any(|scope| scope.starts_with("meta.preprocessor"))
+        {
+            return;
+        }
+
+        // only the root scope, such as ` point ` of C or ` m {` of Rust
+        if element.category == TokenCategory::Other && element.scopes.len() == 1 {
+            self.process_words(element);
+            return;
+        }
+
+        if self.process_declaration(element) {
+            return;
+        }
+
+        for char in element.value.chars() {
+            self.process_char(char);
+        }
+    }
+
+    /// an unscoped token may hold names and brackets, every word of it is an identifier
+    fn process_words(&mut self, element: &TokenElement) {
+        let mut word = String::new();
+        for char in element.value.chars() {
+            if char.is_alphanumeric() || char == '_' {
+                word.push(char);
+                continue;
+            }
+
+            self.process_word(element, &mut word);
+            self.process_char(char);
+        }
+        self.process_word(element, &mut word);
+    }
+
+    fn process_word(&mut self, element: &TokenElement, word: &mut String) {
+        if word.is_empty() {
+            return;
+        }
+
+        let mut identifier = TokenElement::new(
+            element.line_num,
+            element.start_index,
+            element.end_index,
+            std::mem::take(word),
+            element.scopes.clone(),
+        );
+        identifier.category = TokenCategory::Identifier;
+        self.process_declaration(&identifier);
+    }
+
+    fn process_char(&mut self, char: char) {
+        match char {
+            '{' => self.open_body(),
+            '}' => self.close_body(),
+            ';' => self.end_statement(),
+            _ => {}
+        }
+    }
+
+    /// true if the element is a part of a declaration
+    fn process_declaration(&mut self, element: &TokenElement) -> bool {
+        let value = element.value.trim();
+        let is_keyword = matches!(
+            element.category,
+            TokenCategory::Keyword | TokenCategory::Type
+        );
+
+        if is_keyword && value == "func" {
+            self.after_func = true;
+            self.has_receiver = false;
+            return true;
+        }
+        // the receiver is right after `func`, `func(` without name is a function literal
+        if self.after_func {
+            self.after_func = false;
+            self.has_receiver = value.starts_with('(');
+        }
+
+        if is_keyword {
+            if let Some(pending) = self.pending.as_mut() {
+                // Go, `type Name struct {`
+                let refined = match (pending.symbol.kind, value) {
+                    (SymbolKind::Type, "struct") => Some(SymbolKind::Struct),
+                    (SymbolKind::Type, "interface") => Some(SymbolKind::Interface),
+                    _ => None,
+                };
+                if let Some(kind) = refined {
+                    pending.symbol.kind = kind;
+                    return true;
+                }
+            }
+
+            if let Some(kind) = SymbolExtractor::keyword_kind(value) {
+                match kind {
+                    // `impl Trait` in the return type of a function is not a declaration
+                    SymbolKind::Package | SymbolKind::Impl if self.pending.is_some() => {}
+                    SymbolKind::Package | SymbolKind::Impl => {
+                        self.pending = Some(Pending {
+                            symbol: CodeSymbol::new(String::new(), kind, element.line_num),
+                            from_entity: true,
+                            collecting: true,
+                        });
+                    }
+                    _ => self.keyword = Some((kind, element.line_num)),
+                }
+                return true;
+            }
+        }
+
+        if element.category == TokenCategory::FunctionDefinition {
+            let in_type = self.stack.last().is_some_and(|open| {
+                matches!(
+                    open.symbol.kind,
+                    SymbolKind::Class
+                        | SymbolKind::Interface
+                        | SymbolKind::Enum
+                        | SymbolKind::Struct
+                        | SymbolKind::Trait
+                        | SymbolKind::Impl
+                )
+            });
+            let kind = if in_type || self.has_receiver {
+                SymbolKind::Method
+            } else {
+                SymbolKind::Function
+            };
+
+            self.pending = Some(Pending {
+                symbol: CodeSymbol::new(value.to_string(), kind, element.line_num),
+                from_entity: true,
+                collecting: false,
+            });
+            self.keyword = None;
+            self.has_receiver = false;
+            return true;
+        }
+
+        let is_name = matches!(
+            element.category,
+            TokenCategory::Type | TokenCategory::Identifier
+        );
+        if is_name {
+            if let Some((kind, line_num)) = self.keyword.take() {
+                let from_entity = element
+                    .scopes
+                    .iter()
+                    .any(|scope| scope.starts_with("entity.name"));
+                self.pending = Some(Pending {
+                    symbol: CodeSymbol::new(value.to_string(), kind, line_num),
+                    from_entity,
+                    collecting: false,
+                });
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn keyword_kind(value: &str) -> Option<SymbolKind> {
+        let kind = match value {
+            "package" => SymbolKind::Package,
+            "mod" => SymbolKind::Module,
+            "class" | "record" => SymbolKind::Class,
+            "interface" => SymbolKind::Interface,
+            "enum" => SymbolKind::Enum,
+            "struct" | "union" => SymbolKind::Struct,
+            "trait" => SymbolKind::Trait,
+            "impl" => SymbolKind::Impl,
+            "type" => SymbolKind::Type,
+            _ => return None,
+        };
+
+        Some(kind)
+    }
+
+    fn open_body(&mut self) {
+        self.depth += 1;
+        self.keyword = None;
+        self.has_receiver = false;
+        if let Some(mut pending) = self.pending.take() {
+            pending.symbol.name = pending.symbol.name.trim().to_string();
+            self.stack.push(OpenSymbol {
+                symbol: pending.symbol,
+                body_depth: self.depth,
+            });
+        }
+    }
+
+    fn close_body(&mut self) {
+        self.pending = None;
+        self.keyword = None;
+        if self.depth == 0 {
+            return;
+        }
+
+        let is_body_end = self
+            .stack
+            .last()
+            .is_some_and(|open| open.body_depth == self.depth);
+        if is_body_end {
+            let mut open = self.stack.pop().unwrap();
+            open.symbol.end_line = self.line_num;
+            self.add_symbol(open.symbol);
+        }
+        self.depth -= 1;
+    }
+
+    fn end_statement(&mut self) {
+        self.keyword = None;
+        let pending = match self.pending.take() {
+            None => return,
+            Some(pending) => pending,
+        };
+
+        if pending.symbol.kind == SymbolKind::Package {
+            self.open_package(pending.symbol);
+            return;
+        }
+
+        // C, `struct point p;` is a variable, not a declaration
+        let is_type = matches!(
+            pending.symbol.kind,
+            SymbolKind::Class | SymbolKind::Interface | SymbolKind::Enum | SymbolKind::Struct
+        );
+        if is_type && !pending.from_entity {
+            return;
+        }
+
+        let mut symbol = pending.symbol;
+        symbol.name = symbol.name.trim().to_string();
+        symbol.end_line = self.line_num;
+        self.add_symbol(symbol);
+    }
+
+    /// Go has no `;`, the package and type aliases end with the line
+    fn end_line(&mut self) {
+        let kind = match self.pending.as_ref() {
+            None => return,
+            Some(pending) => pending.symbol.kind,
+        };
+
+        if kind == SymbolKind::Package || kind == SymbolKind::Type {
+            self.end_statement();
+        }
+    }
+
+    /// a package contains all the following declarations of the file
+    fn open_package(&mut self, mut symbol: CodeSymbol) {
+        symbol.name = symbol.name.trim().to_string();
+        self.stack.push(OpenSymbol {
+            symbol,
+            body_depth: self.depth,
+        });
+    }
+
+    fn add_symbol(&mut self, symbol: CodeSymbol) {
+        match self.stack.last_mut() {
+            None => self.roots.push(symbol),
+            Some(open) => open.symbol.children.push(symbol),
+        }
+    }
+
+    fn finish(mut self) -> Vec<CodeSymbol> {
+        self.end_line();
+        while let Some(mut open) = self.stack.pop() {
+            open.symbol.end_line = self.line_num;
+            self.add_symbol(open.symbol);
+        }
+
+        self.roots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyser::test_helper::elements;
+    use crate::analyser::SymbolExtractor;
+    use scie_model::artifact::{CodeSymbol, SymbolKind};
+
+    fn outline(symbols: &[CodeSymbol]) -> Vec<(String, SymbolKind, i32, i32)> {
+        let mut items = vec![];
+        for symbol in symbols.iter() {
+            items.push((
+                symbol.name.clone(),
+                symbol.kind,
+                symbol.start_line,
+                symbol.end_line,
+            ));
+            items.extend(outline(&symbol.children));
+        }
+        items
+    }
+
+    #[test]
+    fn should_extract_java_outline() {
+        let code = "package com.phodal;
+public class Hello {
+    void main(String[] args) {
+        print(\"{\");
+    }
+}
+";
+
+        let symbols = SymbolExtractor::extract(&elements("Hello.java", code));
+        assert_eq!(1, symbols.len());
+        assert_eq!(
+            vec![
+                (String::from("com.phodal"), SymbolKind::Package, 1, 6),
+                (String::from("Hello"), SymbolKind::Class, 2, 6),
+                (String::from("main"), SymbolKind::Method, 3, 5),
+            ],
+            outline(&symbols)
+        );
+    }
+
+    #[test]
+    fn should_extract_go_methods_and_types() {
+        let code = "package main
+type Id int
+type Point struct {
+}
+func (p *Point) Move() {
+}
+func main() {
+}
+";
+
+        assert_eq!(
+            vec![
+                (String::from("main"), SymbolKind::Package, 1, 8),
+                (String::from("Id"), SymbolKind::Type, 2, 2),
+                (String::from("Point"), SymbolKind::Struct, 3, 4),
+                (String::from("Move"), SymbolKind::Method, 5, 6),
+                (String::from("main"), SymbolKind::Function, 7, 8),
+            ],
+            outline(&SymbolExtractor::extract(&elements("main.go", code)))
+        );
+    }
+
+    #[test]
+    fn should_extract_rust_impl_and_modules() {
+        let code = "struct Foo;
+impl Display for Foo {
+    fn fmt(&self) { write!(f, \"{}\", 1); }
+}
+mod inner {
+    pub struct Point {
+        x: i32,
+    }
+}
+mod tests;
+";
+
+        assert_eq!(
+            vec![
+                (String::from("Foo"), SymbolKind::Struct, 1, 1),
+                (String::from("Display for Foo"), SymbolKind::Impl, 2, 4),
+                (String::from("fmt"), SymbolKind::Method, 3, 3),
+                (String::from("inner"), SymbolKind::Module, 5, 9),
+                (String::from("Point"), SymbolKind::Struct, 6, 8),
+                (String::from("tests"), SymbolKind::Module, 10, 10),
+            ],
+            outline(&SymbolExtractor::extract(&elements("main.rs", code)))
+        );
+    }
+
+    #[test]
+    fn should_extract_c_struct_and_function() {
+        let code = "#include <stdio.h>
+struct point {
+};
+struct point p;
+int main() {
+}
+";
+
+        assert_eq!(
+            vec![
+                (String::from("point"), SymbolKind::Struct, 2, 3),
+                (String::from("main"), SymbolKind::Function, 5, 6),
+            ],
+            outline(&SymbolExtractor::extract(&elements("main.c", code)))
+        );
+    }
+}
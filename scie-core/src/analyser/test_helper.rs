@@ -0,0 +1,16 @@
+use std::path::Path;
+
+use crate::analyser::{Analyser, GrammarPool, TokenCategorizer};
+use scie_model::artifact::TokenElement;
+
+/// the categorized tokens of `code`, by the bundled grammar of `file_name`
+pub fn elements(file_name: &str, code: &str) -> Vec<TokenElement> {
+    let mut grammar_pool = GrammarPool::default();
+    let grammar = grammar_pool
+        .grammar_by_path(Path::new(file_name))
+        .expect(file_name);
+
+    let mut elements = Analyser::tokenize_code(grammar, code);
+    TokenCategorizer::default().categorize(&mut elements);
+    elements
+}
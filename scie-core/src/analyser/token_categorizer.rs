@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use scie_model::artifact::{TokenCategory, TokenElement};
+
+/// scope rules of `TokenCategorizer`, the languages ones are checked before the default ones
+pub static TOKEN_CATEGORIES: &str = include_str!("token_categories.json");
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct CategoryRule {
+    /// scope prefix, `entity.name.function` matches `entity.name.function.java`
+    pub scope: String,
+    /// one of them should be an outer scope of the matched one
+    #[serde(default)]
+    pub parents: Vec<String>,
+    /// innermost scope of the previous token, except whitespaces
+    #[serde(default)]
+    pub previous: Option<String>,
+    pub category: TokenCategory,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct CategoryRules {
+    pub default: Vec<CategoryRule>,
+    /// by the scope name of grammars, such as `source.rust`
+    #[serde(default)]
+    pub languages: HashMap<String, Vec<CategoryRule>>,
+}
+
+/// Map the scopes of tokens to `TokenCategory`, the innermost scope which matches a rule wins.
+pub struct TokenCategorizer {
+    pub rules: CategoryRules,
+}
+
+impl Default for TokenCategorizer {
+    fn default() -> Self {
+        TokenCategorizer::from_json(TOKEN_CATEGORIES).unwrap()
+    }
+}
+
+impl TokenCategorizer {
+    pub fn from_json(code: &str) -> Result<Self, String> {
+        let rules: CategoryRules = serde_json::from_str(code).map_err(|err| err.to_string())?;
+        Ok(TokenCategorizer { rules })
+    }
+
+    pub fn categorize(&self, elements: &mut [TokenElement]) {
+        let mut previous: Option<String> = None;
+        for element in elements.iter_mut() {
+            element.category = self.category(element, previous.as_deref());
+            if element.category != TokenCategory::Whitespace {
+                previous = element.scopes.last().cloned();
+            }
+        }
+    }
+
+    /// `previous` is the innermost scope of the previous token
+    pub fn category(&self, element: &TokenElement, previous: Option<&str>) -> TokenCategory {
+        if element.value.trim().is_empty() {
+            return TokenCategory::Whitespace;
+        }
+
+        let empty = vec![];
+        let language_rules = element
+            .scopes
+            .first()
+            .and_then(|root| self.rules.languages.get(root))
+            .unwrap_or(&empty);
+
+        for (index, scope) in element.scopes.iter().enumerate().rev() {
+            let outer_scopes = &element.scopes[..index];
+            for rule in language_rules.iter().chain(self.rules.default.iter()) {
+                if TokenCategorizer::is_match(scope, &rule.scope)
+                    && TokenCategorizer::has_parent(outer_scopes, &rule.parents)
+                    && TokenCategorizer::is_previous(previous, &rule.previous)
+                {
+                    return rule.category;
+                }
+            }
+        }
+
+        let mut chars = element.value.chars();
+        let is_word = chars
+            .next()
+            .is_some_and(|char| char.is_alphabetic() || char == '_')
+            && chars.all(|char| char.is_alphanumeric() || char == '_');
+        if is_word {
+            TokenCategory::Identifier
+        } else {
+            TokenCategory::Other
+        }
+    }
+
+    fn is_match(scope: &str, prefix: &str) -> bool {
+        scope == prefix || (scope.starts_with(prefix) && scope[prefix.len()..].starts_with('.'))
+    }
+
+    fn has_parent(outer_scopes: &[String], parents: &[String]) -> bool {
+        parents.is_empty()
+            || outer_scopes.iter().any(|scope| {
+                parents
+                    .iter()
+                    .any(|parent| TokenCategorizer::is_match(scope, parent))
+            })
+    }
+
+    fn is_previous(previous: Option<&str>, expected: &Option<String>) -> bool {
+        match (expected, previous) {
+            (None, _) => true,
+            (Some(expected), Some(previous)) => TokenCategorizer::is_match(previous, expected),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyser::TokenCategorizer;
+    use scie_model::artifact::{TokenCategory, TokenElement};
+
+    fn token(value: &str, scopes: &[&str]) -> TokenElement {
+        let scopes = scopes.iter().map(|scope| scope.to_string()).collect();
+        TokenElement::new(1, 0, value.len() as i32, value.to_string(), scopes)
+    }
+
+    #[test]
+    fn should_map_java_scopes() {
+        let categorizer = TokenCategorizer::default();
+        let mut elements = vec![
+            token("int", &["source.java", "storage.type.primitive.java"]),
+            token(" ", &["source.java"]),
+            token(
+                "main",
+                &[
+                    "source.java",
+                    "meta.method.identifier.java",
+                    "entity.name.function.java",
+                ],
+            ),
+            token(
+                "print",
+                &[
+                    "source.java",
+                    "meta.method-call.java",
+                    "entity.name.function.java",
+                ],
+            ),
+            token(
+                "\"",
+                &[
+                    "source.java",
+                    "string.quoted.double.java",
+                    "punctuation.definition.string.begin.java",
+                ],
+            ),
+            token("42", &["source.java", "constant.numeric.decimal.java"]),
+            token("=", &["source.java", "keyword.operator.assignment.java"]),
+            token("// a", &["source.java", "comment.line.double-slash.java"]),
+            token("name", &["source.java"]),
+        ];
+        categorizer.categorize(&mut elements);
+
+        let categories: Vec<TokenCategory> = elements.iter().map(|e| e.category).collect();
+        assert_eq!(
+            vec![
+                TokenCategory::Type,
+                TokenCategory::Whitespace,
+                TokenCategory::FunctionDefinition,
+                TokenCategory::FunctionCall,
+                TokenCategory::String,
+                TokenCategory::Number,
+                TokenCategory::Operator,
+                TokenCategory::Comment,
+                TokenCategory::Identifier,
+            ],
+            categories
+        );
+    }
+
+    #[test]
+    fn should_use_language_rules_of_rust() {
+        let categorizer = TokenCategorizer::default();
+        let mut elements = vec![
+            token("fn", &["source.rust", "keyword.other.fn.rust"]),
+            token(" ", &["source.rust"]),
+            token("main", &["source.rust", "entity.name.function.rust"]),
+            token("println", &["source.rust", "entity.name.function.rust"]),
+            token("struct", &["source.rust", "storage.type.rust"]),
+            token("i32", &["source.rust", "storage.type.core.rust"]),
+        ];
+        categorizer.categorize(&mut elements);
+
+        let categories: Vec<TokenCategory> = elements.iter().map(|e| e.category).collect();
+        assert_eq!(
+            vec![
+                TokenCategory::Keyword,
+                TokenCategory::Whitespace,
+                TokenCategory::FunctionDefinition,
+                TokenCategory::FunctionCall,
+                TokenCategory::Keyword,
+                TokenCategory::Type,
+            ],
+            categories
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use scie_detector::framework_detector::FrameworkDetector;
+use scie_infra::paths::relative_path;
+use scie_model::artifact::{CodeFile, Module, Package, Project, SymbolKind, Workspace};
+
+/// Group analysed files into `Workspace` > `Project` > `Module` > `Package`: a file belongs to
+/// the deepest module root above it, and to the package it declares, or its dir in the module.
+pub struct WorkspaceAssembler {
+    pub root: PathBuf,
+    pub frameworks: Vec<String>,
+    /// the build roots of the detected frameworks, the root is always one of them
+    pub module_roots: Vec<PathBuf>,
+}
+
+impl WorkspaceAssembler {
+    /// `module_roots` are the dirs of build files, such as `FrameworkDetector.frameworks`
+    pub fn new(root: &Path, module_roots: &[PathBuf], frameworks: Vec<String>) -> Self {
+        let mut roots = vec![root.to_path_buf()];
+        for dir in module_roots.iter() {
+            if !roots.contains(dir) {
+                roots.push(dir.clone());
+            }
+        }
+        roots.sort();
+
+        WorkspaceAssembler {
+            root: root.to_path_buf(),
+            frameworks,
+            module_roots: roots,
+        }
+    }
+
+    /// a module for each build root of `detector`, which has run on `root`
+    pub fn from_detector(root: &Path, detector: &FrameworkDetector) -> Self {
+        let module_roots: Vec<PathBuf> = detector
+            .frameworks
+            .iter()
+            .map(|framework| PathBuf::from(&framework.path))
+            .collect();
+        WorkspaceAssembler::new(
+            root,
+            &module_roots,
+            WorkspaceAssembler::frameworks(detector),
+        )
+    }
+
+    /// the tags which are detected
+    pub fn frameworks(detector: &FrameworkDetector) -> Vec<String> {
+        detector
+            .tags
+            .iter()
+            .filter(|(_, detected)| **detected)
+            .map(|(tag, _)| tag.to_string())
+            .collect()
+    }
+
+    pub fn assemble(&self, files: Vec<CodeFile>) -> Workspace {
+        let mut modules: BTreeMap<PathBuf, BTreeMap<String, Vec<CodeFile>>> = self
+            .module_roots
+            .iter()
+            .map(|root| (root.clone(), Default::default()))
+            .collect();
+
+        for file in files {
+            let path = PathBuf::from(&file.path);
+            let module_root = self.module_root(&path);
+            let package = WorkspaceAssembler::package_name(&file, &path, &module_root);
+            modules
+                .entry(module_root)
+                .or_default()
+                .entry(package)
+                .or_default()
+                .push(file);
+        }
+
+        let modules = modules
+            .into_iter()
+            .map(|(module_root, packages)| Module {
+                name: self.module_name(&module_root),
+                path: relative_path(&module_root, &self.root),
+                packages: packages
+                    .into_iter()
+                    .map(|(name, files)| Package { name, files })
+                    .collect(),
+                artifacts: vec![],
+            })
+            .collect();
+
+        let name = self.module_name(&self.root);
+        Workspace {
+            name: name.clone(),
+            projects: vec![Project {
+                name,
+                path: self.root.display().to_string(),
+                frameworks: self.frameworks.clone(),
+                modules,
+            }],
+        }
+    }
+
+    fn module_root(&self, path: &Path) -> PathBuf {
+        self.module_roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .cloned()
+            .unwrap_or_else(|| self.root.clone())
+    }
+
+    fn module_name(&self, module_root: &Path) -> String {
+        if module_root == self.root {
+            return module_root
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+        }
+
+        relative_path(module_root, &self.root)
+    }
+
+    /// declared by `package` in Java or Go, else the dir in the module, such as `src/analyser`
+    fn package_name(file: &CodeFile, path: &Path, module_root: &Path) -> String {
+        let declared = file
+            .symbols
+            .iter()
+            .find(|symbol| symbol.kind == SymbolKind::Package);
+        if let Some(symbol) = declared {
+            return symbol.name.clone();
+        }
+
+        path.parent()
+            .map(|dir| relative_path(dir, module_root))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyser::WorkspaceAssembler;
+    use scie_detector::framework_detector::FrameworkDetector;
+    use scie_infra::paths::relative_path;
+    use scie_model::artifact::{CodeFile, CodeSymbol, SymbolKind};
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_group_files_by_module_and_package() {
+        let root = PathBuf::from("/work/shop");
+        let assembler = WorkspaceAssembler::new(
+            &root,
+            &[root.clone(), root.join("app")],
+            vec![String::from("workspace.java.gradle")],
+        );
+
+        let mut main = CodeFile::new(root.join("app").join("src").join("Main.java"));
+        main.symbols = vec![CodeSymbol::new(
+            String::from("com.shop"),
+            SymbolKind::Package,
+            1,
+        )];
+        let files = vec![
+            CodeFile::new(root.join("build.gradle")),
+            main,
+            CodeFile::new(root.join("tools").join("gen.rs")),
+        ];
+
+        let workspace = assembler.assemble(files);
+        assert_eq!("shop", workspace.name);
+        let project = &workspace.projects[0];
+        assert_eq!(vec!["workspace.java.gradle"], project.frameworks);
+
+        let modules: Vec<(&str, &str)> = project
+            .modules
+            .iter()
+            .map(|module| (module.name.as_str(), module.path.as_str()))
+            .collect();
+        assert_eq!(vec![("shop", ""), ("app", "app")], modules);
+
+        let root_packages: Vec<&str> = project.modules[0]
+            .packages
+            .iter()
+            .map(|package| package.name.as_str())
+            .collect();
+        assert_eq!(vec!["", "tools"], root_packages);
+        assert_eq!("com.shop", project.modules[1].packages[0].name);
+        assert_eq!(3, workspace.code_files().len());
+    }
+
+    #[test]
+    fn should_use_build_roots_of_detector_as_modules() {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("polyglot");
+        let mut detector = FrameworkDetector::new();
+        detector.run(root.display().to_string());
+
+        let assembler = WorkspaceAssembler::from_detector(&root, &detector);
+        let modules: Vec<String> = assembler
+            .module_roots
+            .iter()
+            .map(|dir| relative_path(dir, &root))
+            .collect();
+        assert_eq!(vec!["", "backend", "frontend", "tools"], modules);
+        assert!(assembler
+            .frameworks
+            .contains(&String::from("workspace.rust.cargo")));
+    }
+}
@@ -0,0 +1,728 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use scie_detector::dependency::base_library::{BaseLibrary, LibraryScope};
+use scie_detector::dependency::module_dependency::{ModuleDependency, PackageImport};
+use scie_infra::paths::relative_path;
+use scie_model::artifact::{CodeFile, TokenCategory, TokenElement};
+
+/// a token of a script, the tokens of a string are merged by their `string.quoted` scope
+#[derive(PartialEq, Debug, Clone)]
+enum Lexeme {
+    Word(String),
+    /// a double quoted string can be interpolated
+    Str(String, bool),
+    Char(char),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    lexeme: Lexeme,
+    line_num: i32,
+}
+
+/// dependencies of a `build.gradle`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct GradleScript {
+    /// relative to the project
+    pub path: String,
+    pub libraries: Vec<BaseLibrary>,
+    /// `project(':x')` references, as the imports of the module of the script
+    pub module: ModuleDependency,
+}
+
+/// Read the `dependencies` blocks of Gradle scripts by the tokens of the Groovy grammar, the
+/// versions can come from variables of `ext`, `def` or `gradle.properties`.
+#[derive(Default)]
+pub struct GradleParser {
+    /// without `ext.` or `rootProject.ext.`, the entries of a map are `versions.junit`
+    pub variables: BTreeMap<String, String>,
+}
+
+impl GradleParser {
+    /// `key=value` or `key: value` lines of `gradle.properties`
+    pub fn add_properties(&mut self, code: &str) {
+        for line in code.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+                continue;
+            }
+
+            if let Some(index) = line.find(['=', ':']) {
+                self.variables.insert(
+                    line[..index].trim().to_string(),
+                    line[index + 1..].trim().to_string(),
+                );
+            }
+        }
+    }
+
+    /// `build.gradle` files of an analysed project, the root script goes first, so that its
+    /// variables are visible in the scripts of sub projects
+    pub fn parse_project(root: &Path, files: &[&CodeFile]) -> Vec<GradleScript> {
+        let mut parser = GradleParser::default();
+        if let Ok(code) = fs::read_to_string(root.join("gradle.properties")) {
+            parser.add_properties(&code);
+        }
+
+        let mut scripts: Vec<&CodeFile> = files
+            .iter()
+            .filter(|file| file.name == "build.gradle")
+            .copied()
+            .collect();
+        scripts.sort_by_key(|file| Path::new(&file.path).components().count());
+
+        scripts
+            .into_iter()
+            .map(|file| {
+                let path = Path::new(&file.path);
+                let dir = path.parent().unwrap_or(root);
+                let module = if dir == root {
+                    root.file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                } else {
+                    relative_path(dir, root)
+                };
+                parser.parse(file, &relative_path(path, root), &module)
+            })
+            .collect()
+    }
+
+    /// `path` of the script is relative to the project, `module` is the name of its module
+    pub fn parse(&mut self, file: &CodeFile, path: &str, module: &str) -> GradleScript {
+        let tokens = GradleParser::tokens(&file.elements);
+        let mut script = GradleScript {
+            path: path.to_string(),
+            libraries: vec![],
+            module: ModuleDependency::new(module, ""),
+        };
+        script.module.files.push(path.to_string());
+
+        let mut blocks: Vec<String> = vec![];
+        let mut index = 0;
+        while index < tokens.len() {
+            match &tokens[index].lexeme {
+                Lexeme::Char('{') => {
+                    let name = match index.checked_sub(1).map(|i| &tokens[i].lexeme) {
+                        Some(Lexeme::Word(word)) => word.clone(),
+                        _ => String::new(),
+                    };
+                    blocks.push(name);
+                }
+                Lexeme::Char('}') => {
+                    blocks.pop();
+                }
+                Lexeme::Word(word) if blocks.last().is_some_and(|b| b == "dependencies") => {
+                    let end = GradleParser::statement_end(&tokens, index + 1);
+                    let line_num = tokens[index].line_num;
+                    self.add_dependency(&mut script, word, &tokens[index + 1..end], line_num);
+                    index = end;
+                    continue;
+                }
+                Lexeme::Word(_) => {
+                    if let Some(next) = self.assign(&tokens, index) {
+                        index = next;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+
+        script
+    }
+
+    /// `implementation 'g:a:v'`, `implementation group: 'g', name: 'a'`, `project(':x')` or
+    /// `platform('g:a:v')`, with or without parens
+    fn add_dependency(
+        &self,
+        script: &mut GradleScript,
+        config: &str,
+        tokens: &[Token],
+        line_num: i32,
+    ) {
+        let tokens = GradleParser::unwrap_parens(tokens);
+        let scope = GradleParser::scope(config);
+        match tokens.first().map(|token| &token.lexeme) {
+            Some(Lexeme::Word(word)) if word == "project" => {
+                if let Some(path) = self.project_path(&tokens[1..]) {
+                    script.module.imports.push(PackageImport {
+                        file: script.path.clone(),
+                        line_num,
+                        source: path.clone(),
+                        to: path.trim_start_matches(':').replace(':', "/"),
+                        to_file: String::new(),
+                        external: false,
+                        scope: scope.name().to_string(),
+                    });
+                }
+            }
+            Some(Lexeme::Word(word))
+                if matches!(
+                    word.as_str(),
+                    "platform" | "enforcedPlatform" | "testFixtures"
+                ) =>
+            {
+                self.add_dependency(script, config, &tokens[1..], line_num);
+            }
+            Some(Lexeme::Word(_))
+                if tokens.get(1).map(|token| &token.lexeme) == Some(&Lexeme::Char(':')) =>
+            {
+                let mut values: BTreeMap<&str, String> = Default::default();
+                for pair in tokens.split(|token| token.lexeme == Lexeme::Char(',')) {
+                    if let [key, colon, value, ..] = pair {
+                        if let (Lexeme::Word(key), Lexeme::Char(':')) = (&key.lexeme, &colon.lexeme)
+                        {
+                            if let Some(value) = self.value_of(&value.lexeme) {
+                                values.insert(key.as_str(), value);
+                            }
+                        }
+                    }
+                }
+
+                if let Some(name) = values.get("name") {
+                    script.libraries.push(GradleParser::library(
+                        values.get("group").cloned().unwrap_or_default(),
+                        name.clone(),
+                        values.get("version").cloned().unwrap_or_default(),
+                        scope,
+                    ));
+                }
+            }
+            Some(_) => {
+                for item in tokens.split(|token| token.lexeme == Lexeme::Char(',')) {
+                    if let [token] = item {
+                        if let Some(notation) = self.value_of(&token.lexeme) {
+                            if let Some(library) = GradleParser::notation(&notation, scope) {
+                                script.libraries.push(library);
+                            }
+                        }
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// `(':x')` or `(path: ':x')`
+    fn project_path(&self, tokens: &[Token]) -> Option<String> {
+        let tokens = GradleParser::unwrap_parens(tokens);
+        match tokens.last().map(|token| &token.lexeme) {
+            Some(lexeme) if tokens.len() == 1 || tokens.len() == 3 => self.value_of(lexeme),
+            _ => None,
+        }
+    }
+
+    /// `group:name:version:classifier@extension`, the version can be empty in a platform
+    fn notation(notation: &str, scope: LibraryScope) -> Option<BaseLibrary> {
+        let notation = notation.split('@').next().unwrap_or_default();
+        let parts: Vec<&str> = notation.split(':').collect();
+        if parts.len() < 2 || parts[1].is_empty() {
+            return None;
+        }
+
+        Some(GradleParser::library(
+            parts[0].to_string(),
+            parts[1].to_string(),
+            parts.get(2).map(|v| v.to_string()).unwrap_or_default(),
+            scope,
+        ))
+    }
+
+    fn library(group: String, name: String, version: String, scope: LibraryScope) -> BaseLibrary {
+        BaseLibrary {
+            name,
+            version,
+            group,
+            source: String::from("maven"),
+            scope: scope.name().to_string(),
+        }
+    }
+
+    /// configurations of tests, and of tools which are not a part of the artifact
+    fn scope(config: &str) -> LibraryScope {
+        let config = config.to_lowercase();
+        if config.contains("test") {
+            return LibraryScope::Test;
+        }
+
+        match config.as_str() {
+            "compileonly"
+            | "compileonlyapi"
+            | "annotationprocessor"
+            | "kapt"
+            | "classpath"
+            | "developmentonly" => LibraryScope::Dev,
+            _ => LibraryScope::Main,
+        }
+    }
+
+    /// `name = 'v'`, `def name = 'v'`, `ext.name = 'v'` or `name = [key: 'v']`, returns the
+    /// index after the assignment
+    fn assign(&mut self, tokens: &[Token], index: usize) -> Option<usize> {
+        let mut index = index;
+        if tokens[index].lexeme == Lexeme::Word(String::from("def")) {
+            index += 1;
+        }
+
+        let name = match tokens.get(index).map(|token| &token.lexeme) {
+            Some(Lexeme::Word(name)) => GradleParser::variable_name(name).to_string(),
+            _ => return None,
+        };
+        if tokens.get(index + 1).map(|token| &token.lexeme) != Some(&Lexeme::Char('=')) {
+            return None;
+        }
+
+        match tokens.get(index + 2).map(|token| &token.lexeme) {
+            Some(Lexeme::Char('[')) => {
+                let mut end = index + 3;
+                while end < tokens.len() && tokens[end].lexeme != Lexeme::Char(']') {
+                    end += 1;
+                }
+                for pair in tokens[index + 3..end].split(|t| t.lexeme == Lexeme::Char(',')) {
+                    if let [key, colon, value] = pair {
+                        if let (Lexeme::Word(key), Lexeme::Char(':')) = (&key.lexeme, &colon.lexeme)
+                        {
+                            if let Some(value) = self.value_of(&value.lexeme) {
+                                self.variables.insert(format!("{}.{}", name, key), value);
+                            }
+                        }
+                    }
+                }
+                Some(end)
+            }
+            Some(lexeme) => {
+                if let Some(value) = self.value_of(lexeme) {
+                    self.variables.insert(name, value);
+                }
+                Some(index + 3)
+            }
+            None => None,
+        }
+    }
+
+    /// a string, interpolated if it is double quoted, or a variable
+    fn value_of(&self, lexeme: &Lexeme) -> Option<String> {
+        match lexeme {
+            Lexeme::Str(value, false) => Some(value.clone()),
+            Lexeme::Str(value, true) => Some(self.interpolate(value)),
+            Lexeme::Word(name) => self
+                .variables
+                .get(GradleParser::variable_name(name))
+                .cloned(),
+            Lexeme::Char(_) => None,
+        }
+    }
+
+    /// `${name}` and `$name`, the unknown ones are kept as they are
+    fn interpolate(&self, value: &str) -> String {
+        let mut output = String::new();
+        let mut rest = value;
+        while let Some(index) = rest.find('$') {
+            output.push_str(&rest[..index]);
+            rest = &rest[index..];
+
+            let (expr, length) = if rest.starts_with("${") {
+                match rest.find('}') {
+                    Some(end) => (rest[2..end].trim(), end + 1),
+                    None => break,
+                }
+            } else {
+                let end = rest[1..]
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                    .map_or(rest.len(), |end| end + 1);
+                let expr = rest[1..end].trim_end_matches('.');
+                (expr, 1 + expr.len())
+            };
+
+            match self.variables.get(GradleParser::variable_name(expr)) {
+                Some(value) if !expr.is_empty() => output.push_str(value),
+                _ => output.push_str(&rest[..length]),
+            }
+            rest = &rest[length..];
+        }
+
+        output.push_str(rest);
+        output
+    }
+
+    fn variable_name(name: &str) -> &str {
+        let prefixes = [
+            "rootProject.ext.",
+            "project.ext.",
+            "ext.",
+            "rootProject.",
+            "project.",
+        ];
+        prefixes
+            .iter()
+            .find_map(|prefix| name.strip_prefix(prefix))
+            .unwrap_or(name)
+    }
+
+    /// a statement ends at a line break out of parens, unless the line ends with a comma
+    fn statement_end(tokens: &[Token], start: usize) -> usize {
+        let mut depth: usize = 0;
+        let mut index = start;
+        while index < tokens.len() {
+            let token = &tokens[index];
+            if depth == 0 {
+                let is_new_line = token.line_num != tokens[index - 1].line_num
+                    && tokens[index - 1].lexeme != Lexeme::Char(',');
+                let is_end = matches!(token.lexeme, Lexeme::Char('{' | '}' | ';'));
+                if is_new_line || is_end {
+                    break;
+                }
+            }
+
+            match token.lexeme {
+                Lexeme::Char('(' | '[') => depth += 1,
+                Lexeme::Char(')' | ']') => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            index += 1;
+        }
+        index
+    }
+
+    fn unwrap_parens(tokens: &[Token]) -> &[Token] {
+        match (tokens.first(), tokens.last()) {
+            (Some(first), Some(last))
+                if tokens.len() > 1
+                    && first.lexeme == Lexeme::Char('(')
+                    && last.lexeme == Lexeme::Char(')') =>
+            {
+                &tokens[1..tokens.len() - 1]
+            }
+            _ => tokens,
+        }
+    }
+
+    /// comments are skipped, the others are split into words and chars
+    fn tokens(elements: &[TokenElement]) -> Vec<Token> {
+        let mut tokens = vec![];
+        let mut string: Option<Token> = None;
+        for element in elements.iter() {
+            if element.category == TokenCategory::Comment {
+                continue;
+            }
+
+            let quoted = element
+                .scopes
+                .iter()
+                .rev()
+                .find(|scope| scope.starts_with("string.quoted"));
+            let quoted = match quoted {
+                Some(quoted) => quoted,
+                None => {
+                    tokens.extend(string.take());
+                    GradleParser::split(element, &mut tokens);
+                    continue;
+                }
+            };
+
+            let is_double = quoted.starts_with("string.quoted.double");
+            let is_punctuation = |kind: &str| {
+                element.scopes.iter().any(|scope| {
+                    scope.starts_with(&format!("punctuation.definition.string.{}", kind))
+                })
+            };
+            if is_punctuation("begin") {
+                tokens.extend(string.take());
+                string = Some(GradleParser::string(element, is_double));
+            } else if is_punctuation("end") {
+                let token = string
+                    .take()
+                    .unwrap_or_else(|| GradleParser::string(element, is_double));
+                tokens.push(token);
+            } else {
+                let token = string.get_or_insert_with(|| GradleParser::string(element, is_double));
+                if let Lexeme::Str(value, _) = &mut token.lexeme {
+                    value.push_str(&element.value);
+                }
+            }
+        }
+
+        tokens.extend(string);
+        tokens
+    }
+
+    fn string(element: &TokenElement, is_double: bool) -> Token {
+        Token {
+            lexeme: Lexeme::Str(String::new(), is_double),
+            line_num: element.line_num,
+        }
+    }
+
+    fn split(element: &TokenElement, tokens: &mut Vec<Token>) {
+        let mut word = String::new();
+        for char in element.value.chars() {
+            if char.is_alphanumeric() || char == '_' || char == '.' || char == '$' {
+                word.push(char);
+                continue;
+            }
+
+            if !word.is_empty() {
+                tokens.push(Token {
+                    lexeme: Lexeme::Word(std::mem::take(&mut word)),
+                    line_num: element.line_num,
+                });
+            }
+            if !char.is_whitespace() {
+                tokens.push(Token {
+                    lexeme: Lexeme::Char(char),
+                    line_num: element.line_num,
+                });
+            }
+        }
+
+        if !word.is_empty() {
+            tokens.push(Token {
+                lexeme: Lexeme::Word(word),
+                line_num: element.line_num,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyser::{Analyser, TokenCategorizer};
+    use crate::build::GradleParser;
+    use scie_model::artifact::{CodeFile, TokenElement};
+    use std::path::PathBuf;
+
+    /// tokens with the string and comment scopes of the Groovy grammar
+    fn groovy(code: &str) -> CodeFile {
+        let mut file = CodeFile::new(PathBuf::from("build.gradle"));
+        let scopes = |names: &[&str]| -> Vec<String> {
+            let mut scopes = vec![String::from("source.groovy")];
+            scopes.extend(names.iter().map(|name| name.to_string()));
+            scopes
+        };
+
+        for (index, line) in code.lines().enumerate() {
+            let line_num = index as i32 + 1;
+            let mut push = |value: &str, names: &[&str]| {
+                if !value.is_empty() {
+                    let element =
+                        TokenElement::new(line_num, 0, 0, value.to_string(), scopes(names));
+                    file.elements.push(element);
+                }
+            };
+
+            let mut rest = line;
+            while !rest.is_empty() {
+                let start = rest.find(['\'', '"', '/']).unwrap_or(rest.len());
+                push(&rest[..start], &[]);
+                rest = &rest[start..];
+                if rest.starts_with("//") {
+                    push(rest, &["comment.line.double-slash.groovy"]);
+                    break;
+                }
+                if rest.starts_with('/') {
+                    push("/", &[]);
+                    rest = &rest[1..];
+                    continue;
+                }
+                if rest.is_empty() {
+                    break;
+                }
+
+                let quote = &rest[..1];
+                let scope = if quote == "'" {
+                    "string.quoted.single.groovy"
+                } else {
+                    "string.quoted.double.groovy"
+                };
+                let end = rest[1..].find(quote).map_or(rest.len(), |end| end + 1);
+                push(
+                    quote,
+                    &[scope, "punctuation.definition.string.begin.groovy"],
+                );
+                push(&rest[1..end], &[scope]);
+                push(quote, &[scope, "punctuation.definition.string.end.groovy"]);
+                rest = &rest[(end + 1).min(rest.len())..];
+            }
+        }
+
+        TokenCategorizer::default().categorize(&mut file.elements);
+        file
+    }
+
+    fn libraries(parser: &mut GradleParser, code: &str) -> Vec<(String, String, String, String)> {
+        parser
+            .parse(&groovy(code), "build.gradle", "app")
+            .libraries
+            .into_iter()
+            .map(|lib| (lib.group, lib.name, lib.version, lib.scope))
+            .collect()
+    }
+
+    fn library(
+        group: &str,
+        name: &str,
+        version: &str,
+        scope: &str,
+    ) -> (String, String, String, String) {
+        (
+            group.to_string(),
+            name.to_string(),
+            version.to_string(),
+            scope.to_string(),
+        )
+    }
+
+    #[test]
+    fn should_parse_string_and_map_notations() {
+        let mut parser = GradleParser::default();
+        let code = r#"
+dependencies {
+    // implementation 'commented:out:1.0'
+    implementation 'org.springframework:spring-core:5.2.9.RELEASE'
+    implementation("com.google.guava:guava:30.0-jre")
+    compileOnly group: 'org.projectlombok', name: 'lombok', version: '1.18.16'
+    testImplementation 'junit:junit:4.13.1'
+    implementation platform('org.springframework.boot:spring-boot-dependencies:2.3.4.RELEASE')
+    implementation fileTree(dir: 'libs', include: ['*.jar'])
+}
+"#;
+
+        assert_eq!(
+            vec![
+                library(
+                    "org.springframework",
+                    "spring-core",
+                    "5.2.9.RELEASE",
+                    "Main"
+                ),
+                library("com.google.guava", "guava", "30.0-jre", "Main"),
+                library("org.projectlombok", "lombok", "1.18.16", "Dev"),
+                library("junit", "junit", "4.13.1", "Test"),
+                library(
+                    "org.springframework.boot",
+                    "spring-boot-dependencies",
+                    "2.3.4.RELEASE",
+                    "Main"
+                ),
+            ],
+            libraries(&mut parser, code)
+        );
+    }
+
+    #[test]
+    fn should_resolve_variables_of_ext_and_properties() {
+        let mut parser = GradleParser::default();
+        parser.add_properties("# versions\nkotlinVersion=1.4.10\n");
+        let code = r#"
+ext {
+    springVersion = '5.2.9.RELEASE'
+    versions = [junit: '4.13.1', mockito: "3.6.0"]
+}
+def guava = 'com.google.guava:guava:30.0-jre'
+
+dependencies {
+    implementation "org.springframework:spring-core:${springVersion}"
+    implementation "org.jetbrains.kotlin:kotlin-stdlib:$kotlinVersion"
+    implementation guava
+    testImplementation "junit:junit:${rootProject.ext.versions.junit}"
+    testImplementation "org.mockito:mockito-core:${versions.mockito}"
+    implementation "io.grpc:grpc-core:${grpcVersion}"
+    implementation 'org.slf4j:slf4j-api:${slf4jVersion}'
+}
+"#;
+
+        assert_eq!(
+            vec![
+                library(
+                    "org.springframework",
+                    "spring-core",
+                    "5.2.9.RELEASE",
+                    "Main"
+                ),
+                library("org.jetbrains.kotlin", "kotlin-stdlib", "1.4.10", "Main"),
+                library("com.google.guava", "guava", "30.0-jre", "Main"),
+                library("junit", "junit", "4.13.1", "Test"),
+                library("org.mockito", "mockito-core", "3.6.0", "Test"),
+                library("io.grpc", "grpc-core", "${grpcVersion}", "Main"),
+                library("org.slf4j", "slf4j-api", "${slf4jVersion}", "Main"),
+            ],
+            libraries(&mut parser, code)
+        );
+    }
+
+    #[test]
+    fn should_parse_project_references_as_module_dependency() {
+        let mut parser = GradleParser::default();
+        let code = r#"
+dependencies {
+    implementation project(':core')
+    api(project(path: ':shared:model'))
+    testImplementation(project(':core')) {
+        exclude group: 'junit'
+    }
+}
+"#;
+
+        let script = parser.parse(&groovy(code), "app/build.gradle", "app");
+        assert_eq!("app", script.module.name);
+        let imports: Vec<(&str, &str, i32, &str)> = script
+            .module
+            .imports
+            .iter()
+            .map(|import| {
+                (
+                    import.source.as_str(),
+                    import.to.as_str(),
+                    import.line_num,
+                    import.scope.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (":core", "core", 3, "Main"),
+                (":shared:model", "shared/model", 4, "Main"),
+                (":core", "core", 5, "Test"),
+            ],
+            imports
+        );
+        assert!(script.libraries.is_empty());
+    }
+
+    #[test]
+    fn should_parse_scripts_of_gradle_project() {
+        let root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+        let dir = root_dir
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("java")
+            .join("simple");
+
+        let workspace = Analyser::workspace_by_dir(&dir, false, false);
+        let scripts = GradleParser::parse_project(&dir, &workspace.code_files());
+        let paths: Vec<&str> = scripts.iter().map(|script| script.path.as_str()).collect();
+        assert_eq!(vec!["build.gradle", "app/build.gradle"], paths);
+
+        let app = &scripts[1];
+        assert_eq!("app", app.module.name);
+        let versions: Vec<(&str, &str)> = app
+            .libraries
+            .iter()
+            .map(|lib| (lib.name.as_str(), lib.version.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("spring-core", "5.2.9.RELEASE"),
+                ("lombok", "1.18.16"),
+                ("junit", "4.13.1")
+            ],
+            versions
+        );
+    }
+}
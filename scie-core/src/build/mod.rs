@@ -0,0 +1,3 @@
+pub mod gradle_parser;
+
+pub use gradle_parser::{GradleParser, GradleScript};
@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::graph::{ImportResolver, ImportTarget};
+use scie_detector::dependency::module_dependency::{ModuleDependency, PackageImport};
+use scie_detector::dependency::project_dependency::ProjectDependency;
+use scie_model::artifact::{CodeFile, Project, Workspace};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum GraphLevel {
+    File,
+    /// `module:package` of the workspace, imports inside a package are ignored
+    Package,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct DependencyNode {
+    pub id: String,
+    /// not a part of the workspace, such as `java.util` or `serde`
+    pub external: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct DependencyEdge {
+    pub from: String,
+    pub to: String,
+    /// imports from `from` to `to`
+    pub count: usize,
+}
+
+/// Dependencies between the files or packages of a workspace, by the imports of files.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct DependencyGraph {
+    pub nodes: Vec<DependencyNode>,
+    pub edges: Vec<DependencyEdge>,
+}
+
+impl DependencyGraph {
+    /// packages of each project, with the imports of their files resolved
+    pub fn project_dependencies(workspace: &Workspace) -> Vec<ProjectDependency> {
+        workspace
+            .projects
+            .iter()
+            .map(DependencyGraph::project_dependency)
+            .collect()
+    }
+
+    pub fn project_dependency(project: &Project) -> ProjectDependency {
+        let mut modules = vec![];
+        let mut files: Vec<(String, &CodeFile)> = vec![];
+        let mut packages: BTreeMap<String, String> = Default::default();
+        for module in project.modules.iter() {
+            for package in module.packages.iter() {
+                let mut dependency = ModuleDependency::new(&module.name, &package.name);
+                for file in package.files.iter() {
+                    let id = DependencyGraph::file_id(&file.path, &project.path);
+                    packages.insert(id.clone(), dependency.name.clone());
+                    dependency.files.push(id.clone());
+                    files.push((id, file));
+                }
+                modules.push(dependency);
+            }
+        }
+
+        let resolver = ImportResolver::new(&files);
+        let mut files = files.iter();
+        for dependency in modules.iter_mut() {
+            for (id, file) in files.by_ref().take(dependency.files.len()) {
+                let root = ImportResolver::root_scope(file);
+                for import in file.imports.iter() {
+                    let targets = match resolver.resolve(id, root, &import.source) {
+                        ImportTarget::Files(targets) => targets
+                            .into_iter()
+                            .map(|target| (packages[&target].clone(), target, false))
+                            .collect(),
+                        ImportTarget::External(name) => vec![(name, String::new(), true)],
+                    };
+
+                    for (to, to_file, external) in targets {
+                        dependency.imports.push(PackageImport {
+                            file: id.clone(),
+                            line_num: import.line_num,
+                            source: import.source.clone(),
+                            to,
+                            to_file,
+                            external,
+                            scope: String::new(),
+                        });
+                    }
+                }
+            }
+        }
+
+        ProjectDependency {
+            name: project.name.clone(),
+            modules,
+        }
+    }
+
+    pub fn from_workspace(workspace: &Workspace, level: GraphLevel) -> DependencyGraph {
+        DependencyGraph::from_projects(&DependencyGraph::project_dependencies(workspace), level)
+    }
+
+    pub fn from_projects(projects: &[ProjectDependency], level: GraphLevel) -> DependencyGraph {
+        let mut nodes: BTreeMap<String, bool> = Default::default();
+        let mut edges: BTreeMap<(String, String), usize> = Default::default();
+
+        for module in projects.iter().flat_map(|project| project.modules.iter()) {
+            match level {
+                GraphLevel::File => {
+                    for file in module.files.iter() {
+                        nodes.entry(file.clone()).or_insert(false);
+                    }
+                }
+                GraphLevel::Package => {
+                    nodes.entry(module.name.clone()).or_insert(false);
+                }
+            }
+
+            for import in module.imports.iter() {
+                let (from, to) = match (level, import.external) {
+                    (GraphLevel::File, false) => (&import.file, &import.to_file),
+                    (GraphLevel::File, true) => (&import.file, &import.to),
+                    (GraphLevel::Package, _) => (&module.name, &import.to),
+                };
+                if from == to {
+                    continue;
+                }
+
+                nodes.entry(to.clone()).or_insert(import.external);
+                *edges.entry((from.clone(), to.clone())).or_default() += 1;
+            }
+        }
+
+        DependencyGraph {
+            nodes: nodes
+                .into_iter()
+                .map(|(id, external)| DependencyNode { id, external })
+                .collect(),
+            edges: edges
+                .into_iter()
+                .map(|((from, to), count)| DependencyEdge { from, to, count })
+                .collect(),
+        }
+    }
+
+    /// Graphviz, external nodes are dashed, edges of more than one import are labeled
+    pub fn to_dot(&self) -> String {
+        let quote = |id: &str| format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""));
+
+        let mut output = String::from("digraph dependencies {\n");
+        for node in self.nodes.iter() {
+            if node.external {
+                output.push_str(&format!("  {} [style=dashed];\n", quote(&node.id)));
+            } else {
+                output.push_str(&format!("  {};\n", quote(&node.id)));
+            }
+        }
+        for edge in self.edges.iter() {
+            let label = if edge.count > 1 {
+                format!(" [label=\"{}\"]", edge.count)
+            } else {
+                String::new()
+            };
+            output.push_str(&format!(
+                "  {} -> {}{};\n",
+                quote(&edge.from),
+                quote(&edge.to),
+                label
+            ));
+        }
+        output.push_str("}\n");
+        output
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self).unwrap()
+    }
+
+    /// `/` separated path relative to the project
+    fn file_id(path: &str, project: &str) -> String {
+        let path = Path::new(path);
+        let relative = path.strip_prefix(project).unwrap_or(path);
+        let parts: Vec<String> = relative
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy().to_string())
+            .collect();
+        parts.join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyser::WorkspaceAssembler;
+    use crate::graph::{DependencyGraph, GraphLevel};
+    use scie_model::artifact::{CodeFile, CodeImport, TokenElement};
+    use std::path::{Path, PathBuf};
+
+    fn file(root: &Path, path: &str, imports: &[&str]) -> CodeFile {
+        let mut file = CodeFile::new(root.join(path));
+        file.elements = vec![TokenElement::new(
+            1,
+            0,
+            0,
+            String::new(),
+            vec![String::from("source.js")],
+        )];
+        file.imports = imports
+            .iter()
+            .map(|source| CodeImport::new(source.to_string(), 1))
+            .collect();
+        file
+    }
+
+    #[test]
+    fn should_build_graph_by_file_and_package() {
+        let root = PathBuf::from("/work/web");
+        let files = vec![
+            file(
+                &root,
+                "src/app.js",
+                &["./util/date", "./util/math", "react"],
+            ),
+            file(&root, "src/util/date.js", &["./math", "moment"]),
+            file(&root, "src/util/math.js", &[]),
+        ];
+        let workspace = WorkspaceAssembler::new(&root, &[], vec![]).assemble(files);
+
+        let graph = DependencyGraph::from_workspace(&workspace, GraphLevel::File);
+        let edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("src/app.js", "react"),
+                ("src/app.js", "src/util/date.js"),
+                ("src/app.js", "src/util/math.js"),
+                ("src/util/date.js", "moment"),
+                ("src/util/date.js", "src/util/math.js"),
+            ],
+            edges
+        );
+        assert_eq!(5, graph.nodes.len());
+        assert!(graph.to_dot().contains("\"react\" [style=dashed];"));
+        assert!(graph
+            .to_dot()
+            .contains("\"src/app.js\" -> \"src/util/date.js\";"));
+
+        let graph = DependencyGraph::from_workspace(&workspace, GraphLevel::Package);
+        let edge = graph
+            .edges
+            .iter()
+            .find(|edge| edge.from == "web:src" && edge.to == "web:src/util")
+            .unwrap();
+        assert_eq!(2, edge.count);
+        assert!(graph.to_json().contains("\"external\": true"));
+
+        let project = &DependencyGraph::project_dependencies(&workspace)[0];
+        let util = project.module("web:src/util").unwrap();
+        assert_eq!("src.util", util.qualified_name());
+        let import = &util.imports[0];
+        assert_eq!(
+            ("src/util/date.js", 1, "web:src/util", "src/util/math.js"),
+            (
+                import.file.as_str(),
+                import.line_num,
+                import.to.as_str(),
+                import.to_file.as_str()
+            )
+        );
+    }
+}
@@ -0,0 +1,492 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use scie_model::artifact::{CodeFile, SymbolKind};
+
+/// what an import points to
+#[derive(PartialEq, Debug, Clone)]
+pub enum ImportTarget {
+    /// relative paths of the files in the workspace, empty if it is a part of the workspace
+    /// which is not analysed
+    Files(Vec<String>),
+    /// the library or package out of the workspace, such as `java.util` or `serde`
+    External(String),
+}
+
+/// Map the imports of a file to the files of the same project, by the conventions of each
+/// language: Java packages, Go dirs, Rust modules, C includes, Python modules and relative
+/// paths of JavaScript. File ids are `/` separated paths relative to the project.
+#[derive(Default)]
+pub struct ImportResolver {
+    files: BTreeSet<String>,
+    /// `com.shop.User` of Java, by the package and the file name
+    classes: BTreeMap<String, String>,
+    /// Java packages, and dirs of Go files
+    packages: BTreeMap<String, Vec<String>>,
+    /// `shop.models` of `src/shop/models.py`, every suffix of the path is a key
+    python_modules: BTreeMap<String, String>,
+}
+
+impl ImportResolver {
+    /// `files` are `(id, file)`, the root scope of elements decides the language
+    pub fn new(files: &[(String, &CodeFile)]) -> Self {
+        let mut resolver = ImportResolver::default();
+        for (id, file) in files.iter() {
+            resolver.files.insert(id.clone());
+            match ImportResolver::root_scope(file) {
+                "source.java" => {
+                    let package = file
+                        .symbols
+                        .iter()
+                        .find(|symbol| symbol.kind == SymbolKind::Package)
+                        .map(|symbol| symbol.name.clone())
+                        .unwrap_or_default();
+                    let class = ImportResolver::stem(id);
+                    let name = if package.is_empty() {
+                        class
+                    } else {
+                        format!("{}.{}", package, class)
+                    };
+                    resolver.classes.insert(name, id.clone());
+                    resolver
+                        .packages
+                        .entry(package)
+                        .or_default()
+                        .push(id.clone());
+                }
+                "source.go" => {
+                    let dir = ImportResolver::dir(id);
+                    resolver.packages.entry(dir).or_default().push(id.clone());
+                }
+                "source.python" => {
+                    let module = id.trim_end_matches(".py").trim_end_matches("/__init__");
+                    let parts: Vec<&str> = module.split('/').collect();
+                    for index in 0..parts.len() {
+                        resolver
+                            .python_modules
+                            .entry(parts[index..].join("."))
+                            .or_insert_with(|| id.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        resolver
+    }
+
+    pub fn root_scope(file: &CodeFile) -> &str {
+        file.elements
+            .first()
+            .and_then(|element| element.scopes.first())
+            .map(|scope| scope.as_str())
+            .unwrap_or_default()
+    }
+
+    /// `id` of the file which imports `source`, `root` is its root scope
+    pub fn resolve(&self, id: &str, root: &str, source: &str) -> ImportTarget {
+        match root {
+            "source.java" => self.java(source),
+            "source.go" => self.go(source),
+            "source.rust" => self.rust(id, source),
+            "source.c" | "source.cpp" => self.c(id, source),
+            "source.python" => self.python(id, source),
+            "source.js" | "source.js.jsx" | "source.ts" | "source.tsx" => {
+                self.javascript(id, source)
+            }
+            "source.groovy" if source.starts_with(':') => self.gradle(source),
+            _ => ImportTarget::External(source.to_string()),
+        }
+    }
+
+    /// `a.b.*`, `a.b.Class` or `a.b.Class.member` of a static import
+    fn java(&self, source: &str) -> ImportTarget {
+        if let Some(package) = source.strip_suffix(".*") {
+            return match self.packages.get(package) {
+                Some(files) => ImportTarget::Files(files.clone()),
+                None => ImportTarget::External(package.to_string()),
+            };
+        }
+
+        if let Some(id) = self.classes.get(source) {
+            return ImportTarget::Files(vec![id.clone()]);
+        }
+
+        let parent = ImportResolver::parent(source, '.');
+        match self.classes.get(parent) {
+            Some(id) => ImportTarget::Files(vec![id.clone()]),
+            None => ImportTarget::External(parent.to_string()),
+        }
+    }
+
+    /// the longest dir which is a suffix of the import path, such as `pkg/util` of
+    /// `github.com/a/b/pkg/util`
+    fn go(&self, source: &str) -> ImportTarget {
+        let matched = self
+            .packages
+            .iter()
+            .filter(|(dir, _)| {
+                !dir.is_empty() && (source == *dir || source.ends_with(&format!("/{}", dir)))
+            })
+            .max_by_key(|(dir, _)| dir.len());
+
+        match matched {
+            Some((_, files)) => ImportTarget::Files(files.clone()),
+            None => ImportTarget::External(source.to_string()),
+        }
+    }
+
+    /// `crate::a::b::C`, `self::a`, `super::a` or `a::B` of a child module `a`, the longest
+    /// module path which is a file
+    fn rust(&self, id: &str, source: &str) -> ImportTarget {
+        let segments: Vec<&str> = source.split("::").collect();
+        let (base, is_crate_root, segments) = match segments[0] {
+            "crate" => (ImportResolver::crate_dir(id), true, &segments[1..]),
+            "self" => (ImportResolver::module_dir(id), false, &segments[1..]),
+            "super" => (
+                ImportResolver::dir(&ImportResolver::module_dir(id)),
+                false,
+                &segments[1..],
+            ),
+            // a child module, such as `model::Cart` in `main.rs` since the 2018 edition
+            name if self
+                .rust_module(&ImportResolver::module_dir(id), name)
+                .is_some() =>
+            {
+                (ImportResolver::module_dir(id), false, &segments[..])
+            }
+            name => return ImportTarget::External(name.to_string()),
+        };
+
+        for count in (1..=segments.len()).rev() {
+            if let Some(file) = self.rust_module(&base, &segments[..count].join("/")) {
+                return ImportTarget::Files(vec![file]);
+            }
+        }
+
+        // an item of the module itself
+        let roots = if is_crate_root {
+            vec![
+                ImportResolver::join(&base, "lib.rs"),
+                ImportResolver::join(&base, "main.rs"),
+            ]
+        } else {
+            vec![
+                ImportResolver::join(&base, "mod.rs"),
+                format!("{}.rs", base),
+            ]
+        };
+        let files = roots
+            .into_iter()
+            .find(|root| self.files.contains(root))
+            .into_iter()
+            .collect();
+        ImportTarget::Files(files)
+    }
+
+    /// `a.rs` or `a/mod.rs` in `base`
+    fn rust_module(&self, base: &str, path: &str) -> Option<String> {
+        let module = ImportResolver::join(base, path);
+        vec![format!("{}.rs", module), format!("{}/mod.rs", module)]
+            .into_iter()
+            .find(|candidate| self.files.contains(candidate))
+    }
+
+    /// relative to the file first, then any file which ends with the path
+    fn c(&self, id: &str, source: &str) -> ImportTarget {
+        let relative = ImportResolver::join(&ImportResolver::dir(id), source);
+        if self.files.contains(&relative) {
+            return ImportTarget::Files(vec![relative]);
+        }
+
+        let suffix = format!("/{}", source);
+        let matched = self
+            .files
+            .iter()
+            .find(|file| *file == source || file.ends_with(&suffix));
+        match matched {
+            Some(file) => ImportTarget::Files(vec![file.clone()]),
+            None => ImportTarget::External(source.to_string()),
+        }
+    }
+
+    /// `..models` is relative to the package of the file, `a.b` is any module path which ends
+    /// with it
+    fn python(&self, id: &str, source: &str) -> ImportTarget {
+        let module = source.trim_start_matches('.');
+        let dots = source.len() - module.len();
+        if dots == 0 {
+            return match self.python_modules.get(source) {
+                Some(file) => ImportTarget::Files(vec![file.clone()]),
+                None => {
+                    ImportTarget::External(source.split('.').next().unwrap_or(source).to_string())
+                }
+            };
+        }
+
+        let mut base = ImportResolver::dir(id);
+        for _ in 1..dots {
+            base = ImportResolver::dir(&base);
+        }
+        let path = ImportResolver::join(&base, &module.replace('.', "/"));
+        let candidates = vec![
+            format!("{}.py", path),
+            ImportResolver::join(&path, "__init__.py"),
+        ];
+        let files = candidates
+            .into_iter()
+            .find(|candidate| self.files.contains(candidate))
+            .into_iter()
+            .collect();
+        ImportTarget::Files(files)
+    }
+
+    /// `./a` and `../a` are files, with or without extension, others are packages of npm
+    fn javascript(&self, id: &str, source: &str) -> ImportTarget {
+        if !source.starts_with('.') {
+            let mut parts = source.split('/');
+            let first = parts.next().unwrap_or_default();
+            let name = match (first.starts_with('@'), parts.next()) {
+                (true, Some(second)) => format!("{}/{}", first, second),
+                _ => first.to_string(),
+            };
+            return ImportTarget::External(name);
+        }
+
+        let path = ImportResolver::join(&ImportResolver::dir(id), source);
+        let suffixes = [
+            "",
+            ".js",
+            ".jsx",
+            ".ts",
+            ".tsx",
+            ".mjs",
+            "/index.js",
+            "/index.jsx",
+            "/index.ts",
+            "/index.tsx",
+        ];
+        let files = suffixes
+            .iter()
+            .map(|suffix| format!("{}{}", path, suffix))
+            .find(|candidate| self.files.contains(candidate))
+            .into_iter()
+            .collect();
+        ImportTarget::Files(files)
+    }
+
+    /// `:a:b` of `project(':a:b')` in a Gradle script is the script of `a/b`, relative to the
+    /// project
+    fn gradle(&self, source: &str) -> ImportTarget {
+        let dir = source.trim_start_matches(':').replace(':', "/");
+        let files = ["build.gradle", "build.gradle.kts"]
+            .iter()
+            .map(|name| ImportResolver::join(&dir, name))
+            .find(|candidate| self.files.contains(candidate))
+            .into_iter()
+            .collect();
+        ImportTarget::Files(files)
+    }
+
+    /// the nearest `src` dir above the file, which `crate::` starts from
+    fn crate_dir(id: &str) -> String {
+        let parts: Vec<&str> = id.split('/').collect();
+        match parts.iter().rposition(|part| *part == "src") {
+            Some(index) if index + 1 < parts.len() => parts[..=index].join("/"),
+            _ => ImportResolver::dir(id),
+        }
+    }
+
+    /// children of `a/b.rs` are in `a/b/`, children of `mod.rs`, `lib.rs` and `main.rs` are
+    /// next to them
+    fn module_dir(id: &str) -> String {
+        let file = id.rsplit('/').next().unwrap_or(id);
+        match file {
+            "mod.rs" | "lib.rs" | "main.rs" => ImportResolver::dir(id),
+            _ => id.trim_end_matches(".rs").to_string(),
+        }
+    }
+
+    fn dir(id: &str) -> String {
+        match id.rfind('/') {
+            Some(index) => id[..index].to_string(),
+            None => String::new(),
+        }
+    }
+
+    fn stem(id: &str) -> String {
+        Path::new(id)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    fn parent(name: &str, separator: char) -> &str {
+        match name.rfind(separator) {
+            Some(index) => &name[..index],
+            None => name,
+        }
+    }
+
+    /// `/` separated, `.` and `..` are resolved
+    fn join(base: &str, relative: &str) -> String {
+        let mut parts: Vec<&str> = base.split('/').filter(|part| !part.is_empty()).collect();
+        for part in relative.split('/') {
+            match part {
+                "" | "." => {}
+                ".." => {
+                    parts.pop();
+                }
+                _ => parts.push(part),
+            }
+        }
+        parts.join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{ImportResolver, ImportTarget};
+    use scie_model::artifact::{CodeFile, CodeSymbol, SymbolKind, TokenElement};
+    use std::path::PathBuf;
+
+    fn file(id: &str, root: &str) -> CodeFile {
+        let mut file = CodeFile::new(PathBuf::from(id));
+        file.elements = vec![TokenElement::new(
+            1,
+            0,
+            0,
+            String::new(),
+            vec![root.to_string()],
+        )];
+        file
+    }
+
+    fn files(id: &str) -> ImportTarget {
+        ImportTarget::Files(vec![id.to_string()])
+    }
+
+    #[test]
+    fn should_resolve_java_and_go_packages() {
+        let mut user = file("app/src/User.java", "source.java");
+        user.symbols = vec![CodeSymbol::new(
+            String::from("com.shop"),
+            SymbolKind::Package,
+            1,
+        )];
+        let util = file("pkg/util/util.go", "source.go");
+        let resolver = ImportResolver::new(&[
+            (String::from("app/src/User.java"), &user),
+            (String::from("pkg/util/util.go"), &util),
+        ]);
+
+        let java = |source: &str| resolver.resolve("Main.java", "source.java", source);
+        assert_eq!(files("app/src/User.java"), java("com.shop.User"));
+        assert_eq!(files("app/src/User.java"), java("com.shop.User.NAME"));
+        assert_eq!(files("app/src/User.java"), java("com.shop.*"));
+        assert_eq!(
+            ImportTarget::External(String::from("java.util")),
+            java("java.util.List")
+        );
+
+        let go = |source: &str| resolver.resolve("main.go", "source.go", source);
+        assert_eq!(files("pkg/util/util.go"), go("github.com/shop/pkg/util"));
+        assert_eq!(ImportTarget::External(String::from("fmt")), go("fmt"));
+    }
+
+    #[test]
+    fn should_resolve_paths_of_rust_c_python_and_javascript() {
+        let ids = [
+            ("src/lib.rs", "source.rust"),
+            ("src/analyser/mod.rs", "source.rust"),
+            ("src/analyser/identify.rs", "source.rust"),
+            ("include/list.h", "source.c"),
+            ("shop/__init__.py", "source.python"),
+            ("shop/models.py", "source.python"),
+            ("web/app.js", "source.js"),
+            ("web/util/index.js", "source.js"),
+        ];
+        let code_files: Vec<CodeFile> = ids.iter().map(|(id, root)| file(id, root)).collect();
+        let entries: Vec<(String, &CodeFile)> = ids
+            .iter()
+            .zip(code_files.iter())
+            .map(|((id, _), file)| (id.to_string(), file))
+            .collect();
+        let resolver = ImportResolver::new(&entries);
+
+        let rust = |id: &str, source: &str| resolver.resolve(id, "source.rust", source);
+        assert_eq!(
+            files("src/analyser/identify.rs"),
+            rust("src/lib.rs", "crate::analyser::identify::Identify")
+        );
+        assert_eq!(
+            files("src/analyser/mod.rs"),
+            rust("src/lib.rs", "crate::analyser::Analyser")
+        );
+        assert_eq!(
+            files("src/analyser/mod.rs"),
+            rust("src/analyser/identify.rs", "crate::analyser")
+        );
+        assert_eq!(
+            files("src/analyser/identify.rs"),
+            rust("src/lib.rs", "analyser::identify::Identify")
+        );
+        assert_eq!(
+            files("src/analyser/identify.rs"),
+            rust("src/analyser/mod.rs", "identify::Identify")
+        );
+        assert_eq!(
+            ImportTarget::External(String::from("identify")),
+            rust("src/lib.rs", "identify::Identify")
+        );
+        assert_eq!(
+            files("src/analyser/mod.rs"),
+            rust("src/analyser/identify.rs", "super::Analyser")
+        );
+        assert_eq!(
+            ImportTarget::External(String::from("std")),
+            rust("src/lib.rs", "std::path::Path")
+        );
+
+        assert_eq!(
+            files("include/list.h"),
+            resolver.resolve("src/main.c", "source.c", "list.h")
+        );
+        assert_eq!(
+            files("shop/__init__.py"),
+            resolver.resolve("shop/views.py", "source.python", ".")
+        );
+        assert_eq!(
+            files("shop/models.py"),
+            resolver.resolve("main.py", "source.python", "shop.models")
+        );
+        assert_eq!(
+            files("web/util/index.js"),
+            resolver.resolve("web/app.js", "source.js", "./util")
+        );
+        assert_eq!(
+            ImportTarget::External(String::from("@babel/core")),
+            resolver.resolve("web/app.js", "source.js", "@babel/core/lib")
+        );
+    }
+
+    #[test]
+    fn should_resolve_gradle_projects_to_their_scripts() {
+        let ids = ["build.gradle", "libs/core/build.gradle"];
+        let code_files: Vec<CodeFile> = ids.iter().map(|id| file(id, "source.groovy")).collect();
+        let entries: Vec<(String, &CodeFile)> = ids
+            .iter()
+            .zip(code_files.iter())
+            .map(|(id, file)| (id.to_string(), file))
+            .collect();
+        let resolver = ImportResolver::new(&entries);
+
+        let gradle = |source: &str| resolver.resolve("app/build.gradle", "source.groovy", source);
+        assert_eq!(files("libs/core/build.gradle"), gradle(":libs:core"));
+        assert_eq!(ImportTarget::Files(vec![]), gradle(":web"));
+        assert_eq!(
+            ImportTarget::External(String::from("java.util.List")),
+            gradle("java.util.List")
+        );
+    }
+}
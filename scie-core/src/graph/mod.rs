@@ -0,0 +1,5 @@
+pub mod dependency_graph;
+pub mod import_resolver;
+
+pub use dependency_graph::{DependencyEdge, DependencyGraph, DependencyNode, GraphLevel};
+pub use import_resolver::{ImportResolver, ImportTarget};
@@ -6,4 +6,7 @@ extern crate serde;
 extern crate serde_derive;
 
 pub mod analyser;
+pub mod build;
+pub mod graph;
 pub mod sima;
+pub mod stats;
@@ -0,0 +1,314 @@
+use std::collections::BTreeMap;
+use std::ops::AddAssign;
+use std::path::Path;
+
+use scie_infra::format_output::FormatOutput;
+use scie_model::artifact::{CodeFile, TokenElement};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum LineKind {
+    Blank,
+    Comment,
+    Code,
+    /// code and comment in one line, such as `int a = 1; // one`
+    Mixed,
+}
+
+/// how to count a line with code and comment
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub enum MixedLinePolicy {
+    /// same as tokei and cloc
+    #[default]
+    Code,
+    Comment,
+    /// count it as a code line and a comment line
+    Both,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum StatsFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct LineStats {
+    pub files: usize,
+    pub lines: usize,
+    pub code: usize,
+    pub comments: usize,
+    pub blanks: usize,
+}
+
+impl AddAssign<&LineStats> for LineStats {
+    fn add_assign(&mut self, other: &LineStats) {
+        self.files += other.files;
+        self.lines += other.lines;
+        self.code += other.code;
+        self.comments += other.comments;
+        self.blanks += other.blanks;
+    }
+}
+
+impl LineStats {
+    /// lines of a tokenized file, a line is a comment line if all of its tokens, except
+    /// whitespaces, are in a `comment.*` scope
+    pub fn from_code_file(file: &CodeFile, policy: MixedLinePolicy) -> LineStats {
+        let mut lines: BTreeMap<i32, Vec<&TokenElement>> = Default::default();
+        for element in file.elements.iter() {
+            lines.entry(element.line_num).or_default().push(element);
+        }
+
+        let line_count = file.elements.iter().map(|e| e.line_num).max().unwrap_or(0);
+        let mut stats = LineStats {
+            files: 1,
+            ..Default::default()
+        };
+        for line_num in 1..=line_count {
+            stats.lines += 1;
+            let kind = match lines.get(&line_num) {
+                None => LineKind::Blank,
+                Some(elements) => LineStats::line_kind(elements),
+            };
+
+            match (kind, policy) {
+                (LineKind::Blank, _) => stats.blanks += 1,
+                (LineKind::Comment, _) | (LineKind::Mixed, MixedLinePolicy::Comment) => {
+                    stats.comments += 1
+                }
+                (LineKind::Code, _) | (LineKind::Mixed, MixedLinePolicy::Code) => stats.code += 1,
+                (LineKind::Mixed, MixedLinePolicy::Both) => {
+                    stats.code += 1;
+                    stats.comments += 1;
+                }
+            }
+        }
+
+        stats
+    }
+
+    pub fn line_kind(elements: &[&TokenElement]) -> LineKind {
+        let mut has_code = false;
+        let mut has_comment = false;
+        for element in elements.iter() {
+            if element.value.trim().is_empty() {
+                continue;
+            }
+
+            let is_comment = element
+                .scopes
+                .iter()
+                .any(|scope| scope.starts_with("comment."));
+            if is_comment {
+                has_comment = true;
+            } else {
+                has_code = true;
+            }
+        }
+
+        match (has_code, has_comment) {
+            (false, false) => LineKind::Blank,
+            (false, true) => LineKind::Comment,
+            (true, false) => LineKind::Code,
+            (true, true) => LineKind::Mixed,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct FileStats {
+    pub path: String,
+    pub language: String,
+    pub stats: LineStats,
+}
+
+/// Line statistics of tokenized files, by file, language and dir.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct StatsReport {
+    pub files: Vec<FileStats>,
+    pub languages: BTreeMap<String, LineStats>,
+    /// by the parent dir of files, with the files of sub dirs rolled up to their ancestors
+    pub dirs: BTreeMap<String, LineStats>,
+    pub total: LineStats,
+}
+
+impl StatsReport {
+    /// by the languages which the files are tokenized by, such as the ones of user extensions
+    pub fn from_files(files: &[CodeFile], policy: MixedLinePolicy) -> StatsReport {
+        let mut report = StatsReport::default();
+        for file in files.iter() {
+            let path = Path::new(&file.path);
+            let language = if file.language.is_empty() {
+                String::from("unknown")
+            } else {
+                file.language.clone()
+            };
+            let dir = path
+                .parent()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default();
+
+            report.add(file, language, dir, policy);
+        }
+
+        report
+    }
+
+    /// `dir` is the parent dir of `file`, which is counted in the ancestors of `dir` too
+    pub fn add(&mut self, file: &CodeFile, language: String, dir: String, policy: MixedLinePolicy) {
+        let stats = LineStats::from_code_file(file, policy);
+
+        *self.languages.entry(language.clone()).or_default() += &stats;
+        for ancestor in Path::new(&dir).ancestors() {
+            let ancestor = ancestor.display().to_string();
+            if !ancestor.is_empty() {
+                *self.dirs.entry(ancestor).or_default() += &stats;
+            }
+        }
+        self.total += &stats;
+        self.files.push(FileStats {
+            path: file.path.clone(),
+            language,
+            stats,
+        });
+    }
+
+    pub fn render(&self, format: StatsFormat) -> String {
+        match format {
+            StatsFormat::Json => serde_json::to_string_pretty(&self).unwrap(),
+            StatsFormat::Table => {
+                let mut rows = StatsReport::rows(&self.languages);
+                rows.push(StatsReport::row("total", &self.total));
+                FormatOutput::table(&StatsReport::headers("language"), &rows)
+            }
+            StatsFormat::Csv => {
+                let rows: Vec<Vec<String>> = self
+                    .files
+                    .iter()
+                    .map(|file| {
+                        let mut row = StatsReport::row(&file.path, &file.stats);
+                        row.insert(1, file.language.clone());
+                        row
+                    })
+                    .collect();
+                let mut headers = StatsReport::headers("path");
+                headers.insert(1, "language");
+                FormatOutput::csv(&headers, &rows)
+            }
+        }
+    }
+
+    fn headers(name: &str) -> Vec<&str> {
+        vec![name, "files", "lines", "code", "comments", "blanks"]
+    }
+
+    fn rows(map: &BTreeMap<String, LineStats>) -> Vec<Vec<String>> {
+        map.iter()
+            .map(|(name, stats)| StatsReport::row(name, stats))
+            .collect()
+    }
+
+    fn row(name: &str, stats: &LineStats) -> Vec<String> {
+        vec![
+            name.to_string(),
+            stats.files.to_string(),
+            stats.lines.to_string(),
+            stats.code.to_string(),
+            stats.comments.to_string(),
+            stats.blanks.to_string(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stats::{LineStats, MixedLinePolicy, StatsFormat, StatsReport};
+    use scie_model::artifact::{CodeFile, TokenElement};
+    use std::path::PathBuf;
+
+    fn token(line_num: i32, value: &str, scope: &str) -> TokenElement {
+        TokenElement::new(
+            line_num,
+            0,
+            value.len() as i32,
+            value.to_string(),
+            vec![String::from("source.java"), scope.to_string()],
+        )
+    }
+
+    fn java_file() -> CodeFile {
+        let mut file = CodeFile::new(PathBuf::from("src/Hello.java"));
+        file.language = String::from("java");
+        file.elements = vec![
+            token(1, "/** doc */", "comment.block.javadoc.java"),
+            token(2, "class", "storage.modifier.java"),
+            token(3, "    ", "meta.class.body.java"),
+            token(4, "int a = 1;", "meta.class.body.java"),
+            token(4, "// one", "comment.line.double-slash.java"),
+            token(5, "}", "punctuation.section.class.end.java"),
+        ];
+        file
+    }
+
+    #[test]
+    fn should_aggregate_by_language_of_files() {
+        let mut user = java_file();
+        user.language = String::from("acme-java");
+        let mut unknown = java_file();
+        unknown.language = String::new();
+
+        let report = StatsReport::from_files(&[java_file(), user, unknown], MixedLinePolicy::Code);
+        let languages: Vec<&str> = report.languages.keys().map(|lang| lang.as_str()).collect();
+        assert_eq!(vec!["acme-java", "java", "unknown"], languages);
+    }
+
+    #[test]
+    fn should_roll_up_dirs_to_ancestors() {
+        let mut nested = java_file();
+        nested.path = String::from("src/main/java/Hello.java");
+        let report = StatsReport::from_files(&[java_file(), nested], MixedLinePolicy::Code);
+
+        let dirs: Vec<(&str, usize)> = report
+            .dirs
+            .iter()
+            .map(|(dir, stats)| (dir.as_str(), stats.files))
+            .collect();
+        assert_eq!(
+            vec![("src", 2), ("src/main", 1), ("src/main/java", 1)],
+            dirs
+        );
+        assert_eq!(report.total, report.dirs["src"]);
+    }
+
+    #[test]
+    fn should_count_lines_by_scopes() {
+        let stats = LineStats::from_code_file(&java_file(), MixedLinePolicy::Code);
+        assert_eq!(5, stats.lines);
+        assert_eq!(3, stats.code);
+        assert_eq!(1, stats.comments);
+        assert_eq!(1, stats.blanks);
+    }
+
+    #[test]
+    fn should_count_mixed_lines_by_policy() {
+        let stats = LineStats::from_code_file(&java_file(), MixedLinePolicy::Comment);
+        assert_eq!((2, 2), (stats.code, stats.comments));
+
+        let stats = LineStats::from_code_file(&java_file(), MixedLinePolicy::Both);
+        assert_eq!((3, 2), (stats.code, stats.comments));
+    }
+
+    #[test]
+    fn should_aggregate_by_language_and_dir() {
+        let report = StatsReport::from_files(&[java_file(), java_file()], MixedLinePolicy::Code);
+        assert_eq!(2, report.languages["java"].files);
+        assert_eq!(6, report.dirs["src"].code);
+        assert_eq!(10, report.total.lines);
+
+        assert!(report.render(StatsFormat::Table).contains("java"));
+        assert!(report
+            .render(StatsFormat::Csv)
+            .starts_with("path,language,files"));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod line_stats;
+
+pub use line_stats::{FileStats, LineKind, LineStats, MixedLinePolicy, StatsFormat, StatsReport};
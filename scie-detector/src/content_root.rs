@@ -1,26 +1,295 @@
-#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+use std::path::Path;
+
+use scie_model::artifact::SourceKind;
+
+/// dirs which build tools use by default, the ones which exist in a module are its content root
+pub struct Convention {
+    /// prefix of the tags, such as `workspace.java` for both Gradle and Maven
+    pub tag: &'static str,
+    pub source_dirs: &'static [&'static str],
+    pub test_source_dirs: &'static [&'static str],
+    pub resource_dirs: &'static [&'static str],
+    pub gen_source_dirs: &'static [&'static str],
+    pub exclude_dirs: &'static [&'static str],
+}
+
+/// an empty dir is the module itself, for the languages which keep sources beside build files
+pub const CONVENTIONS: [Convention; 9] = [
+    Convention {
+        tag: "workspace.java",
+        source_dirs: &[
+            "src/main/java",
+            "src/main/kotlin",
+            "src/main/groovy",
+            "src/main/scala",
+        ],
+        test_source_dirs: &[
+            "src/test/java",
+            "src/test/kotlin",
+            "src/test/groovy",
+            "src/test/scala",
+        ],
+        resource_dirs: &["src/main/resources", "src/test/resources"],
+        gen_source_dirs: &[
+            "build/generated",
+            "target/generated-sources",
+            "target/generated-test-sources",
+        ],
+        exclude_dirs: &["build", "target", ".gradle"],
+    },
+    Convention {
+        tag: "workspace.rust.cargo",
+        source_dirs: &["src", "examples"],
+        test_source_dirs: &["tests", "benches"],
+        resource_dirs: &[],
+        gen_source_dirs: &[],
+        exclude_dirs: &["target"],
+    },
+    Convention {
+        tag: "workspace.go",
+        source_dirs: &[""],
+        test_source_dirs: &[],
+        resource_dirs: &["testdata"],
+        gen_source_dirs: &[],
+        exclude_dirs: &["vendor"],
+    },
+    Convention {
+        tag: "workspace.npm",
+        source_dirs: &["src", "lib"],
+        test_source_dirs: &["test", "tests", "spec", "e2e"],
+        resource_dirs: &["public", "static", "assets", "src/assets"],
+        gen_source_dirs: &[],
+        exclude_dirs: &["node_modules", "dist", "build", "coverage"],
+    },
+    Convention {
+        tag: "workspace.python",
+        source_dirs: &["", "src"],
+        test_source_dirs: &["test", "tests"],
+        resource_dirs: &[],
+        gen_source_dirs: &[],
+        exclude_dirs: &["build", "dist", ".venv", "venv", ".tox"],
+    },
+    Convention {
+        tag: "workspace.php.composer",
+        source_dirs: &["src", "app", "lib"],
+        test_source_dirs: &["tests", "test"],
+        resource_dirs: &["resources", "public", "config"],
+        gen_source_dirs: &[],
+        exclude_dirs: &["vendor", "var"],
+    },
+    Convention {
+        tag: "workspace.ruby.bundler",
+        source_dirs: &["lib", "app"],
+        test_source_dirs: &["spec", "test"],
+        resource_dirs: &["config", "public"],
+        gen_source_dirs: &[],
+        exclude_dirs: &["vendor", "tmp"],
+    },
+    Convention {
+        tag: "workspace.dotnet",
+        source_dirs: &[""],
+        test_source_dirs: &[],
+        resource_dirs: &["wwwroot", "Properties"],
+        gen_source_dirs: &["obj"],
+        exclude_dirs: &["bin"],
+    },
+    Convention {
+        tag: "workspace.c",
+        source_dirs: &["src", "include", "lib"],
+        test_source_dirs: &["test", "tests"],
+        resource_dirs: &[],
+        gen_source_dirs: &[],
+        exclude_dirs: &["build", "cmake-build-debug", "cmake-build-release"],
+    },
+];
+
+/// dirs of a module by what their files are for, relative to the root of the module or
+/// of its build, such as the reactor of Maven
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 pub struct ContentRoot {
     pub root_dirs: Vec<String>,
     pub source_dirs: Vec<String>,
     pub gen_source_dirs: Vec<String>,
     pub resource_dirs: Vec<String>,
     pub test_source_dirs: Vec<String>,
-    pub get_test_dirs: Vec<String>,
-    pub get_test_source_dirs: Vec<String>,
     pub exclude_dirs: Vec<String>,
 }
 
-impl Default for ContentRoot {
-    fn default() -> Self {
+impl ContentRoot {
+    /// the conventional dirs of `tag` which exist in `dir`, relative to it
+    pub fn infer(tag: &str, dir: &Path) -> ContentRoot {
+        let convention = match CONVENTIONS
+            .iter()
+            .find(|convention| tag.starts_with(convention.tag))
+        {
+            Some(convention) => convention,
+            None => {
+                return ContentRoot {
+                    root_dirs: vec!["".to_string()],
+                    source_dirs: vec!["".to_string()],
+                    ..Default::default()
+                }
+            }
+        };
+
+        let existing = |dirs: &[&str]| -> Vec<String> {
+            dirs.iter()
+                .filter(|it| it.is_empty() || dir.join(it).is_dir())
+                .map(|it| it.to_string())
+                .collect()
+        };
+        ContentRoot {
+            root_dirs: vec!["".to_string()],
+            source_dirs: existing(convention.source_dirs),
+            gen_source_dirs: existing(convention.gen_source_dirs),
+            resource_dirs: existing(convention.resource_dirs),
+            test_source_dirs: existing(convention.test_source_dirs),
+            exclude_dirs: existing(convention.exclude_dirs),
+        }
+    }
+
+    /// the dirs are relative to `base` instead, such as the ones of a Maven module
+    /// in a reactor, the dirs out of `base` are left out
+    pub fn relative_to(&self, base: &str) -> ContentRoot {
+        let relative = |dirs: &Vec<String>| -> Vec<String> {
+            dirs.iter()
+                .filter_map(|dir| {
+                    let dir = Path::new(dir).strip_prefix(base).ok()?;
+                    Some(dir.to_string_lossy().replace('\\', "/"))
+                })
+                .collect()
+        };
         ContentRoot {
-            root_dirs: vec![],
-            source_dirs: vec![],
-            gen_source_dirs: vec![],
-            resource_dirs: vec![],
-            test_source_dirs: vec![],
-            get_test_dirs: vec![],
-            get_test_source_dirs: vec![],
-            exclude_dirs: vec![],
+            root_dirs: relative(&self.root_dirs),
+            source_dirs: relative(&self.source_dirs),
+            gen_source_dirs: relative(&self.gen_source_dirs),
+            resource_dirs: relative(&self.resource_dirs),
+            test_source_dirs: relative(&self.test_source_dirs),
+            exclude_dirs: relative(&self.exclude_dirs),
         }
     }
+
+    /// in one of `gen_source_dirs`, such as `target/generated-sources` of Maven
+    pub fn is_generated(&self, path: &str) -> bool {
+        ContentRoot::is_in(&self.gen_source_dirs, path)
+    }
+
+    /// in one of `exclude_dirs`, such as `node_modules` or `target`
+    pub fn is_excluded(&self, path: &str) -> bool {
+        ContentRoot::is_in(&self.exclude_dirs, path)
+    }
+
+    /// main code in one of the `source_dirs` which are named, such as `src/main/java`, but not
+    /// the module itself as the empty dir of Go or Python
+    pub fn is_in_source_dirs(&self, path: &str) -> bool {
+        self.kind_of(path) == SourceKind::Main && ContentRoot::is_in(&self.source_dirs, path)
+    }
+
+    fn is_in(dirs: &[String], path: &str) -> bool {
+        let path = Path::new(path);
+        dirs.iter()
+            .any(|dir| !dir.is_empty() && path.starts_with(dir))
+    }
+
+    /// by the deepest dir which has the file, the outputs in `exclude_dirs` are generated, Go
+    /// keeps tests beside sources as `_test.go`, and Jest in `__tests__` of any dir
+    pub fn kind_of(&self, path: &str) -> SourceKind {
+        let path = Path::new(path);
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        if name.ends_with("_test.go") || path.components().any(|it| it.as_os_str() == "__tests__") {
+            return SourceKind::Test;
+        }
+
+        let kinds = [
+            (&self.source_dirs, SourceKind::Main),
+            (&self.test_source_dirs, SourceKind::Test),
+            (&self.resource_dirs, SourceKind::Resource),
+            (&self.gen_source_dirs, SourceKind::Generated),
+            (&self.exclude_dirs, SourceKind::Generated),
+        ];
+        kinds
+            .iter()
+            .flat_map(|(dirs, kind)| dirs.iter().map(move |dir| (dir, *kind)))
+            .filter(|(dir, _)| path.starts_with(dir))
+            .max_by_key(|(dir, _)| Path::new(dir).components().count())
+            .map(|(_, kind)| kind)
+            .unwrap_or(SourceKind::Main)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::content_root::ContentRoot;
+    use scie_model::artifact::SourceKind;
+    use std::path::PathBuf;
+
+    fn fixture() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("polyglot")
+    }
+
+    #[test]
+    fn should_infer_existing_conventional_dirs() {
+        let backend = ContentRoot::infer("workspace.java.gradle", &fixture().join("backend"));
+        assert_eq!(
+            vec!["src/main/java", "src/main/kotlin"],
+            backend.source_dirs
+        );
+        assert_eq!(vec!["src/test/java"], backend.test_source_dirs);
+        assert_eq!(vec!["src/main/resources"], backend.resource_dirs);
+
+        let tools = ContentRoot::infer("workspace.rust.cargo", &fixture().join("tools"));
+        assert_eq!(vec!["src"], tools.source_dirs);
+        assert_eq!(vec!["tests"], tools.test_source_dirs);
+
+        let frontend = ContentRoot::infer("workspace.npm", &fixture().join("frontend"));
+        assert_eq!(vec!["node_modules"], frontend.exclude_dirs);
+    }
+
+    #[test]
+    fn should_tag_files_by_the_deepest_dir() {
+        let root = ContentRoot {
+            root_dirs: vec!["".to_string()],
+            source_dirs: vec!["".to_string(), "src".to_string()],
+            test_source_dirs: vec!["tests".to_string()],
+            resource_dirs: vec!["src/assets".to_string()],
+            exclude_dirs: vec!["build".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(SourceKind::Main, root.kind_of("manage.py"));
+        assert_eq!(SourceKind::Main, root.kind_of("src/app.js"));
+        assert_eq!(SourceKind::Test, root.kind_of("tests/test_cart.py"));
+        assert_eq!(SourceKind::Resource, root.kind_of("src/assets/logo.svg"));
+        assert_eq!(SourceKind::Generated, root.kind_of("build/app.js"));
+        assert_eq!(SourceKind::Test, root.kind_of("src/__tests__/app.js"));
+        assert_eq!(SourceKind::Test, root.kind_of("cart_test.go"));
+
+        assert!(root.is_in_source_dirs("src/app.js"));
+        assert!(!root.is_in_source_dirs("manage.py"));
+        assert!(!root.is_in_source_dirs("src/assets/logo.svg"));
+        assert!(!root.is_in_source_dirs("src/__tests__/app.js"));
+    }
+
+    #[test]
+    fn should_rebase_dirs_of_reactor_modules() {
+        let root = ContentRoot {
+            root_dirs: vec!["core".to_string()],
+            source_dirs: vec!["core/src/main/java".to_string()],
+            exclude_dirs: vec!["target".to_string()],
+            ..Default::default()
+        };
+
+        let relative = root.relative_to("core");
+        assert_eq!(vec![""], relative.root_dirs);
+        assert_eq!(vec!["src/main/java"], relative.source_dirs);
+        assert!(relative.exclude_dirs.is_empty());
+    }
 }
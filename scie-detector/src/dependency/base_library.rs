@@ -7,14 +7,27 @@ pub struct BaseLibrary {
     pub scope: String,
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
 pub enum LibraryScope {
+    Main,
+    /// only to build or develop, such as `compileOnly` of Gradle
     Dev,
     Test,
 }
 
+impl LibraryScope {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LibraryScope::Main => "Main",
+            LibraryScope::Dev => "Dev",
+            LibraryScope::Test => "Test",
+        }
+    }
+}
+
 impl BaseLibrary {
     pub fn is_dev(&self) -> bool {
-        return self.scope == "Test";
+        self.scope == LibraryScope::Dev.name() || self.scope == LibraryScope::Test.name()
     }
 }
 
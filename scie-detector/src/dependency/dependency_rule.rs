@@ -0,0 +1,260 @@
+use std::fs;
+use std::path::Path;
+
+use crate::dependency::project_dependency::ProjectDependency;
+
+/// `from` packages must not import `forbidden` ones. Patterns are the same as ArchUnit: `..` is
+/// any number of packages and `*` is any part of a name, such as `..controller..` or
+/// `com.shop.*.domain..`, they match package names in dotted form or external libraries.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct LayerRule {
+    #[serde(default)]
+    pub name: String,
+    pub from: String,
+    pub forbidden: Vec<String>,
+}
+
+/// Layering rules of a project, from a JSON config, such as
+/// `{ "no_cycles": true, "layers": [{ "from": "..domain..", "forbidden": ["..controller.."] }] }`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct DependencyRules {
+    /// packages must not import each other, directly or not
+    #[serde(default)]
+    pub no_cycles: bool,
+    #[serde(default)]
+    pub layers: Vec<LayerRule>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct RuleViolation {
+    pub rule: String,
+    pub from: String,
+    pub to: String,
+    pub file: String,
+    pub line_num: i32,
+    pub source: String,
+}
+
+impl RuleViolation {
+    pub fn render(&self) -> String {
+        format!(
+            "{}:{}: {}: {} -> {} (`{}`)",
+            self.file, self.line_num, self.rule, self.from, self.to, self.source
+        )
+    }
+}
+
+impl DependencyRules {
+    pub fn from_json(code: &str) -> Result<Self, String> {
+        serde_json::from_str(code).map_err(|err| err.to_string())
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let code = fs::read_to_string(path).map_err(|err| format!("{:?}: {}", path, err))?;
+        DependencyRules::from_json(&code)
+    }
+
+    /// violations by file and line
+    pub fn check(&self, project: &ProjectDependency) -> Vec<RuleViolation> {
+        let mut violations = vec![];
+        for rule in self.layers.iter() {
+            let name = if rule.name.is_empty() {
+                format!(
+                    "{} must not depend on {}",
+                    rule.from,
+                    rule.forbidden.join(", ")
+                )
+            } else {
+                rule.name.clone()
+            };
+
+            for module in project.modules.iter() {
+                if !DependencyRules::is_match(&rule.from, &module.qualified_name()) {
+                    continue;
+                }
+
+                for import in module.imports.iter() {
+                    let target = match project.module(&import.to) {
+                        Some(target) => target.qualified_name(),
+                        None => import.to.clone(),
+                    };
+                    let is_forbidden = rule
+                        .forbidden
+                        .iter()
+                        .any(|pattern| DependencyRules::is_match(pattern, &target));
+                    if is_forbidden {
+                        violations.push(RuleViolation {
+                            rule: name.clone(),
+                            from: module.name.clone(),
+                            to: import.to.clone(),
+                            file: import.file.clone(),
+                            line_num: import.line_num,
+                            source: import.source.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        if self.no_cycles {
+            let imports = project.internal_imports();
+            for cycle in project.cycles() {
+                let rule = format!("cycle between {}", cycle.join(", "));
+                for ((from, to), items) in imports.iter() {
+                    if !cycle.contains(from) || !cycle.contains(to) {
+                        continue;
+                    }
+                    for import in items.iter() {
+                        violations.push(RuleViolation {
+                            rule: rule.clone(),
+                            from: from.clone(),
+                            to: to.clone(),
+                            file: import.file.clone(),
+                            line_num: import.line_num,
+                            source: import.source.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        violations
+    }
+
+    /// `pattern` of ArchUnit, `name` is dotted, such as `com.shop.domain`
+    pub fn is_match(pattern: &str, name: &str) -> bool {
+        let pattern = pattern.replace("..", ".**.");
+        let patterns: Vec<&str> = pattern.split('.').filter(|part| !part.is_empty()).collect();
+        let names: Vec<&str> = name.split('.').filter(|part| !part.is_empty()).collect();
+        DependencyRules::match_parts(&patterns, &names)
+    }
+
+    fn match_parts(patterns: &[&str], names: &[&str]) -> bool {
+        match patterns.first() {
+            None => names.is_empty(),
+            Some(&"**") => {
+                DependencyRules::match_parts(&patterns[1..], names)
+                    || (!names.is_empty() && DependencyRules::match_parts(patterns, &names[1..]))
+            }
+            Some(pattern) => {
+                !names.is_empty()
+                    && DependencyRules::match_part(pattern, names[0])
+                    && DependencyRules::match_parts(&patterns[1..], &names[1..])
+            }
+        }
+    }
+
+    /// `*` is any text, such as `*Service` or `web*`
+    fn match_part(pattern: &str, name: &str) -> bool {
+        let parts: Vec<&str> = pattern.split('*').collect();
+        if parts.len() == 1 {
+            return pattern == name;
+        }
+
+        let first = parts[0];
+        let last = parts[parts.len() - 1];
+        if name.len() < first.len() + last.len() || !name.starts_with(first) {
+            return false;
+        }
+
+        let mut rest = &name[first.len()..name.len() - last.len()];
+        for part in parts[1..parts.len() - 1].iter() {
+            match rest.find(part) {
+                Some(index) => rest = &rest[index + part.len()..],
+                None => return false,
+            }
+        }
+        name.ends_with(last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dependency::dependency_rule::DependencyRules;
+    use crate::dependency::module_dependency::{ModuleDependency, PackageImport};
+    use crate::dependency::project_dependency::ProjectDependency;
+
+    fn import(file: &str, line_num: i32, to: &str, external: bool) -> PackageImport {
+        PackageImport {
+            file: file.to_string(),
+            line_num,
+            source: to.to_string(),
+            to: to.to_string(),
+            to_file: String::new(),
+            external,
+            scope: String::new(),
+        }
+    }
+
+    fn project() -> ProjectDependency {
+        let mut domain = ModuleDependency::new("app", "com.shop.domain");
+        domain.imports = vec![
+            import("Order.java", 3, "app:com.shop.web.controller", false),
+            import("Order.java", 4, "java.util", true),
+        ];
+        let mut controller = ModuleDependency::new("app", "com.shop.web.controller");
+        controller.imports = vec![import(
+            "OrderController.java",
+            5,
+            "app:com.shop.domain",
+            false,
+        )];
+
+        ProjectDependency {
+            name: String::from("shop"),
+            modules: vec![domain, controller],
+        }
+    }
+
+    #[test]
+    fn should_match_archunit_patterns() {
+        assert!(DependencyRules::is_match(
+            "..controller..",
+            "com.shop.controller"
+        ));
+        assert!(DependencyRules::is_match("..controller..", "controller"));
+        assert!(DependencyRules::is_match("com.shop..", "com.shop"));
+        assert!(DependencyRules::is_match("com.*.domain", "com.shop.domain"));
+        assert!(DependencyRules::is_match(
+            "..*Service",
+            "com.shop.UserService"
+        ));
+        assert!(!DependencyRules::is_match(
+            "..controller..",
+            "com.shop.controllers"
+        ));
+        assert!(!DependencyRules::is_match("com.shop", "com.shop.domain"));
+    }
+
+    #[test]
+    fn should_report_layer_violations_by_file_and_line() {
+        let rules = DependencyRules::from_json(
+            r#"{ "layers": [{ "from": "..domain..", "forbidden": ["..controller..", "javax.."] }] }"#,
+        )
+        .unwrap();
+
+        let violations = rules.check(&project());
+        assert_eq!(1, violations.len());
+        assert_eq!(
+            "Order.java:3: ..domain.. must not depend on ..controller.., javax..: \
+             app:com.shop.domain -> app:com.shop.web.controller (`app:com.shop.web.controller`)",
+            violations[0].render()
+        );
+    }
+
+    #[test]
+    fn should_report_imports_of_cycles() {
+        let rules = DependencyRules::from_json(r#"{ "no_cycles": true }"#).unwrap();
+
+        let violations = rules.check(&project());
+        let lines: Vec<(&str, i32)> = violations
+            .iter()
+            .map(|violation| (violation.file.as_str(), violation.line_num))
+            .collect();
+        assert_eq!(vec![("Order.java", 3), ("OrderController.java", 5)], lines);
+        assert_eq!(
+            "cycle between app:com.shop.domain, app:com.shop.web.controller",
+            violations[0].rule
+        );
+    }
+}
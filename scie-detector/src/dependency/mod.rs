@@ -1,4 +1,5 @@
 pub mod base_library;
+pub mod dependency_rule;
 pub mod library_dependency;
 pub mod module_dependency;
 pub mod project_dependency;
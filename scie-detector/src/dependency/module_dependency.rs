@@ -1,2 +1,57 @@
+/// an import of a file, resolved to the package of the imported file or to an external library
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
-pub struct ModuleDependency {}
+pub struct PackageImport {
+    /// the file which imports, relative to the project
+    pub file: String,
+    pub line_num: i32,
+    /// as written in the file, such as `com.shop.domain.User`
+    pub source: String,
+    /// `name` of the imported package, or the external library, such as `java.util`
+    pub to: String,
+    /// the imported file, empty if it is external or a module of a build script
+    pub to_file: String,
+    pub external: bool,
+    /// `LibraryScope` of a module in a build script, empty for imports of code
+    #[serde(default)]
+    pub scope: String,
+}
+
+/// A package of a module, with the imports of its files.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct ModuleDependency {
+    /// `module:package`, or the module name for the root package of a module
+    pub name: String,
+    pub module: String,
+    /// `com.shop.domain` of Java, or a dir in the module, such as `src/domain`
+    pub package: String,
+    /// relative to the project
+    pub files: Vec<String>,
+    pub imports: Vec<PackageImport>,
+}
+
+impl ModuleDependency {
+    pub fn new(module: &str, package: &str) -> ModuleDependency {
+        let name = if package.is_empty() {
+            module.to_string()
+        } else {
+            format!("{}:{}", module, package)
+        };
+
+        ModuleDependency {
+            name,
+            module: module.to_string(),
+            package: package.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// the package in dotted form for patterns, `src/domain` is `src.domain`, the root package
+    /// is the module name
+    pub fn qualified_name(&self) -> String {
+        if self.package.is_empty() {
+            self.module.clone()
+        } else {
+            self.package.replace('/', ".")
+        }
+    }
+}
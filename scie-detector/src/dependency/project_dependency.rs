@@ -1,2 +1,171 @@
-#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
-pub struct ProjectDependency {}
+use std::collections::BTreeMap;
+
+use crate::dependency::module_dependency::{ModuleDependency, PackageImport};
+
+/// Packages of a project and the imports between them.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct ProjectDependency {
+    pub name: String,
+    pub modules: Vec<ModuleDependency>,
+}
+
+impl ProjectDependency {
+    pub fn module(&self, name: &str) -> Option<&ModuleDependency> {
+        self.modules.iter().find(|module| module.name == name)
+    }
+
+    /// imports from a package to another package of the project, by `(from, to)`
+    pub fn internal_imports(&self) -> BTreeMap<(String, String), Vec<&PackageImport>> {
+        let mut imports: BTreeMap<(String, String), Vec<&PackageImport>> = Default::default();
+        for module in self.modules.iter() {
+            for import in module.imports.iter() {
+                if import.external || import.to == module.name {
+                    continue;
+                }
+                imports
+                    .entry((module.name.clone(), import.to.clone()))
+                    .or_default()
+                    .push(import);
+            }
+        }
+        imports
+    }
+
+    /// packages which import each other, directly or not, each cycle is sorted by name
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let names: Vec<&str> = self.modules.iter().map(|m| m.name.as_str()).collect();
+        let index_of: BTreeMap<&str, usize> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (*name, i))
+            .collect();
+
+        let mut edges: Vec<Vec<usize>> = vec![vec![]; names.len()];
+        for (from, to) in self.internal_imports().keys() {
+            if let (Some(from), Some(to)) = (index_of.get(from.as_str()), index_of.get(to.as_str()))
+            {
+                edges[*from].push(*to);
+            }
+        }
+
+        let mut tarjan = Tarjan::new(names.len());
+        for node in 0..names.len() {
+            if tarjan.indexes[node].is_none() {
+                tarjan.connect(node, &edges);
+            }
+        }
+
+        let mut cycles: Vec<Vec<String>> = tarjan
+            .components
+            .into_iter()
+            .filter(|component| component.len() > 1)
+            .map(|component| {
+                let mut cycle: Vec<String> =
+                    component.iter().map(|i| names[*i].to_string()).collect();
+                cycle.sort();
+                cycle
+            })
+            .collect();
+        cycles.sort();
+        cycles
+    }
+}
+
+/// strongly connected components
+struct Tarjan {
+    index: usize,
+    indexes: Vec<Option<usize>>,
+    low_links: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan {
+    fn new(size: usize) -> Self {
+        Tarjan {
+            index: 0,
+            indexes: vec![None; size],
+            low_links: vec![0; size],
+            on_stack: vec![false; size],
+            stack: vec![],
+            components: vec![],
+        }
+    }
+
+    fn connect(&mut self, node: usize, edges: &[Vec<usize>]) {
+        self.indexes[node] = Some(self.index);
+        self.low_links[node] = self.index;
+        self.index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for next in edges[node].iter() {
+            match self.indexes[*next] {
+                None => {
+                    self.connect(*next, edges);
+                    self.low_links[node] = self.low_links[node].min(self.low_links[*next]);
+                }
+                Some(index) if self.on_stack[*next] => {
+                    self.low_links[node] = self.low_links[node].min(index);
+                }
+                _ => {}
+            }
+        }
+
+        if Some(self.low_links[node]) == self.indexes[node] {
+            let mut component = vec![];
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dependency::module_dependency::{ModuleDependency, PackageImport};
+    use crate::dependency::project_dependency::ProjectDependency;
+
+    fn package(name: &str, imports: &[&str]) -> ModuleDependency {
+        let mut module = ModuleDependency::new("app", name);
+        module.imports = imports
+            .iter()
+            .map(|to| PackageImport {
+                file: format!("{}.rs", name),
+                line_num: 1,
+                source: to.to_string(),
+                to: format!("app:{}", to),
+                to_file: String::new(),
+                external: false,
+                scope: String::new(),
+            })
+            .collect();
+        module
+    }
+
+    #[test]
+    fn should_find_cycles_of_packages() {
+        let project = ProjectDependency {
+            name: String::from("app"),
+            modules: vec![
+                package("a", &["b"]),
+                package("b", &["c"]),
+                package("c", &["a", "d"]),
+                package("d", &["e"]),
+                package("e", &["d", "e"]),
+                package("f", &["a"]),
+            ],
+        };
+
+        assert_eq!(
+            vec![vec!["app:a", "app:b", "app:c"], vec!["app:d", "app:e"]],
+            project.cycles()
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::dependency::base_library::{BaseLibrary, LibraryScope};
+use scie_infra::paths::{normalize, relative_path};
+use scie_infra::xml::XmlElement;
+
+lazy_static! {
+    /// `Project("{type}") = "Name", "path\to\Name.csproj", "{id}"` of a solution
+    static ref SLN_PROJECT: Regex =
+        Regex::new(r#"^Project\("[^"]*"\)\s*=\s*"([^"]*)"\s*,\s*"([^"]*)""#).unwrap();
+}
+
+/// languages by the extensions of project files
+pub const PROJECT_EXTENSIONS: [(&str, &str); 3] = [
+    ("csproj", "C#"),
+    ("fsproj", "F#"),
+    ("vbproj", "Visual Basic"),
+];
+
+/// packages which make a project a test one
+const TEST_PACKAGES: [&str; 5] = [
+    "Microsoft.NET.Test.Sdk",
+    "xunit",
+    "NUnit",
+    "MSTest.TestFramework",
+    "MSTest.Sdk",
+];
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct DotnetProject {
+    /// `AssemblyName`, or the name of the project file
+    pub name: String,
+    /// `/` separated path of the project file, relative to the facet
+    pub path: String,
+    pub language: String,
+    /// such as `Microsoft.NET.Sdk.Web`, empty for the old style projects
+    pub sdk: String,
+    pub target_frameworks: Vec<String>,
+    pub is_test: bool,
+    /// paths of `ProjectReference`, relative to the facet
+    pub project_references: Vec<String>,
+    /// `PackageReference` and `packages.config`, all of a test project are for tests, the
+    /// `PrivateAssets="all"` ones are only for building
+    pub libraries: Vec<BaseLibrary>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct DotnetFacet {
+    /// `*.sln` in the dir
+    pub solutions: Vec<String>,
+    /// of the solutions, and the project files in the dir
+    pub projects: Vec<DotnetProject>,
+}
+
+impl DotnetFacet {
+    /// `dir` is the one of a solution or a project file, versions of central package
+    /// management come from `Directory.Packages.props` of it or its parents
+    pub fn from_path(dir: &Path) -> Result<DotnetFacet, String> {
+        let mut facet = DotnetFacet::default();
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)
+            .map_err(|err| format!("{}: {}", dir.display(), err))?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .collect();
+        files.sort();
+
+        let mut projects: Vec<PathBuf> = vec![];
+        for file in files.iter() {
+            match file.extension().and_then(|ext| ext.to_str()) {
+                Some("sln") => {
+                    let code = fs::read_to_string(file)
+                        .map_err(|err| format!("{}: {}", file.display(), err))?;
+                    facet.solutions.push(relative_path(file, dir));
+                    projects.extend(
+                        DotnetFacet::solution_projects(&code)
+                            .into_iter()
+                            .map(|project| normalize(&dir.join(project))),
+                    );
+                }
+                Some(ext) if DotnetFacet::language(ext).is_some() => projects.push(file.clone()),
+                _ => {}
+            }
+        }
+
+        let central = DotnetFacet::central_versions(dir);
+        let mut visited: BTreeSet<PathBuf> = Default::default();
+        for project in projects.into_iter() {
+            if !visited.insert(project.clone()) || !project.is_file() {
+                continue;
+            }
+            facet
+                .projects
+                .push(DotnetFacet::parse_project(&project, dir, &central)?);
+        }
+
+        Ok(facet)
+    }
+
+    pub fn language(extension: &str) -> Option<&'static str> {
+        PROJECT_EXTENSIONS
+            .iter()
+            .find(|(ext, _)| *ext == extension)
+            .map(|(_, language)| *language)
+    }
+
+    /// paths of project files in a `.sln`, solution folders are left out
+    fn solution_projects(code: &str) -> Vec<String> {
+        code.lines()
+            .filter_map(|line| SLN_PROJECT.captures(line.trim()))
+            .map(|captures| captures[2].replace('\\', "/"))
+            .filter(|path| {
+                Path::new(path)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .and_then(DotnetFacet::language)
+                    .is_some()
+            })
+            .collect()
+    }
+
+    fn parse_project(
+        path: &Path,
+        root: &Path,
+        central: &BTreeMap<String, String>,
+    ) -> Result<DotnetProject, String> {
+        let code =
+            fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let project =
+            XmlElement::parse(&code).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+        let properties: Vec<&XmlElement> = project
+            .children_by_name("PropertyGroup")
+            .flat_map(|group| group.children.iter())
+            .collect();
+        let property = |name: &str| {
+            properties
+                .iter()
+                .rev()
+                .find(|property| property.name == name)
+                .map(|property| property.text.trim().to_string())
+        };
+        let items: Vec<&XmlElement> = project
+            .children_by_name("ItemGroup")
+            .flat_map(|group| group.children.iter())
+            .collect();
+
+        let stem = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        let mut result = DotnetProject {
+            name: property("AssemblyName").unwrap_or(stem),
+            path: relative_path(path, root),
+            language: DotnetFacet::language(extension).unwrap_or("").to_string(),
+            sdk: project.attributes.get("Sdk").cloned().unwrap_or_default(),
+            target_frameworks: property("TargetFrameworks")
+                .or_else(|| property("TargetFramework"))
+                .or_else(|| property("TargetFrameworkVersion"))
+                .map(|frameworks| {
+                    frameworks
+                        .split(';')
+                        .filter(|framework| !framework.trim().is_empty())
+                        .map(|framework| framework.trim().to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+
+        let mut references: Vec<(String, String, bool)> = vec![];
+        for item in items.iter() {
+            let include = item.attributes.get("Include").map(|it| it.trim());
+            match (item.name.as_str(), include) {
+                ("PackageReference", Some(name)) => {
+                    let version = item
+                        .attributes
+                        .get("Version")
+                        .cloned()
+                        .or_else(|| item.text_of("Version"))
+                        .or_else(|| central.get(name).cloned())
+                        .unwrap_or_default();
+                    let private = item
+                        .attributes
+                        .get("PrivateAssets")
+                        .cloned()
+                        .or_else(|| item.text_of("PrivateAssets"))
+                        .is_some_and(|assets| assets.eq_ignore_ascii_case("all"));
+                    references.push((name.to_string(), version, private));
+                }
+                ("ProjectReference", Some(reference)) => {
+                    let reference = normalize(&dir.join(reference.replace('\\', "/")));
+                    result
+                        .project_references
+                        .push(relative_path(&reference, root));
+                }
+                _ => {}
+            }
+        }
+
+        // projects of the old style list their packages in `packages.config`
+        let config = dir.join("packages.config");
+        if let Ok(code) = fs::read_to_string(&config) {
+            let packages =
+                XmlElement::parse(&code).map_err(|err| format!("{}: {}", config.display(), err))?;
+            for package in packages.children_by_name("package") {
+                let attribute =
+                    |name: &str| package.attributes.get(name).cloned().unwrap_or_default();
+                let private = attribute("developmentDependency") == "true";
+                references.push((attribute("id"), attribute("version"), private));
+            }
+        }
+
+        result.is_test = property("IsTestProject").is_some_and(|it| it == "true")
+            || result.sdk == "MSTest.Sdk"
+            || references
+                .iter()
+                .any(|(name, _, _)| TEST_PACKAGES.contains(&name.as_str()));
+        for (name, version, private) in references.into_iter() {
+            let scope = if result.is_test {
+                LibraryScope::Test
+            } else if private {
+                LibraryScope::Dev
+            } else {
+                LibraryScope::Main
+            };
+            result.libraries.push(BaseLibrary {
+                name,
+                version,
+                group: "".to_string(),
+                source: "nuget".to_string(),
+                scope: scope.name().to_string(),
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// `PackageVersion` of the nearest `Directory.Packages.props`
+    fn central_versions(dir: &Path) -> BTreeMap<String, String> {
+        let mut versions: BTreeMap<String, String> = Default::default();
+        let props = dir
+            .ancestors()
+            .map(|dir| dir.join("Directory.Packages.props"))
+            .find(|path| path.is_file())
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|code| XmlElement::parse(&code).ok());
+
+        for group in props
+            .iter()
+            .flat_map(|props| props.children_by_name("ItemGroup"))
+        {
+            for package in group.children_by_name("PackageVersion") {
+                if let (Some(name), Some(version)) = (
+                    package.attributes.get("Include"),
+                    package.attributes.get("Version"),
+                ) {
+                    versions.insert(name.clone(), version.clone());
+                }
+            }
+        }
+        versions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::facet::dotnet_facet::DotnetFacet;
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_read_projects_of_solution() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("dotnet")
+            .join("shop");
+        let facet = DotnetFacet::from_path(&dir).unwrap();
+
+        assert_eq!(vec!["Shop.sln"], facet.solutions);
+        let projects: Vec<(&str, &str, &str, bool)> = facet
+            .projects
+            .iter()
+            .map(|project| {
+                (
+                    project.name.as_str(),
+                    project.path.as_str(),
+                    project.language.as_str(),
+                    project.is_test,
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("Shop.Core", "src/Shop.Core/Shop.Core.csproj", "C#", false),
+                ("Shop.Web", "src/Shop.Web/Shop.Web.fsproj", "F#", false),
+                (
+                    "Shop.Tests",
+                    "tests/Shop.Tests/Shop.Tests.csproj",
+                    "C#",
+                    true
+                ),
+            ],
+            projects
+        );
+
+        let web = &facet.projects[1];
+        assert_eq!("Microsoft.NET.Sdk.Web", web.sdk);
+        assert_eq!(vec!["net5.0", "netcoreapp3.1"], web.target_frameworks);
+        assert_eq!(
+            vec!["src/Shop.Core/Shop.Core.csproj"],
+            web.project_references
+        );
+
+        let libraries: Vec<(&str, &str, &str)> = facet
+            .projects
+            .iter()
+            .flat_map(|project| project.libraries.iter())
+            .map(|library| {
+                (
+                    library.name.as_str(),
+                    library.version.as_str(),
+                    library.scope.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("Newtonsoft.Json", "12.0.3", "Main"),
+                ("StyleCop.Analyzers", "1.1.118", "Dev"),
+                ("Giraffe", "5.0.0", "Main"),
+                ("Microsoft.NET.Test.Sdk", "16.7.1", "Test"),
+                ("xunit", "2.4.1", "Test"),
+            ],
+            libraries
+        );
+    }
+}
@@ -1,4 +1,196 @@
-#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::dependency::base_library::{BaseLibrary, LibraryScope};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 pub struct GoFacet {
     pub has_mod: bool,
+    /// checksums of the downloaded modules, only whether `go.sum` exists
+    #[serde(default)]
+    pub has_sum: bool,
+    /// path of `module`
+    #[serde(default)]
+    pub module: String,
+    /// of the `go` directive
+    #[serde(default)]
+    pub go_version: String,
+    /// `replace` targets by the replaced modules, `path version` or a local dir
+    #[serde(default)]
+    pub replaces: BTreeMap<String, String>,
+    /// of `require`, the replaced ones come with the target of the replacement. The versions
+    /// are the ones of `go.mod`, they are not checked against `go.sum`
+    #[serde(default)]
+    pub libraries: Vec<BaseLibrary>,
+    /// names of the `libraries` which are required as `// indirect`, they are still built into
+    /// the module so they keep the `Main` scope, but the module does not import them itself
+    #[serde(default)]
+    pub indirect: Vec<String>,
+}
+
+impl GoFacet {
+    /// `dir` is the one of `go.mod`
+    pub fn from_path(dir: &Path) -> Result<GoFacet, String> {
+        let path = dir.join("go.mod");
+        let code =
+            fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let mut facet = GoFacet::parse_mod(&code);
+        facet.has_sum = dir.join("go.sum").is_file();
+        let replaces = &facet.replaces;
+        for library in facet.libraries.iter_mut() {
+            let replace = replaces
+                .get(&format!("{} {}", library.name, library.version))
+                .or_else(|| replaces.get(&library.name));
+            let replace = match replace {
+                Some(replace) => replace,
+                None => continue,
+            };
+
+            let mut parts = replace.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some(module), Some(version)) => {
+                    for name in facet.indirect.iter_mut() {
+                        if *name == library.name {
+                            *name = module.to_string();
+                        }
+                    }
+                    library.name = module.to_string();
+                    library.version = version.to_string();
+                }
+                // a local dir has no version
+                (Some(_), None) => {
+                    library.version = String::new();
+                    library.source = String::from("path");
+                }
+                _ => {}
+            }
+        }
+
+        Ok(facet)
+    }
+
+    /// `module`, `go`, `require` and `replace`, alone or in blocks, comments are skipped but
+    /// the `// indirect` ones of requires
+    pub fn parse_mod(code: &str) -> GoFacet {
+        let mut facet = GoFacet {
+            has_mod: true,
+            ..Default::default()
+        };
+
+        let mut block = "";
+        for line in code.lines() {
+            let mut parts = line.splitn(2, "//");
+            let line = parts.next().unwrap_or_default().trim();
+            let is_indirect = parts.next().map(str::trim) == Some("indirect");
+            if line.is_empty() {
+                continue;
+            }
+            if line == ")" {
+                block = "";
+                continue;
+            }
+
+            let (directive, rest) = if block.is_empty() {
+                let mut parts = line.splitn(2, char::is_whitespace);
+                let directive = parts.next().unwrap_or_default();
+                let rest = parts.next().unwrap_or_default().trim();
+                if rest == "(" {
+                    block = directive;
+                    continue;
+                }
+                (directive, rest)
+            } else {
+                (block, line)
+            };
+
+            let words: Vec<&str> = rest
+                .split_whitespace()
+                .map(|word| word.trim_matches('"'))
+                .collect();
+            match directive {
+                "module" => facet.module = words.join(" "),
+                "go" => facet.go_version = words.join(" "),
+                "require" if words.len() >= 2 => {
+                    if is_indirect {
+                        facet.indirect.push(words[0].to_string());
+                    }
+                    facet.libraries.push(BaseLibrary {
+                        name: words[0].to_string(),
+                        version: words[1].to_string(),
+                        group: "".to_string(),
+                        source: "go".to_string(),
+                        scope: LibraryScope::Main.name().to_string(),
+                    })
+                }
+                "replace" => {
+                    if let Some(arrow) = words.iter().position(|word| *word == "=>") {
+                        let from = words[..arrow].join(" ");
+                        let to = words[arrow + 1..].join(" ");
+                        facet.replaces.insert(from, to);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        facet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::facet::go_facet::GoFacet;
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_read_mod_with_replaces_and_sums() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("go")
+            .join("shop");
+        let facet = GoFacet::from_path(&dir).unwrap();
+
+        assert!(facet.has_mod && facet.has_sum);
+        assert_eq!("github.com/example/shop", facet.module);
+        assert_eq!("1.15", facet.go_version);
+        assert_eq!("../model", facet.replaces["github.com/example/model"]);
+
+        let libraries: Vec<(&str, &str, &str)> = facet
+            .libraries
+            .iter()
+            .map(|library| {
+                (
+                    library.name.as_str(),
+                    library.version.as_str(),
+                    library.source.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("github.com/gin-gonic/gin", "v1.6.3", "go"),
+                ("github.com/example/model", "", "path"),
+                ("github.com/example/uuid", "v1.1.3", "go"),
+            ],
+            libraries
+        );
+        assert_eq!(vec!["github.com/example/uuid"], facet.indirect);
+    }
+
+    #[test]
+    fn should_keep_indirect_requires_of_blocks_and_lines() {
+        let facet = GoFacet::parse_mod(
+            "module shop\n\nrequire github.com/pkg/errors v0.9.1 // indirect\n\nrequire (\n\tgolang.org/x/text v0.3.3 // indirect\n\tgithub.com/gin-gonic/gin v1.6.3 // web\n)\n",
+        );
+
+        assert_eq!(3, facet.libraries.len());
+        assert_eq!(
+            vec!["github.com/pkg/errors", "golang.org/x/text"],
+            facet.indirect
+        );
+    }
 }
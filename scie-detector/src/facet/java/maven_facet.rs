@@ -0,0 +1,597 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::content_root::ContentRoot;
+use crate::dependency::base_library::{BaseLibrary, LibraryScope};
+use crate::facet::JavaModuleData;
+use scie_infra::paths::{normalize, relative_path};
+use scie_infra::xml::XmlElement;
+
+/// parents which refer to each other are stopped here
+const MAX_PARENT_DEPTH: usize = 16;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct MavenModule {
+    pub group_id: String,
+    pub artifact_id: String,
+    pub version: String,
+    pub packaging: String,
+    /// `/` separated dir of the pom, relative to the root pom, empty for the root
+    pub path: String,
+    pub module: JavaModuleData,
+    /// the versions and scopes which are left out come from the managed libraries
+    pub libraries: Vec<BaseLibrary>,
+    /// `<dependencyManagement>` of the pom and its parents
+    pub managed_libraries: Vec<BaseLibrary>,
+    /// paths of `<modules>`, relative to the root pom
+    pub modules: Vec<String>,
+}
+
+/// The modules of a Maven reactor, from the root `pom.xml` down by `<modules>`. Only local
+/// files are read: parents come from `<relativePath>` and are ignored if they are not on disk.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct MavenFacet {
+    pub root: String,
+    pub modules: Vec<MavenModule>,
+    /// poms of modules or parents which can not be read or parsed, the other ones are still read
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+impl MavenFacet {
+    /// `root` is the dir of the root `pom.xml`
+    pub fn from_path(root: &Path) -> Result<MavenFacet, String> {
+        let root = normalize(root);
+        let mut facet = MavenFacet {
+            root: root.display().to_string(),
+            modules: vec![],
+            errors: vec![],
+        };
+
+        let mut visited: BTreeSet<PathBuf> = Default::default();
+        let mut queue = vec![root.join("pom.xml")];
+        while !queue.is_empty() {
+            let path = queue.remove(0);
+            if !visited.insert(path.clone()) {
+                continue;
+            }
+
+            let pom = match EffectivePom::load(&path, 0, &mut facet.errors) {
+                Ok(pom) => pom,
+                // only the root pom is required
+                Err(err) if !facet.modules.is_empty() => {
+                    facet.errors.push(format!("{}: {}", path.display(), err));
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            let module = pom.module(&root);
+            for module_path in pom.pom.modules.iter() {
+                let mut module_pom = normalize(&pom.pom.dir.join(module_path));
+                if module_pom.is_dir() {
+                    module_pom = module_pom.join("pom.xml");
+                }
+                if module_pom.is_file() {
+                    queue.push(module_pom);
+                }
+            }
+
+            facet.modules.push(module);
+        }
+
+        Ok(facet)
+    }
+
+    pub fn module(&self, artifact_id: &str) -> Option<&MavenModule> {
+        self.modules
+            .iter()
+            .find(|module| module.artifact_id == artifact_id)
+    }
+
+    /// Maven scopes: `test` for tests, `provided` and `system` are only for building
+    pub fn scope(scope: &str) -> LibraryScope {
+        match scope {
+            "test" => LibraryScope::Test,
+            "provided" | "system" => LibraryScope::Dev,
+            _ => LibraryScope::Main,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct PomDependency {
+    group_id: String,
+    artifact_id: String,
+    version: String,
+    scope: String,
+}
+
+impl PomDependency {
+    fn from_xml(element: &XmlElement) -> PomDependency {
+        let text = |name: &str| element.text_of(name).unwrap_or_default();
+        PomDependency {
+            group_id: text("groupId"),
+            artifact_id: text("artifactId"),
+            version: text("version"),
+            scope: text("scope"),
+        }
+    }
+}
+
+/// the values of a `pom.xml` as they are written
+#[derive(Debug, Clone, Default)]
+struct Pom {
+    dir: PathBuf,
+    group_id: String,
+    artifact_id: String,
+    version: String,
+    packaging: String,
+    parent_group_id: String,
+    parent_artifact_id: String,
+    parent_version: String,
+    /// `None` without a `<parent>`, empty if `<relativePath/>` turns the lookup off
+    parent_path: Option<String>,
+    properties: Vec<(String, String)>,
+    modules: Vec<String>,
+    dependencies: Vec<PomDependency>,
+    managed_dependencies: Vec<PomDependency>,
+    source_directory: String,
+    test_source_directory: String,
+    resource_directories: Vec<String>,
+    build_directory: String,
+}
+
+impl Pom {
+    fn from_path(path: &Path) -> Result<Pom, String> {
+        let code =
+            fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let project =
+            XmlElement::parse(&code).map_err(|err| format!("{}: {}", path.display(), err))?;
+        if project.name != "project" {
+            return Err(format!("{}: root is not <project>", path.display()));
+        }
+
+        let text = |path: &str| project.text_of(path).unwrap_or_default();
+        let list = |path: &str, name: &str| -> Vec<&XmlElement> {
+            match project.find(path) {
+                Some(element) => element
+                    .children
+                    .iter()
+                    .filter(|child| child.name == name)
+                    .collect(),
+                None => vec![],
+            }
+        };
+
+        let parent_path = project.child("parent").map(|parent| {
+            parent
+                .text_of("relativePath")
+                .unwrap_or_else(|| String::from("../pom.xml"))
+        });
+
+        Ok(Pom {
+            dir: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            group_id: text("groupId"),
+            artifact_id: text("artifactId"),
+            version: text("version"),
+            packaging: text("packaging"),
+            parent_group_id: text("parent/groupId"),
+            parent_artifact_id: text("parent/artifactId"),
+            parent_version: text("parent/version"),
+            parent_path,
+            properties: project
+                .child("properties")
+                .map(|properties| {
+                    properties
+                        .children
+                        .iter()
+                        .map(|property| (property.name.clone(), property.text.trim().to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            modules: list("modules", "module")
+                .iter()
+                .map(|module| module.text.trim().to_string())
+                .collect(),
+            dependencies: list("dependencies", "dependency")
+                .into_iter()
+                .map(PomDependency::from_xml)
+                .collect(),
+            managed_dependencies: list("dependencyManagement/dependencies", "dependency")
+                .into_iter()
+                .map(PomDependency::from_xml)
+                .collect(),
+            source_directory: text("build/sourceDirectory"),
+            test_source_directory: text("build/testSourceDirectory"),
+            resource_directories: list("build/resources", "resource")
+                .iter()
+                .filter_map(|resource| resource.text_of("directory"))
+                .collect(),
+            build_directory: text("build/directory"),
+        })
+    }
+}
+
+/// a pom with the properties, dependencies and managed versions of its parents on disk
+#[derive(Default)]
+struct EffectivePom {
+    pom: Pom,
+    properties: BTreeMap<String, String>,
+    dependencies: Vec<PomDependency>,
+    managed_dependencies: Vec<PomDependency>,
+}
+
+impl EffectivePom {
+    /// a parent which can't be read is left out, and recorded in `errors`
+    fn load(path: &Path, depth: usize, errors: &mut Vec<String>) -> Result<EffectivePom, String> {
+        let mut pom = Pom::from_path(path)?;
+        let parent = match EffectivePom::parent_path(&pom) {
+            Some(parent_path) if depth < MAX_PARENT_DEPTH => {
+                match EffectivePom::load(&parent_path, depth + 1, errors) {
+                    Ok(parent) => Some(parent)
+                        .filter(|parent| parent.pom.artifact_id == pom.parent_artifact_id),
+                    Err(err) => {
+                        errors.push(format!("{}: {}", parent_path.display(), err));
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let mut effective = parent.unwrap_or_default();
+
+        if pom.group_id.is_empty() {
+            pom.group_id = pom.parent_group_id.clone();
+        }
+        if pom.version.is_empty() {
+            pom.version = pom.parent_version.clone();
+        }
+        if pom.packaging.is_empty() {
+            pom.packaging = String::from("jar");
+        }
+
+        for (name, value) in pom.properties.iter() {
+            effective.properties.insert(name.clone(), value.clone());
+        }
+        for (name, value) in [
+            ("groupId", &pom.group_id),
+            ("artifactId", &pom.artifact_id),
+            ("version", &pom.version),
+            ("packaging", &pom.packaging),
+            ("parent.groupId", &pom.parent_group_id),
+            ("parent.artifactId", &pom.parent_artifact_id),
+            ("parent.version", &pom.parent_version),
+        ]
+        .iter()
+        {
+            // `pom.` is the deprecated prefix of `project.`
+            for prefix in ["project", "pom"].iter() {
+                effective
+                    .properties
+                    .insert(format!("{}.{}", prefix, name), value.to_string());
+            }
+        }
+        // content roots are relative to the dir of the pom
+        for name in ["basedir", "project.basedir"].iter() {
+            effective
+                .properties
+                .insert(name.to_string(), String::from("."));
+        }
+
+        effective.dependencies.extend(pom.dependencies.clone());
+        effective
+            .managed_dependencies
+            .extend(pom.managed_dependencies.clone());
+        effective.pom = pom;
+        Ok(effective)
+    }
+
+    fn parent_path(pom: &Pom) -> Option<PathBuf> {
+        let relative = pom.parent_path.as_ref().filter(|path| !path.is_empty())?;
+        let mut path = normalize(&pom.dir.join(relative));
+        if path.is_dir() {
+            path = path.join("pom.xml");
+        }
+        Some(path).filter(|path| path.is_file())
+    }
+
+    fn module(&self, root: &Path) -> MavenModule {
+        let path = relative_path(&self.pom.dir, root);
+        let managed: Vec<BaseLibrary> = self
+            .managed_dependencies
+            .iter()
+            .map(|dependency| self.library(dependency, None))
+            .collect();
+        // the later ones are of children, which override their parents
+        let libraries = self
+            .dependencies
+            .iter()
+            .map(|dependency| self.library(dependency, Some(&managed)))
+            .rev()
+            .fold(vec![], |mut libraries: Vec<BaseLibrary>, library| {
+                if !libraries
+                    .iter()
+                    .any(|it| it.group == library.group && it.name == library.name)
+                {
+                    libraries.insert(0, library);
+                }
+                libraries
+            });
+
+        let dir = |dir: &str, default: &str| -> String {
+            let dir = if dir.is_empty() {
+                default.to_string()
+            } else {
+                self.interpolate(dir)
+            };
+            let joined = normalize(&self.pom.dir.join(dir));
+            relative_path(&joined, root)
+        };
+        let resource_dirs = if self.pom.resource_directories.is_empty() {
+            vec![dir("", "src/main/resources")]
+        } else {
+            self.pom
+                .resource_directories
+                .iter()
+                .map(|resource| dir(resource, ""))
+                .collect()
+        };
+
+        let content_root = ContentRoot {
+            root_dirs: vec![dir("", ".")],
+            source_dirs: vec![dir(&self.pom.source_directory, "src/main/java")],
+            resource_dirs,
+            test_source_dirs: vec![dir(&self.pom.test_source_directory, "src/test/java")],
+            exclude_dirs: vec![dir(&self.pom.build_directory, "target")],
+            ..Default::default()
+        };
+
+        MavenModule {
+            group_id: self.interpolate(&self.pom.group_id),
+            artifact_id: self.interpolate(&self.pom.artifact_id),
+            version: self.interpolate(&self.pom.version),
+            packaging: self.interpolate(&self.pom.packaging),
+            module: JavaModuleData {
+                module_name: self.interpolate(&self.pom.artifact_id),
+                content_roots: vec![content_root],
+            },
+            libraries,
+            managed_libraries: managed,
+            modules: self
+                .pom
+                .modules
+                .iter()
+                .map(|module| {
+                    let dir = normalize(&self.pom.dir.join(module));
+                    relative_path(&dir, root)
+                })
+                .collect(),
+            path,
+        }
+    }
+
+    /// without a version or a scope, they come from `managed`, the scope is `compile` at last
+    fn library(&self, dependency: &PomDependency, managed: Option<&[BaseLibrary]>) -> BaseLibrary {
+        let group = self.interpolate(&dependency.group_id);
+        let name = self.interpolate(&dependency.artifact_id);
+        let managed = managed.and_then(|managed| {
+            managed
+                .iter()
+                .rev()
+                .find(|library| library.group == group && library.name == name)
+        });
+
+        let mut version = self.interpolate(&dependency.version);
+        if version.is_empty() {
+            version = managed
+                .map(|library| library.version.clone())
+                .unwrap_or_default();
+        }
+        let scope = if !dependency.scope.is_empty() {
+            MavenFacet::scope(&self.interpolate(&dependency.scope))
+                .name()
+                .to_string()
+        } else {
+            managed
+                .map(|library| library.scope.clone())
+                .unwrap_or_else(|| LibraryScope::Main.name().to_string())
+        };
+
+        BaseLibrary {
+            name,
+            version,
+            group,
+            source: String::from("maven"),
+            scope,
+        }
+    }
+
+    /// `${name}` by the properties, unknown ones are kept
+    fn interpolate(&self, text: &str) -> String {
+        let mut output = text.to_string();
+        for _ in 0..MAX_PARENT_DEPTH {
+            let mut changed = false;
+            let mut result = String::new();
+            let mut rest = output.as_str();
+            while let Some(start) = rest.find("${") {
+                result.push_str(&rest[..start]);
+                let end = match rest[start..].find('}') {
+                    Some(end) => start + end,
+                    None => {
+                        result.push_str(&rest[start..]);
+                        rest = "";
+                        break;
+                    }
+                };
+                let name = &rest[start + 2..end];
+                match self.properties.get(name) {
+                    Some(value) => {
+                        result.push_str(value);
+                        changed = true;
+                    }
+                    None => result.push_str(&rest[start..=end]),
+                }
+                rest = &rest[end + 1..];
+            }
+            result.push_str(rest);
+            output = result;
+
+            if !changed {
+                break;
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::facet::java::maven_facet::EffectivePom;
+    use crate::facet::java::MavenFacet;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    fn fixture() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("java")
+            .join("maven")
+    }
+
+    #[test]
+    fn should_read_modules_of_reactor() {
+        let facet = MavenFacet::from_path(&fixture()).unwrap();
+
+        let modules: Vec<(&str, &str, &str)> = facet
+            .modules
+            .iter()
+            .map(|module| {
+                (
+                    module.artifact_id.as_str(),
+                    module.path.as_str(),
+                    module.packaging.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("shop", "", "pom"),
+                ("shop-core", "core", "jar"),
+                ("shop-web", "web", "war"),
+            ],
+            modules
+        );
+        assert_eq!(vec!["core", "web"], facet.modules[0].modules);
+
+        let web = facet.module("shop-web").unwrap();
+        assert_eq!("com.example.shop", web.group_id);
+        assert_eq!("1.2.0", web.version);
+    }
+
+    #[test]
+    fn should_resolve_versions_by_properties_and_management() {
+        let facet = MavenFacet::from_path(&fixture()).unwrap();
+        let web = facet.module("shop-web").unwrap();
+
+        let libraries: Vec<(&str, &str, &str, &str)> = web
+            .libraries
+            .iter()
+            .map(|library| {
+                (
+                    library.group.as_str(),
+                    library.name.as_str(),
+                    library.version.as_str(),
+                    library.scope.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("org.slf4j", "slf4j-api", "1.7.30", "Main"),
+                ("com.example.shop", "shop-core", "1.2.0", "Main"),
+                (
+                    "org.springframework",
+                    "spring-webmvc",
+                    "5.2.9.RELEASE",
+                    "Main"
+                ),
+                ("javax.servlet", "javax.servlet-api", "4.0.1", "Dev"),
+                ("junit", "junit", "4.13.1", "Test"),
+            ],
+            libraries
+        );
+        assert_eq!(2, web.managed_libraries.len());
+        assert!(web
+            .libraries
+            .iter()
+            .all(|library| library.source == "maven"));
+    }
+
+    #[test]
+    fn should_build_content_roots_with_defaults() {
+        let facet = MavenFacet::from_path(&fixture()).unwrap();
+
+        let core = &facet.module("shop-core").unwrap().module;
+        assert_eq!("shop-core", core.module_name);
+        let root = &core.content_roots[0];
+        assert_eq!(vec!["core"], root.root_dirs);
+        assert_eq!(vec!["core/src/main/java"], root.source_dirs);
+        assert_eq!(vec!["core/src/main/resources"], root.resource_dirs);
+        assert_eq!(vec!["core/src/test/java"], root.test_source_dirs);
+        assert_eq!(vec!["core/target"], root.exclude_dirs);
+
+        let web = &facet.module("shop-web").unwrap().module.content_roots[0];
+        assert_eq!(vec!["web/src/java"], web.source_dirs);
+        assert_eq!(vec!["web/test"], web.test_source_dirs);
+        assert_eq!(vec!["web/conf"], web.resource_dirs);
+    }
+
+    #[test]
+    fn should_keep_unterminated_placeholders() {
+        let mut pom = EffectivePom::default();
+        pom.properties
+            .insert(String::from("version"), String::from("1.0"));
+
+        assert_eq!("abc${def", pom.interpolate("abc${def"));
+        assert_eq!("1.0-${def", pom.interpolate("${version}-${def"));
+        assert_eq!("${unknown}-1.0", pom.interpolate("${unknown}-${version}"));
+    }
+
+    #[test]
+    fn should_skip_broken_parent_poms() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("shop");
+        fs::create_dir(&root).unwrap();
+        fs::write(dir.path().join("pom.xml"), "<project><artifactId>").unwrap();
+        fs::write(
+            root.join("pom.xml"),
+            r#"<project>
+    <parent>
+        <groupId>com.example</groupId>
+        <artifactId>parent</artifactId>
+        <version>1.0</version>
+        <relativePath>../pom.xml</relativePath>
+    </parent>
+    <artifactId>shop</artifactId>
+</project>"#,
+        )
+        .unwrap();
+
+        let facet = MavenFacet::from_path(&root).unwrap();
+        assert_eq!("shop", facet.modules[0].artifact_id);
+        assert_eq!("com.example", facet.modules[0].group_id);
+        assert_eq!(1, facet.errors.len());
+        assert!(facet.errors[0].contains("pom.xml"));
+    }
+
+    #[test]
+    fn should_fail_without_root_pom() {
+        assert!(MavenFacet::from_path(&fixture().join("core").join("src")).is_err());
+    }
+}
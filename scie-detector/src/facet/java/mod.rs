@@ -1,5 +1,7 @@
 pub mod java_facet;
 pub mod java_module_data;
+pub mod maven_facet;
 
 pub use java_facet::JavaFacet;
 pub use java_module_data::JavaModuleData;
+pub use maven_facet::{MavenFacet, MavenModule};
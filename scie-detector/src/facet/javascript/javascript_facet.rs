@@ -1,3 +1,51 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::dependency::base_library::{BaseLibrary, LibraryScope};
+
+/// packages of `devDependencies` which are only for tests, `@types/` of them are too
+const TEST_PACKAGES: [&str; 16] = [
+    "jest",
+    "ts-jest",
+    "babel-jest",
+    "mocha",
+    "chai",
+    "sinon",
+    "jasmine",
+    "karma",
+    "protractor",
+    "cypress",
+    "enzyme",
+    "ava",
+    "vitest",
+    "nyc",
+    "supertest",
+    "playwright",
+];
+
+/// prefixes of the test packages which come in families, such as `karma-chrome-launcher`
+const TEST_PACKAGE_PREFIXES: [&str; 7] = [
+    "@testing-library/",
+    "@playwright/",
+    "jest-",
+    "karma-",
+    "jasmine-",
+    "enzyme-",
+    "mocha-",
+];
+
+const NODE_PACKAGES: [&str; 6] = [
+    "express",
+    "koa",
+    "fastify",
+    "hapi",
+    "@hapi/hapi",
+    "@nestjs/core",
+];
+
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct JavaScriptFacet {
     pub is_frontend: bool,
@@ -17,6 +65,15 @@ pub struct JavaScriptFacet {
 
     pub has_grunt: bool,
     pub has_gulp: bool,
+
+    /// `name` of `package.json`
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub scripts: BTreeMap<String, String>,
+    /// versions are the locked ones of `package-lock.json` or `yarn.lock` if they exist
+    #[serde(default)]
+    pub libraries: Vec<BaseLibrary>,
 }
 
 impl Default for JavaScriptFacet {
@@ -36,6 +93,319 @@ impl Default for JavaScriptFacet {
             is_javascript: false,
             has_grunt: false,
             has_gulp: false,
+            name: "".to_string(),
+            scripts: Default::default(),
+            libraries: vec![],
+        }
+    }
+}
+
+impl JavaScriptFacet {
+    /// `dir` is the one of `package.json`, config files beside it are checked too
+    pub fn from_path(dir: &Path) -> Result<JavaScriptFacet, String> {
+        let path = dir.join("package.json");
+        let code =
+            fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let package: Value =
+            serde_json::from_str(&code).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let locked = JavaScriptFacet::locked_versions(dir);
+
+        let mut facet = JavaScriptFacet {
+            name: package["name"].as_str().unwrap_or_default().to_string(),
+            ..Default::default()
+        };
+        if let Some(scripts) = package["scripts"].as_object() {
+            for (name, script) in scripts.iter() {
+                let script = script.as_str().unwrap_or_default();
+                facet.scripts.insert(name.clone(), script.to_string());
+            }
+        }
+
+        for (key, is_dev) in [
+            ("dependencies", false),
+            ("optionalDependencies", false),
+            ("devDependencies", true),
+        ]
+        .iter()
+        {
+            let dependencies = match package[key].as_object() {
+                Some(dependencies) => dependencies,
+                None => continue,
+            };
+
+            for (name, range) in dependencies.iter() {
+                let range = range.as_str().unwrap_or_default();
+                let scope = match is_dev {
+                    true if JavaScriptFacet::is_test_package(name) => LibraryScope::Test,
+                    true => LibraryScope::Dev,
+                    false => LibraryScope::Main,
+                };
+                let version = locked
+                    .get(&(name.clone(), range.to_string()))
+                    .or_else(|| locked.get(&(name.clone(), String::new())))
+                    .cloned()
+                    .unwrap_or_else(|| range.to_string());
+
+                facet.libraries.push(BaseLibrary {
+                    name: name.clone(),
+                    version,
+                    group: "".to_string(),
+                    source: "npm".to_string(),
+                    scope: scope.name().to_string(),
+                });
+            }
+        }
+
+        facet.detect(dir, &package);
+        Ok(facet)
+    }
+
+    /// packages of tests, such as `jest`, `karma-jasmine` or `@types/mocha`
+    pub fn is_test_package(name: &str) -> bool {
+        let name = name.strip_prefix("@types/").unwrap_or(name);
+        TEST_PACKAGES.contains(&name)
+            || TEST_PACKAGE_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+    }
+
+    fn detect(&mut self, dir: &Path, package: &Value) {
+        let has_file = |names: &[&str]| names.iter().any(|name| dir.join(name).is_file());
+        let has_package = |names: &[&str]| {
+            self.libraries
+                .iter()
+                .any(|library| names.contains(&library.name.as_str()))
+        };
+        let has_script = |command: &str| {
+            self.scripts.values().any(|script| {
+                script
+                    .split(|char: char| char.is_whitespace() || char == '&' || char == ';')
+                    .any(|word| word == command)
+            })
+        };
+
+        let is_angular = has_file(&["angular.json", ".angular-cli.json"])
+            || has_package(&["@angular/core", "angular"])
+            || has_script("ng");
+        let is_react = has_package(&["react"]) || has_script("react-scripts");
+        let is_vue = has_package(&["vue"]) || has_script("vue-cli-service");
+        let is_ionic = has_file(&["ionic.config.json"])
+            || has_package(&[
+                "@ionic/angular",
+                "@ionic/react",
+                "@ionic/vue",
+                "@ionic/core",
+                "ionic-angular",
+            ]);
+        let is_cordova = has_file(&["config.xml"])
+            || has_package(&["cordova", "cordova-android", "cordova-ios"])
+            || has_script("cordova");
+        let is_typescript = has_file(&["tsconfig.json"]) || has_package(&["typescript"]);
+        let has_grunt = has_file(&["Gruntfile.js", "Gruntfile.coffee"])
+            || has_package(&["grunt"])
+            || has_script("grunt");
+        let has_gulp = has_file(&["gulpfile.js", "gulpfile.ts", "gulpfile.babel.js"])
+            || has_package(&["gulp"])
+            || has_script("gulp");
+        let is_node = package["engines"]["node"].is_string() || has_package(&NODE_PACKAGES);
+        let is_hybrid = is_ionic || is_cordova || has_package(&["@capacitor/core"]);
+        let is_frontend = is_angular || is_react || is_vue || is_ionic;
+
+        self.is_angular = is_angular;
+        self.is_react = is_react;
+        self.is_vue = is_vue;
+        self.is_ionic = is_ionic;
+        self.is_cordova = is_cordova;
+        self.is_hybrid = is_hybrid;
+        self.is_bower = has_file(&["bower.json"]);
+        self.is_typescript = is_typescript;
+        self.is_javascript = !is_typescript;
+        self.has_grunt = has_grunt;
+        self.has_gulp = has_gulp;
+        self.is_node = is_node;
+        self.is_frontend = is_frontend;
+        self.is_browser =
+            is_frontend || !package["browser"].is_null() || !package["browserslist"].is_null();
+    }
+
+    /// versions by package name and range, the range is empty if the lockfile has no ranges
+    fn locked_versions(dir: &Path) -> BTreeMap<(String, String), String> {
+        if let Ok(code) = fs::read_to_string(dir.join("package-lock.json")) {
+            return JavaScriptFacet::parse_package_lock(&code);
+        }
+        if let Ok(code) = fs::read_to_string(dir.join("yarn.lock")) {
+            return JavaScriptFacet::parse_yarn_lock(&code);
         }
+        Default::default()
+    }
+
+    /// `packages` of lockfile v2 and v3, or `dependencies` of v1
+    fn parse_package_lock(code: &str) -> BTreeMap<(String, String), String> {
+        let mut versions: BTreeMap<(String, String), String> = Default::default();
+        let lock: Value = match serde_json::from_str(code) {
+            Ok(lock) => lock,
+            Err(_) => return versions,
+        };
+
+        if let Some(packages) = lock["packages"].as_object() {
+            for (path, package) in packages.iter() {
+                let name = match path.strip_prefix("node_modules/") {
+                    Some(name) if !name.contains("/node_modules/") => name,
+                    _ => continue,
+                };
+                if let Some(version) = package["version"].as_str() {
+                    versions.insert((name.to_string(), String::new()), version.to_string());
+                }
+            }
+        } else if let Some(dependencies) = lock["dependencies"].as_object() {
+            for (name, package) in dependencies.iter() {
+                if let Some(version) = package["version"].as_str() {
+                    versions.insert((name.clone(), String::new()), version.to_string());
+                }
+            }
+        }
+
+        versions
+    }
+
+    /// entries of Yarn 1 and Berry, such as `"react@^16.0.0", react@^16.8.0:`
+    fn parse_yarn_lock(code: &str) -> BTreeMap<(String, String), String> {
+        let mut versions: BTreeMap<(String, String), String> = Default::default();
+        let mut specs: Vec<(String, String)> = vec![];
+        for line in code.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if !line.starts_with(' ') {
+                specs = line
+                    .trim_end_matches(':')
+                    .split(", ")
+                    .filter_map(|spec| {
+                        let spec = spec.trim().trim_matches('"');
+                        // the `@` of a scope is not the one of a range
+                        let first = spec.chars().next()?.len_utf8();
+                        let at = spec.get(first..)?.find('@')? + first;
+                        let range = &spec[at + 1..];
+                        let range = range.strip_prefix("npm:").unwrap_or(range);
+                        Some((spec[..at].to_string(), range.to_string()))
+                    })
+                    .collect();
+                continue;
+            }
+
+            let line = line.trim();
+            let version = match line.strip_prefix("version") {
+                Some(version) if line.starts_with("version ") || line.starts_with("version:") => {
+                    version.trim_start_matches(':').trim().trim_matches('"')
+                }
+                _ => continue,
+            };
+            for (name, range) in specs.drain(..) {
+                versions
+                    .entry((name.clone(), String::new()))
+                    .or_insert_with(|| version.to_string());
+                versions.insert((name, range), version.to_string());
+            }
+        }
+
+        versions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::facet::javascript::JavaScriptFacet;
+    use std::path::PathBuf;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("javascript")
+            .join(name)
+    }
+
+    #[test]
+    fn should_detect_ionic_angular_by_packages_and_config_files() {
+        let facet = JavaScriptFacet::from_path(&fixture("ionic")).unwrap();
+
+        assert_eq!("ionic-shop", facet.name);
+        assert!(facet.is_angular && facet.is_ionic && facet.is_cordova && facet.is_hybrid);
+        assert!(facet.is_frontend && facet.is_browser);
+        assert!(facet.is_typescript && !facet.is_javascript);
+        assert!(facet.has_gulp && !facet.has_grunt);
+        assert!(!facet.is_react && !facet.is_vue && !facet.is_node && !facet.is_bower);
+        assert_eq!("ng serve", facet.scripts["start"]);
+
+        let libraries: Vec<(&str, &str, &str)> = facet
+            .libraries
+            .iter()
+            .map(|library| {
+                (
+                    library.name.as_str(),
+                    library.version.as_str(),
+                    library.scope.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("@angular/core", "10.1.4", "Main"),
+                ("@ionic/angular", "5.3.5", "Main"),
+                ("cordova-android", "^9.0.0", "Main"),
+                ("@types/jest", "^26.0.14", "Test"),
+                ("gulp", "4.0.2", "Dev"),
+                ("jest", "26.4.2", "Test"),
+                ("karma-jasmine", "~4.0.0", "Test"),
+                ("typescript", "4.0.3", "Dev"),
+            ],
+            libraries
+        );
+    }
+
+    #[test]
+    fn should_detect_react_node_by_packages_and_scripts() {
+        let facet = JavaScriptFacet::from_path(&fixture("react")).unwrap();
+
+        assert!(facet.is_react && facet.is_frontend && facet.is_node);
+        assert!(facet.has_grunt && facet.is_bower);
+        assert!(facet.is_javascript && !facet.is_typescript);
+        assert!(!facet.is_angular && !facet.is_hybrid);
+
+        let versions: Vec<(&str, &str)> = facet
+            .libraries
+            .iter()
+            .map(|library| (library.name.as_str(), library.version.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("@babel/runtime", "7.11.2"),
+                ("express", "4.17.1"),
+                ("react", "16.13.1"),
+                ("@testing-library/react", "11.0.4"),
+                ("grunt", "1.3.0"),
+            ],
+            versions
+        );
+    }
+
+    #[test]
+    fn should_skip_broken_specs_of_yarn_lock() {
+        let versions = JavaScriptFacet::parse_yarn_lock(
+            "\"\":\n  version \"1.0.0\"\n\"é@^1.0.0\":\n  version \"1.2.0\"\n",
+        );
+        assert_eq!(
+            Some(&"1.2.0".to_string()),
+            versions.get(&("é".to_string(), "^1.0.0".to_string()))
+        );
+        assert_eq!(2, versions.len());
+    }
+
+    #[test]
+    fn should_fail_without_package_json() {
+        assert!(JavaScriptFacet::from_path(&fixture("missing")).is_err());
     }
 }
@@ -1,6 +1,8 @@
+pub mod dotnet_facet;
 pub mod go_facet;
+pub mod php_facet;
 pub mod python_facet;
-
+pub mod ruby_facet;
 pub mod rust_facet;
 
 /// Java
@@ -9,6 +11,7 @@ pub mod jvm_facet;
 
 pub use java::JavaFacet;
 pub use java::JavaModuleData;
+pub use java::MavenFacet;
 pub use jvm_facet::JvmFacet;
 
 // JavaScript
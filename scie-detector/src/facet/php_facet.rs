@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::dependency::base_library::{BaseLibrary, LibraryScope};
+
+/// packages of `require-dev` which are only for tests
+const TEST_PACKAGES: [&str; 4] = [
+    "phpunit/phpunit",
+    "mockery/mockery",
+    "behat/behat",
+    "codeception/codeception",
+];
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct PhpFacet {
+    /// `name` of `composer.json`
+    pub name: String,
+    /// dirs of `path` repositories, which are the local packages of a monorepo, globs are
+    /// expanded to the dirs with a `composer.json`
+    pub modules: Vec<String>,
+    /// of `autoload` and `autoload-dev`, by PSR-4 or PSR-0 namespaces
+    pub source_dirs: Vec<String>,
+    pub test_source_dirs: Vec<String>,
+    /// platform requirements, such as `php` and `ext-json`, are left out, versions are the
+    /// locked ones of `composer.lock` if it exists
+    pub libraries: Vec<BaseLibrary>,
+}
+
+impl PhpFacet {
+    /// `dir` is the one of `composer.json`
+    pub fn from_path(dir: &Path) -> Result<PhpFacet, String> {
+        let path = dir.join("composer.json");
+        let code =
+            fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let composer: Value =
+            serde_json::from_str(&code).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let locked = fs::read_to_string(dir.join("composer.lock"))
+            .ok()
+            .and_then(|code| serde_json::from_str::<Value>(&code).ok())
+            .map(|lock| PhpFacet::locked_versions(&lock))
+            .unwrap_or_default();
+
+        let mut facet = PhpFacet {
+            name: composer["name"].as_str().unwrap_or_default().to_string(),
+            ..Default::default()
+        };
+
+        for repository in composer["repositories"].as_array().into_iter().flatten() {
+            if repository["type"] != "path" {
+                continue;
+            }
+            if let Some(url) = repository["url"].as_str() {
+                facet.modules.extend(PhpFacet::expand_module(dir, url));
+            }
+        }
+
+        facet.source_dirs = PhpFacet::autoload_dirs(&composer["autoload"]);
+        facet.test_source_dirs = PhpFacet::autoload_dirs(&composer["autoload-dev"]);
+
+        for (key, is_dev) in [("require", false), ("require-dev", true)].iter() {
+            for (name, constraint) in composer[key].as_object().into_iter().flatten() {
+                if PhpFacet::is_platform(name) {
+                    continue;
+                }
+
+                let scope = match is_dev {
+                    true if TEST_PACKAGES.contains(&name.as_str()) => LibraryScope::Test,
+                    true => LibraryScope::Dev,
+                    false => LibraryScope::Main,
+                };
+                let version = locked
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| constraint.as_str().unwrap_or_default().to_string());
+                facet.libraries.push(BaseLibrary {
+                    name: name.clone(),
+                    version,
+                    group: "".to_string(),
+                    source: "packagist".to_string(),
+                    scope: scope.name().to_string(),
+                });
+            }
+        }
+
+        Ok(facet)
+    }
+
+    /// `php`, `hhvm`, `ext-*`, `lib-*` and `composer-*` are not packages
+    pub fn is_platform(name: &str) -> bool {
+        !name.contains('/')
+    }
+
+    /// `packages` and `packages-dev` of `composer.lock`
+    fn locked_versions(lock: &Value) -> BTreeMap<String, String> {
+        let mut versions: BTreeMap<String, String> = Default::default();
+        for key in ["packages", "packages-dev"].iter() {
+            for package in lock[key].as_array().into_iter().flatten() {
+                if let (Some(name), Some(version)) =
+                    (package["name"].as_str(), package["version"].as_str())
+                {
+                    versions.insert(name.to_string(), version.to_string());
+                }
+            }
+        }
+        versions
+    }
+
+    /// `{"psr-4": {"App\\": "src/"}}`, a namespace may have a list of dirs
+    fn autoload_dirs(autoload: &Value) -> Vec<String> {
+        let mut dirs: Vec<String> = vec![];
+        for key in ["psr-4", "psr-0"].iter() {
+            for paths in autoload[key]
+                .as_object()
+                .into_iter()
+                .flat_map(|it| it.values())
+            {
+                let paths = match paths {
+                    Value::Array(paths) => paths.iter().collect(),
+                    path => vec![path],
+                };
+                for path in paths.into_iter().filter_map(|path| path.as_str()) {
+                    let path = path.trim_end_matches('/').to_string();
+                    if !dirs.contains(&path) {
+                        dirs.push(path);
+                    }
+                }
+            }
+        }
+        dirs
+    }
+
+    fn expand_module(dir: &Path, url: &str) -> Vec<String> {
+        let url = url.trim_end_matches('/');
+        let parent = match url.strip_suffix("/*") {
+            Some(parent) => parent,
+            None => return vec![url.to_string()],
+        };
+
+        let mut modules: Vec<String> = fs::read_dir(dir.join(parent))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().join("composer.json").is_file())
+                    .map(|entry| format!("{}/{}", parent, entry.file_name().to_string_lossy()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        modules.sort();
+        modules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::facet::php_facet::PhpFacet;
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_read_composer_with_lock_and_path_repositories() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("php")
+            .join("shop");
+        let facet = PhpFacet::from_path(&dir).unwrap();
+
+        assert_eq!("example/shop", facet.name);
+        assert_eq!(vec!["packages/billing", "packages/catalog"], facet.modules);
+        assert_eq!(vec!["src"], facet.source_dirs);
+        assert_eq!(vec!["tests"], facet.test_source_dirs);
+
+        let libraries: Vec<(&str, &str, &str)> = facet
+            .libraries
+            .iter()
+            .map(|library| {
+                (
+                    library.name.as_str(),
+                    library.version.as_str(),
+                    library.scope.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("example/billing", "dev-main", "Main"),
+                ("monolog/monolog", "2.1.1", "Main"),
+                ("symfony/framework-bundle", "v5.1.7", "Main"),
+                ("phpunit/phpunit", "9.4.1", "Test"),
+                ("squizlabs/php_codesniffer", "^3.5", "Dev"),
+            ],
+            libraries
+        );
+    }
+}
@@ -1,4 +1,580 @@
-#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::dependency::base_library::{BaseLibrary, LibraryScope};
+use scie_infra::paths::normalize;
+use scie_infra::toml::parse_toml;
+
+lazy_static! {
+    /// `name[extras] (specifier) ; markers` of PEP 508, or `name @ url`
+    static ref REQUIREMENT: Regex = Regex::new(
+        r"^([A-Za-z0-9][A-Za-z0-9._-]*)\s*(\[[^\]]*\])?\s*(@\s*(\S+)|\(?([^;()]*)\)?)\s*(;.*)?$"
+    )
+    .unwrap();
+    static ref EGG: Regex = Regex::new(r"#egg=([A-Za-z0-9._-]+)").unwrap();
+    /// `install_requires=[...]` and `tests_require=[...]` of `setup.py`
+    static ref SETUP_LIST: Regex =
+        Regex::new(r"(install_requires|tests_require)\s*=\s*\[([^\]]*)\]").unwrap();
+    static ref SETUP_NAME: Regex = Regex::new(r#"\bname\s*=\s*['"]([^'"]+)['"]"#).unwrap();
+    static ref QUOTED: Regex = Regex::new(r#"['"]([^'"]+)['"]"#).unwrap();
+}
+
+/// web frameworks by their distributions and files beside the manifests
+pub const FRAMEWORKS: [(&str, &str, &[&str]); 3] = [
+    ("workspace.python.django", "django", &["manage.py"]),
+    ("workspace.python.flask", "flask", &[".flaskenv"]),
+    ("workspace.python.fastapi", "fastapi", &[]),
+];
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 pub struct PythonFacet {
     pub has_requirements: bool,
+    #[serde(default)]
+    pub has_pyproject: bool,
+    #[serde(default)]
+    pub has_setup: bool,
+    #[serde(default)]
+    pub has_pipfile: bool,
+    /// of `[project]`, `[tool.poetry]`, `setup.cfg` or `setup.py`
+    #[serde(default)]
+    pub name: String,
+    /// such as `workspace.python.django`
+    #[serde(default)]
+    pub frameworks: Vec<String>,
+    /// versions are the locked ones of `poetry.lock` or `Pipfile.lock` if they exist, or the
+    /// specifiers as they are written
+    #[serde(default)]
+    pub libraries: Vec<BaseLibrary>,
+    /// manifests which can not be read or parsed, the other ones are still read
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+impl PythonFacet {
+    /// every manifest in `dir`: `requirements*.txt` and the ones they include, `pyproject.toml`,
+    /// `setup.cfg`, `setup.py` and `Pipfile`
+    pub fn from_path(dir: &Path) -> Result<PythonFacet, String> {
+        let mut facet = PythonFacet::default();
+
+        let mut requirements: Vec<PathBuf> = vec![];
+        for dir in [dir.to_path_buf(), dir.join("requirements")].iter() {
+            let mut files: Vec<PathBuf> = fs::read_dir(dir)
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok())
+                        .map(|e| e.path())
+                        .collect()
+                })
+                .unwrap_or_default();
+            files.sort();
+            requirements.extend(files.into_iter().filter(|file| {
+                let name = file.file_name().unwrap_or_default().to_string_lossy();
+                name.ends_with(".txt")
+                    && (name.contains("requirements") || dir.ends_with("requirements"))
+            }));
+        }
+        let mut visited: BTreeSet<PathBuf> = Default::default();
+        for path in requirements.iter() {
+            facet.has_requirements = true;
+            facet.read_requirements(path, &mut visited);
+        }
+
+        let pyproject = dir.join("pyproject.toml");
+        if let Ok(code) = fs::read_to_string(&pyproject) {
+            facet.has_pyproject = true;
+            match parse_toml(&code) {
+                Ok(value) => facet.read_pyproject(&value),
+                Err(err) => facet
+                    .errors
+                    .push(format!("{}: {}", pyproject.display(), err)),
+            }
+        }
+
+        if let Ok(code) = fs::read_to_string(dir.join("setup.cfg")) {
+            facet.has_setup = true;
+            facet.read_setup_cfg(&code);
+        }
+        if let Ok(code) = fs::read_to_string(dir.join("setup.py")) {
+            facet.has_setup = true;
+            facet.read_setup_py(&code);
+        }
+
+        let pipfile = dir.join("Pipfile");
+        if let Ok(code) = fs::read_to_string(&pipfile) {
+            facet.has_pipfile = true;
+            match parse_toml(&code) {
+                Ok(value) => {
+                    for (key, scope) in [
+                        ("packages", LibraryScope::Main),
+                        ("dev-packages", LibraryScope::Dev),
+                    ]
+                    .iter()
+                    {
+                        facet.push_table(&value[key], *scope);
+                    }
+                }
+                Err(err) => facet.errors.push(format!("{}: {}", pipfile.display(), err)),
+            }
+        }
+
+        facet.lock_versions(dir);
+        facet.dedup();
+        for (tag, distribution, files) in FRAMEWORKS.iter() {
+            let has_distribution = facet
+                .libraries
+                .iter()
+                .any(|library| PythonFacet::normalize(&library.name) == *distribution);
+            if has_distribution || files.iter().any(|file| dir.join(file).is_file()) {
+                facet.frameworks.push(tag.to_string());
+            }
+        }
+
+        Ok(facet)
+    }
+
+    /// a line of requirements or an item of `dependencies`, such as `Django>=3.0,<4; python_version>"3.6"`
+    pub fn parse_requirement(line: &str, scope: LibraryScope) -> Option<BaseLibrary> {
+        let line = line.trim();
+        let (name, version, source) = if let Some(editable) = line
+            .strip_prefix("-e")
+            .or_else(|| line.strip_prefix("--editable"))
+        {
+            let name = EGG.captures(editable)?.get(1)?.as_str();
+            (name, "", "git")
+        } else {
+            let captures = REQUIREMENT.captures(line)?;
+            let name = captures.get(1)?.as_str();
+            match captures.get(4) {
+                Some(url) => (name, url.as_str(), "url"),
+                None => (name, captures.get(5).map_or("", |it| it.as_str()), "pypi"),
+            }
+        };
+
+        let version = version.trim();
+        let version = match version.strip_prefix("==") {
+            Some(pinned) if !pinned.contains(',') => pinned.trim(),
+            _ => version,
+        };
+
+        Some(BaseLibrary {
+            name: name.to_string(),
+            version: version.to_string(),
+            group: "".to_string(),
+            source: source.to_string(),
+            scope: scope.name().to_string(),
+        })
+    }
+
+    /// by the name of a file or a group, such as `requirements-dev.txt` or `[tool.poetry.group.test]`
+    pub fn scope(name: &str) -> LibraryScope {
+        let name = name.to_lowercase();
+        if name.contains("test") {
+            LibraryScope::Test
+        } else if ["dev", "lint", "doc", "type"]
+            .iter()
+            .any(|dev| name.contains(dev))
+        {
+            LibraryScope::Dev
+        } else {
+            LibraryScope::Main
+        }
+    }
+
+    /// PEP 503, `Flask_SQLAlchemy` is `flask-sqlalchemy`
+    pub fn normalize(name: &str) -> String {
+        let mut normalized = String::with_capacity(name.len());
+        for char in name.chars() {
+            let char = match char {
+                '_' | '.' => '-',
+                _ => char.to_ascii_lowercase(),
+            };
+            if !(char == '-' && normalized.ends_with('-')) {
+                normalized.push(char);
+            }
+        }
+        normalized
+    }
+
+    /// `-r other.txt` and `--requirement other.txt` are read once, with their own scopes
+    fn read_requirements(&mut self, path: &Path, visited: &mut BTreeSet<PathBuf>) {
+        let path = normalize(path);
+        if !visited.insert(path.clone()) {
+            return;
+        }
+        let code = match fs::read_to_string(&path) {
+            Ok(code) => code,
+            Err(err) => {
+                self.errors.push(format!("{}: {}", path.display(), err));
+                return;
+            }
+        };
+        let scope = PythonFacet::scope(&path.file_name().unwrap_or_default().to_string_lossy());
+
+        for line in code.lines() {
+            let line = match line.find(" #") {
+                Some(comment) => &line[..comment],
+                None => line,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let include = line
+                .strip_prefix("-r")
+                .or_else(|| line.strip_prefix("--requirement"));
+            if let Some(include) = include {
+                let include = include.trim_start_matches('=').trim();
+                let dir = path.parent().unwrap_or_else(|| Path::new(""));
+                self.read_requirements(&dir.join(include), visited);
+                continue;
+            }
+            // other options, such as `-c constraints.txt` or `--index-url`
+            if line.starts_with('-') && !line.starts_with("-e") && !line.starts_with("--editable") {
+                continue;
+            }
+
+            if let Some(library) = PythonFacet::parse_requirement(line, scope) {
+                self.libraries.push(library);
+            }
+        }
+    }
+
+    /// `[project]` of PEP 621 and `[tool.poetry]`
+    fn read_pyproject(&mut self, value: &Value) {
+        let project = &value["project"];
+        let poetry = &value["tool"]["poetry"];
+        if let Some(name) = project["name"].as_str().or_else(|| poetry["name"].as_str()) {
+            self.name = name.to_string();
+        }
+
+        for dependency in project["dependencies"].as_array().into_iter().flatten() {
+            self.push_requirement(dependency, LibraryScope::Main);
+        }
+        if let Some(extras) = project["optional-dependencies"].as_object() {
+            for (extra, dependencies) in extras.iter() {
+                let scope = PythonFacet::scope(extra);
+                for dependency in dependencies.as_array().into_iter().flatten() {
+                    self.push_requirement(dependency, scope);
+                }
+            }
+        }
+
+        self.push_table(&poetry["dependencies"], LibraryScope::Main);
+        self.push_table(&poetry["dev-dependencies"], LibraryScope::Dev);
+        if let Some(groups) = poetry["group"].as_object() {
+            for (group, table) in groups.iter() {
+                let scope = match PythonFacet::scope(group) {
+                    LibraryScope::Main => LibraryScope::Dev,
+                    scope => scope,
+                };
+                self.push_table(&table["dependencies"], scope);
+            }
+        }
+    }
+
+    /// `[metadata] name`, `[options] install_requires` and `tests_require`, and
+    /// `[options.extras_require]`, a requirement for each line
+    fn read_setup_cfg(&mut self, code: &str) {
+        let mut section = String::new();
+        let mut key = String::new();
+        for line in code.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+                continue;
+            }
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                section = trimmed[1..trimmed.len() - 1].trim().to_string();
+                key.clear();
+                continue;
+            }
+
+            let value = if line.starts_with(char::is_whitespace) {
+                trimmed
+            } else {
+                let mut parts = trimmed.splitn(2, ['=', ':'].as_ref());
+                key = parts.next().unwrap_or_default().trim().to_string();
+                parts.next().unwrap_or_default().trim()
+            };
+            if value.is_empty() {
+                continue;
+            }
+
+            let scope = match (section.as_str(), key.as_str()) {
+                ("metadata", "name") => {
+                    self.name = value.to_string();
+                    continue;
+                }
+                ("options", "install_requires") => LibraryScope::Main,
+                ("options", "tests_require") => LibraryScope::Test,
+                ("options.extras_require", extra) => PythonFacet::scope(extra),
+                _ => continue,
+            };
+            if let Some(library) = PythonFacet::parse_requirement(value, scope) {
+                self.libraries.push(library);
+            }
+        }
+    }
+
+    /// the string lists of `setup()`, the ones which are built by code are left out
+    fn read_setup_py(&mut self, code: &str) {
+        if self.name.is_empty() {
+            if let Some(name) = SETUP_NAME.captures(code) {
+                self.name = name[1].to_string();
+            }
+        }
+
+        for captures in SETUP_LIST.captures_iter(code) {
+            let scope = match &captures[1] {
+                "tests_require" => LibraryScope::Test,
+                _ => LibraryScope::Main,
+            };
+            for item in QUOTED.captures_iter(&captures[2]) {
+                if let Some(library) = PythonFacet::parse_requirement(&item[1], scope) {
+                    self.libraries.push(library);
+                }
+            }
+        }
+    }
+
+    fn push_requirement(&mut self, dependency: &Value, scope: LibraryScope) {
+        let library = dependency
+            .as_str()
+            .and_then(|line| PythonFacet::parse_requirement(line, scope));
+        if let Some(library) = library {
+            self.libraries.push(library);
+        }
+    }
+
+    /// `name = "^1.0"` or `name = { version = "^1.0", git = ".." }` of Poetry and Pipfile
+    fn push_table(&mut self, table: &Value, scope: LibraryScope) {
+        let table = match table.as_object() {
+            Some(table) => table,
+            None => return,
+        };
+
+        for (name, spec) in table.iter() {
+            if name == "python" {
+                continue;
+            }
+            let version = spec
+                .as_str()
+                .or_else(|| spec["version"].as_str())
+                .unwrap_or_default();
+            let source = if spec["git"].is_string() {
+                "git"
+            } else if spec["path"].is_string() {
+                "path"
+            } else {
+                "pypi"
+            };
+
+            self.libraries.push(BaseLibrary {
+                name: name.clone(),
+                version: if version == "*" { "" } else { version }.to_string(),
+                group: "".to_string(),
+                source: source.to_string(),
+                scope: scope.name().to_string(),
+            });
+        }
+    }
+
+    /// `[[package]]` of `poetry.lock`, or `default` and `develop` of `Pipfile.lock`
+    fn lock_versions(&mut self, dir: &Path) {
+        let mut locked: BTreeMap<String, String> = Default::default();
+        if let Some(lock) = fs::read_to_string(dir.join("poetry.lock"))
+            .ok()
+            .and_then(|code| parse_toml(&code).ok())
+        {
+            for package in lock["package"].as_array().into_iter().flatten() {
+                if let (Some(name), Some(version)) =
+                    (package["name"].as_str(), package["version"].as_str())
+                {
+                    locked.insert(PythonFacet::normalize(name), version.to_string());
+                }
+            }
+        } else if let Some(lock) = fs::read_to_string(dir.join("Pipfile.lock"))
+            .ok()
+            .and_then(|code| serde_json::from_str::<Value>(&code).ok())
+        {
+            for key in ["default", "develop"].iter() {
+                for (name, package) in lock[key].as_object().into_iter().flatten() {
+                    if let Some(version) = package["version"].as_str() {
+                        let version = version.trim_start_matches("==");
+                        locked.insert(PythonFacet::normalize(name), version.to_string());
+                    }
+                }
+            }
+        }
+
+        for library in self.libraries.iter_mut() {
+            if let Some(version) = locked.get(&PythonFacet::normalize(&library.name)) {
+                library.version = version.clone();
+            }
+        }
+    }
+
+    /// the same distribution may be in several manifests, the first one is kept
+    fn dedup(&mut self) {
+        let mut seen: BTreeSet<String> = Default::default();
+        self.libraries
+            .retain(|library| seen.insert(PythonFacet::normalize(&library.name)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dependency::base_library::LibraryScope;
+    use crate::facet::python_facet::PythonFacet;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("python")
+            .join(name)
+    }
+
+    fn libraries(facet: &PythonFacet) -> Vec<(&str, &str, &str)> {
+        facet
+            .libraries
+            .iter()
+            .map(|library| {
+                (
+                    library.name.as_str(),
+                    library.version.as_str(),
+                    library.scope.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_parse_requirement_lines() {
+        let library = |line: &str| {
+            let library = PythonFacet::parse_requirement(line, LibraryScope::Main).unwrap();
+            (library.name, library.version, library.source)
+        };
+
+        assert_eq!(
+            (
+                "Django".to_string(),
+                ">=3.0,<4".to_string(),
+                "pypi".to_string()
+            ),
+            library("Django>=3.0,<4; python_version > \"3.6\"")
+        );
+        assert_eq!(
+            (
+                "requests".to_string(),
+                "2.24.0".to_string(),
+                "pypi".to_string()
+            ),
+            library("requests[security] == 2.24.0")
+        );
+        assert_eq!(
+            ("shop".to_string(), "".to_string(), "git".to_string()),
+            library("-e git+https://github.com/example/shop.git#egg=shop")
+        );
+        assert_eq!(
+            "url",
+            library("pip @ https://github.com/pypa/pip/archive/1.3.1.zip").2
+        );
+        assert_eq!(
+            "flask-sqlalchemy",
+            PythonFacet::normalize("Flask_SQLAlchemy")
+        );
+    }
+
+    #[test]
+    fn should_read_requirements_with_includes_and_setup_cfg() {
+        let facet = PythonFacet::from_path(&fixture("django")).unwrap();
+
+        assert!(facet.has_requirements && facet.has_setup);
+        assert_eq!("shop", facet.name);
+        assert_eq!(vec!["workspace.python.django"], facet.frameworks);
+        assert_eq!(
+            vec![
+                ("Django", "3.1.2", "Main"),
+                ("psycopg2-binary", ">=2.8", "Main"),
+                ("pytest", "6.1.1", "Test"),
+                ("celery", "", "Main"),
+                ("flake8", "", "Dev"),
+            ],
+            libraries(&facet)
+        );
+    }
+
+    #[test]
+    fn should_read_pep_621_and_poetry_by_lock() {
+        let facet = PythonFacet::from_path(&fixture("fastapi")).unwrap();
+
+        assert!(facet.has_pyproject && !facet.has_requirements);
+        assert_eq!("shop-api", facet.name);
+        assert_eq!(vec!["workspace.python.fastapi"], facet.frameworks);
+        assert_eq!(
+            vec![
+                ("fastapi", "0.61.1", "Main"),
+                ("uvicorn", "0.12.2", "Main"),
+                ("pytest", "6.1.1", "Test"),
+                ("black", "20.8b1", "Dev"),
+            ],
+            libraries(&facet)
+        );
+    }
+
+    #[test]
+    fn should_read_pipfile_and_setup_py() {
+        let facet = PythonFacet::from_path(&fixture("flask")).unwrap();
+
+        assert!(facet.has_pipfile && facet.has_setup);
+        assert_eq!("shop-web", facet.name);
+        assert_eq!(vec!["workspace.python.flask"], facet.frameworks);
+        assert_eq!(
+            vec![
+                ("click", ">=7.0", "Main"),
+                ("flask", "1.1.2", "Main"),
+                ("pytest-flask", "", "Test"),
+                ("gunicorn", "20.0.4", "Main"),
+                ("mypy", "0.790", "Dev"),
+            ],
+            libraries(&facet)
+        );
+    }
+
+    #[test]
+    fn should_record_broken_manifests_and_read_the_others() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("requirements")).unwrap();
+        fs::write(
+            dir.path().join("requirements.txt"),
+            "-r requirements/dev.txt\n-r missing.txt\nrequests==2.25.1\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("requirements").join("dev.txt"),
+            "-r ../requirements.txt\nblack==20.8b1\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("pyproject.toml"), "[project\nname = \n").unwrap();
+
+        let facet = PythonFacet::from_path(dir.path()).unwrap();
+
+        assert_eq!(
+            vec![("black", "20.8b1", "Dev"), ("requests", "2.25.1", "Main")],
+            libraries(&facet)
+        );
+        assert_eq!(2, facet.errors.len());
+        assert!(facet.errors[0].contains("missing.txt"));
+        assert!(facet.errors[1].contains("pyproject.toml"));
+    }
 }
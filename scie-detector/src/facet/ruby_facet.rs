@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::dependency::base_library::{BaseLibrary, LibraryScope};
+
+lazy_static! {
+    static ref GEM: Regex = Regex::new(r#"^gem\s*\(?\s*['"]([^'"]+)['"](.*)$"#).unwrap();
+    static ref RUBY: Regex = Regex::new(r#"^ruby\s*\(?\s*['"]([^'"]+)['"]"#).unwrap();
+    static ref QUOTED: Regex = Regex::new(r#"^['"]([^'"]*)['"]$"#).unwrap();
+    /// `key: value` and `:key => value` of the options of a gem
+    static ref OPTION: Regex = Regex::new(r"^:?(\w+)(?::|\s*=>)\s*(.+)$").unwrap();
+    static ref SYMBOL: Regex = Regex::new(r#":(\w+)|['"](\w+)['"]"#).unwrap();
+    /// `spec.add_dependency "rails", "~> 6.0"` of a gemspec
+    static ref GEMSPEC_DEPENDENCY: Regex = Regex::new(
+        r#"\.add_(runtime_|development_)?dependency\s*\(?\s*['"]([^'"]+)['"]((?:\s*,\s*['"][^'"]*['"])*)"#
+    )
+    .unwrap();
+    /// `    rails (6.0.3.4)` of the `specs` in a `Gemfile.lock`
+    static ref LOCKED: Regex = Regex::new(r"^    ([^\s(]+) \(([^)]+)\)$").unwrap();
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct RubyFacet {
+    /// of the `ruby` directive
+    pub ruby_version: String,
+    /// dirs of the gems with `path:`, which are the local gems of a monorepo
+    pub modules: Vec<String>,
+    /// the gems in `test` groups are for tests, the ones in `development` are only for
+    /// developing, versions are the locked ones of `Gemfile.lock` if it exists
+    pub libraries: Vec<BaseLibrary>,
+}
+
+impl RubyFacet {
+    /// `dir` is the one of `Gemfile`, `gemspec` reads the gemspecs beside it
+    pub fn from_path(dir: &Path) -> Result<RubyFacet, String> {
+        let path = dir.join("Gemfile");
+        let code =
+            fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let locked = fs::read_to_string(dir.join("Gemfile.lock"))
+            .map(|code| RubyFacet::locked_versions(&code))
+            .unwrap_or_default();
+
+        let mut facet = RubyFacet::default();
+        // groups of the blocks, other blocks such as `platforms` have no groups
+        let mut blocks: Vec<Vec<String>> = vec![];
+        for line in code.lines() {
+            let line = line.split(" #").next().unwrap_or_default().trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line == "end" {
+                blocks.pop();
+                continue;
+            }
+            if line.ends_with(" do") || line.contains(" do |") {
+                let groups = match line.strip_prefix("group") {
+                    Some(groups) => RubyFacet::symbols(groups),
+                    None => vec![],
+                };
+                blocks.push(groups);
+                continue;
+            }
+
+            if let Some(captures) = RUBY.captures(line) {
+                facet.ruby_version = captures[1].to_string();
+            } else if line == "gemspec" || line.starts_with("gemspec ") {
+                facet.libraries.extend(RubyFacet::gemspec_libraries(dir));
+            } else if let Some(captures) = GEM.captures(line) {
+                let mut groups: Vec<String> = blocks.iter().flatten().cloned().collect();
+                let mut versions: Vec<String> = vec![];
+                let mut source = "rubygems";
+                for argument in captures[2].split(',').map(|it| it.trim()) {
+                    if let Some(version) = QUOTED.captures(argument) {
+                        versions.push(version[1].to_string());
+                        continue;
+                    }
+                    let option = match OPTION.captures(argument) {
+                        Some(option) => option,
+                        None => continue,
+                    };
+                    match &option[1] {
+                        "group" | "groups" => groups.extend(RubyFacet::symbols(&option[2])),
+                        "git" | "github" => source = "git",
+                        "path" => {
+                            source = "path";
+                            let path = option[2].trim_matches(|char| char == '"' || char == '\'');
+                            facet.modules.push(path.to_string());
+                        }
+                        _ => {}
+                    }
+                }
+
+                let name = captures[1].to_string();
+                let version = locked
+                    .get(&name)
+                    .cloned()
+                    .unwrap_or_else(|| versions.join(", "));
+                facet.libraries.push(BaseLibrary {
+                    name,
+                    version,
+                    group: "".to_string(),
+                    source: source.to_string(),
+                    scope: RubyFacet::scope(&groups).name().to_string(),
+                });
+            }
+        }
+
+        for library in facet.libraries.iter_mut() {
+            if let Some(version) = locked.get(&library.name) {
+                library.version = version.clone();
+            }
+        }
+        Ok(facet)
+    }
+
+    pub fn scope(groups: &[String]) -> LibraryScope {
+        if groups.iter().any(|group| group == "test") {
+            LibraryScope::Test
+        } else if groups.iter().any(|group| group == "development") {
+            LibraryScope::Dev
+        } else {
+            LibraryScope::Main
+        }
+    }
+
+    /// `:development, :test` or `[:development, :test]`
+    fn symbols(text: &str) -> Vec<String> {
+        SYMBOL
+            .captures_iter(text)
+            .filter_map(|captures| captures.get(1).or_else(|| captures.get(2)))
+            .map(|symbol| symbol.as_str().to_string())
+            .collect()
+    }
+
+    /// `add_dependency` and `add_runtime_dependency` are for running, the
+    /// `add_development_dependency` ones are only for developing
+    fn gemspec_libraries(dir: &Path) -> Vec<BaseLibrary> {
+        let mut gemspecs: Vec<PathBuf> = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "gemspec"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        gemspecs.sort();
+
+        let mut libraries = vec![];
+        for code in gemspecs
+            .iter()
+            .filter_map(|path| fs::read_to_string(path).ok())
+        {
+            for captures in GEMSPEC_DEPENDENCY.captures_iter(&code) {
+                let scope = match captures.get(1).map(|it| it.as_str()) {
+                    Some("development_") => LibraryScope::Dev,
+                    _ => LibraryScope::Main,
+                };
+                let versions: Vec<&str> = captures[3]
+                    .split(',')
+                    .map(|it| it.trim().trim_matches(|char| char == '"' || char == '\''))
+                    .filter(|it| !it.is_empty())
+                    .collect();
+                libraries.push(BaseLibrary {
+                    name: captures[2].to_string(),
+                    version: versions.join(", "),
+                    group: "".to_string(),
+                    source: "rubygems".to_string(),
+                    scope: scope.name().to_string(),
+                });
+            }
+        }
+        libraries
+    }
+
+    /// specs of the `GEM`, `GIT` and `PATH` sections, the deeper lines are their dependencies
+    fn locked_versions(code: &str) -> BTreeMap<String, String> {
+        code.lines()
+            .filter_map(|line| LOCKED.captures(line))
+            .map(|captures| (captures[1].to_string(), captures[2].to_string()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::facet::ruby_facet::RubyFacet;
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_read_gemfile_with_groups_and_lock() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("ruby")
+            .join("shop");
+        let facet = RubyFacet::from_path(&dir).unwrap();
+
+        assert_eq!("2.7.2", facet.ruby_version);
+        assert_eq!(vec!["engines/admin"], facet.modules);
+
+        let libraries: Vec<(&str, &str, &str, &str)> = facet
+            .libraries
+            .iter()
+            .map(|library| {
+                (
+                    library.name.as_str(),
+                    library.version.as_str(),
+                    library.source.as_str(),
+                    library.scope.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("money", "6.13.8", "rubygems", "Main"),
+                ("rake", ">= 12.0", "rubygems", "Dev"),
+                ("rails", "6.0.3.4", "rubygems", "Main"),
+                ("pg", "1.2.3", "rubygems", "Main"),
+                ("admin", "0.1.0", "path", "Main"),
+                ("sidekiq", "6.1.2", "git", "Main"),
+                ("pry", "0.13.1", "rubygems", "Dev"),
+                ("rspec-rails", "4.0.1", "rubygems", "Test"),
+                ("capybara", "3.33.0", "rubygems", "Test"),
+                (
+                    "activerecord-jdbcpostgresql-adapter",
+                    "",
+                    "rubygems",
+                    "Main"
+                ),
+            ],
+            libraries
+        );
+    }
+}
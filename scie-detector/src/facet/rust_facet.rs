@@ -1,2 +1,284 @@
-#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
-pub struct RustFacet {}
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::dependency::base_library::{BaseLibrary, LibraryScope};
+use scie_infra::toml::parse_toml;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct RustFacet {
+    /// of `[package]`, empty for a virtual manifest
+    pub name: String,
+    pub version: String,
+    pub edition: String,
+    pub is_workspace: bool,
+    /// dirs of `[workspace] members`, `*` globs are expanded to the dirs with a `Cargo.toml`
+    pub members: Vec<String>,
+    pub features: BTreeMap<String, Vec<String>>,
+    /// versions are the resolved ones of `Cargo.lock` if it exists
+    pub libraries: Vec<BaseLibrary>,
+}
+
+impl RustFacet {
+    /// `dir` is the one of `Cargo.toml`, `Cargo.lock` is the one of its workspace
+    pub fn from_path(dir: &Path) -> Result<RustFacet, String> {
+        let path = dir.join("Cargo.toml");
+        let code =
+            fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let manifest = parse_toml(&code).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let locked = RustFacet::workspace_root(dir)
+            .and_then(|root| fs::read_to_string(root.join("Cargo.lock")).ok())
+            .and_then(|code| parse_toml(&code).ok())
+            .map(|lock| RustFacet::locked_versions(&lock))
+            .unwrap_or_default();
+
+        let package = &manifest["package"];
+        let text = |value: &Value| value.as_str().unwrap_or_default().to_string();
+        let mut facet = RustFacet {
+            name: text(&package["name"]),
+            version: text(&package["version"]),
+            edition: text(&package["edition"]),
+            is_workspace: manifest["workspace"].is_object(),
+            ..Default::default()
+        };
+
+        if let Some(members) = manifest["workspace"]["members"].as_array() {
+            for member in members.iter().filter_map(|member| member.as_str()) {
+                facet.members.extend(RustFacet::expand_member(dir, member));
+            }
+        }
+
+        if let Some(features) = manifest["features"].as_object() {
+            for (name, enables) in features.iter() {
+                let enables = enables
+                    .as_array()
+                    .map(|enables| {
+                        enables
+                            .iter()
+                            .filter_map(|it| it.as_str().map(|it| it.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                facet.features.insert(name.clone(), enables);
+            }
+        }
+
+        // dependencies of `[target.'cfg(..)']` are the same as the common ones
+        let mut tables = vec![&manifest];
+        if let Some(targets) = manifest["target"].as_object() {
+            tables.extend(targets.values());
+        }
+        for table in tables {
+            for (key, scope) in [
+                ("dependencies", LibraryScope::Main),
+                ("dev-dependencies", LibraryScope::Test),
+                ("build-dependencies", LibraryScope::Dev),
+            ]
+            .iter()
+            {
+                if let Some(dependencies) = table[key].as_object() {
+                    for (name, dependency) in dependencies.iter() {
+                        let library = RustFacet::library(name, dependency, *scope, &locked);
+                        facet.libraries.push(library);
+                    }
+                }
+            }
+        }
+
+        Ok(facet)
+    }
+
+    /// `serde = "1.0"` or `serde = { version = "1.0", package = "..", path = "..", git = ".." }`
+    fn library(
+        key: &str,
+        dependency: &Value,
+        scope: LibraryScope,
+        locked: &BTreeMap<String, Vec<String>>,
+    ) -> BaseLibrary {
+        let name = dependency["package"].as_str().unwrap_or(key).to_string();
+        let requirement = dependency
+            .as_str()
+            .or_else(|| dependency["version"].as_str())
+            .unwrap_or_default();
+        let source = if dependency["path"].is_string() {
+            "path"
+        } else if dependency["git"].is_string() {
+            "git"
+        } else {
+            "crates.io"
+        };
+
+        let version = locked
+            .get(&name)
+            .and_then(|versions| {
+                versions
+                    .iter()
+                    .find(|version| RustFacet::is_match(version, requirement))
+            })
+            .cloned()
+            .unwrap_or_else(|| requirement.to_string());
+
+        BaseLibrary {
+            name,
+            version,
+            group: "".to_string(),
+            source: source.to_string(),
+            scope: scope.name().to_string(),
+        }
+    }
+
+    /// versions of `[[package]]` by names, a package may be locked in several versions
+    fn locked_versions(lock: &Value) -> BTreeMap<String, Vec<String>> {
+        let mut versions: BTreeMap<String, Vec<String>> = Default::default();
+        for package in lock["package"].as_array().into_iter().flatten() {
+            if let (Some(name), Some(version)) =
+                (package["name"].as_str(), package["version"].as_str())
+            {
+                versions
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(version.to_string());
+            }
+        }
+        versions
+    }
+
+    /// compatible by the default caret requirement: the parts up to the first non-zero one
+    /// are the same, an empty requirement such as the one of a `path` matches any version
+    fn is_match(version: &str, requirement: &str) -> bool {
+        let requirement = requirement
+            .split(',')
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches(['^', '~', '=', '>', '<', ' '].as_ref());
+        let required: Vec<&str> = requirement
+            .split('.')
+            .take_while(|part| !part.is_empty() && *part != "*")
+            .collect();
+        let version: Vec<&str> = version.split(['.', '-', '+'].as_ref()).collect();
+
+        let significant = required
+            .iter()
+            .position(|part| *part != "0")
+            .unwrap_or_else(|| required.len().saturating_sub(1));
+        required
+            .iter()
+            .take(significant + 1)
+            .enumerate()
+            .all(|(index, part)| version.get(index) == Some(part))
+    }
+
+    /// `dir` if it is locked, or the first parent whose workspace has `dir` as a member
+    fn workspace_root(dir: &Path) -> Option<&Path> {
+        dir.ancestors().find(|root| {
+            let relative = match dir.strip_prefix(root) {
+                Ok(relative) if relative.as_os_str().is_empty() => {
+                    return root.join("Cargo.lock").is_file()
+                }
+                Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+                Err(_) => return false,
+            };
+
+            fs::read_to_string(root.join("Cargo.toml"))
+                .ok()
+                .and_then(|code| parse_toml(&code).ok())
+                .and_then(|manifest| manifest["workspace"]["members"].as_array().cloned())
+                .is_some_and(|members| {
+                    members
+                        .iter()
+                        .filter_map(|member| member.as_str())
+                        .flat_map(|member| RustFacet::expand_member(root, member))
+                        .any(|member| member == relative)
+                })
+        })
+    }
+
+    fn expand_member(dir: &Path, member: &str) -> Vec<String> {
+        let parent = match member.strip_suffix("/*") {
+            Some(parent) => parent,
+            None => return vec![member.to_string()],
+        };
+
+        let mut members: Vec<String> = fs::read_dir(dir.join(parent))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().join("Cargo.toml").is_file())
+                    .map(|entry| format!("{}/{}", parent, entry.file_name().to_string_lossy()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        members.sort();
+        members
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::facet::rust_facet::RustFacet;
+    use std::path::PathBuf;
+
+    fn fixture() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("rust")
+            .join("workspace")
+    }
+
+    #[test]
+    fn should_read_workspace_members() {
+        let facet = RustFacet::from_path(&fixture()).unwrap();
+
+        assert!(facet.is_workspace);
+        assert_eq!("", facet.name);
+        assert_eq!(vec!["cli", "crates/core", "crates/model"], facet.members);
+    }
+
+    #[test]
+    fn should_resolve_dependencies_by_lock() {
+        let facet = RustFacet::from_path(&fixture().join("crates").join("core")).unwrap();
+
+        assert_eq!("shop-core", facet.name);
+        assert_eq!("2018", facet.edition);
+        assert_eq!(vec!["dep:regex"], facet.features["search"]);
+
+        let libraries: Vec<(&str, &str, &str, &str)> = facet
+            .libraries
+            .iter()
+            .map(|library| {
+                (
+                    library.name.as_str(),
+                    library.version.as_str(),
+                    library.source.as_str(),
+                    library.scope.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("regex", "1.4.2", "crates.io", "Main"),
+                ("serde", "1.0.117", "crates.io", "Main"),
+                ("shop-model", "0.1.0", "path", "Main"),
+                ("tempfile", "3.1.0", "crates.io", "Test"),
+                ("cc", "1.0.61", "crates.io", "Dev"),
+                ("libc", "0.2.80", "crates.io", "Main"),
+            ],
+            libraries
+        );
+    }
+
+    #[test]
+    fn should_match_caret_requirements() {
+        assert!(RustFacet::is_match("1.0.117", "1.0"));
+        assert!(RustFacet::is_match("1.4.2", "^1.2"));
+        assert!(!RustFacet::is_match("2.0.0", "1"));
+        assert!(RustFacet::is_match("0.2.80", "0.2"));
+        assert!(!RustFacet::is_match("0.3.0", "0.2"));
+        assert!(RustFacet::is_match("0.1.0", ""));
+    }
+}
@@ -1,8 +1,92 @@
-use crate::facet::{JavaFacet, JvmFacet};
+use crate::content_root::ContentRoot;
+use crate::facet::dotnet_facet::DotnetFacet;
+use crate::facet::go_facet::GoFacet;
+use crate::facet::javascript::JavaScriptFacet;
+use crate::facet::php_facet::PhpFacet;
+use crate::facet::python_facet::{self, PythonFacet};
+use crate::facet::ruby_facet::RubyFacet;
+use crate::facet::rust_facet::RustFacet;
+use crate::facet::{JavaFacet, JvmFacet, MavenFacet};
+use scie_infra::finder::Finder;
+use scie_infra::paths::relative_path;
+use scie_model::artifact::SourceKind;
 use std::collections::hash_map::RandomState;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// tags and the files which make their dir a build root
+pub const FRAMEWORK_FILES: [(&str, &[&str]); 10] = [
+    (
+        "workspace.java.gradle",
+        &[
+            "build.gradle",
+            "build.gradle.kts",
+            "settings.gradle",
+            "settings.gradle.kts",
+        ],
+    ),
+    ("workspace.java.pom", &["pom.xml"]),
+    ("workspace.bower", &["bower.json"]),
+    ("workspace.npm", &["package.json"]),
+    ("workspace.c", &["CMakeLists.txt"]),
+    ("workspace.go", &["go.mod"]),
+    ("workspace.rust.cargo", &["Cargo.toml"]),
+    (
+        "workspace.python",
+        &[
+            "requirements.txt",
+            "pyproject.toml",
+            "setup.py",
+            "setup.cfg",
+            "Pipfile",
+        ],
+    ),
+    ("workspace.php.composer", &["composer.json"]),
+    ("workspace.ruby.bundler", &["Gemfile"]),
+];
+
+/// tags and the extensions of files which make their dir a build root, the names of them
+/// are the ones of projects
+pub const FRAMEWORK_EXTENSIONS: [(&str, &[&str]); 1] =
+    [("workspace.dotnet", &["sln", "csproj", "fsproj", "vbproj"])];
+
+/// dirs of dependencies and build outputs, even they are not in ignore files
+pub const IGNORED_DIRS: [&str; 7] = [
+    "node_modules",
+    "bower_components",
+    "target",
+    "build",
+    "vendor",
+    ".gradle",
+    "__pycache__",
+];
+
+/// languages by file extensions
+pub const LANGUAGE_EXTENSIONS: [(&str, &str); 20] = [
+    ("java", "Java"),
+    ("groovy", "Groovy"),
+    ("kt", "Kotlin"),
+    ("scala", "Scala"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("go", "Go"),
+    ("rs", "Rust"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cpp", "C++"),
+    ("cc", "C++"),
+    ("py", "Python"),
+    ("cs", "C#"),
+    ("fs", "F#"),
+    ("vb", "Visual Basic"),
+    ("php", "PHP"),
+    ("rb", "Ruby"),
+];
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct Framework {
     pub name: String,
     pub path: String,
@@ -15,12 +99,33 @@ pub struct Framework {
     pub framework_files: Vec<String>,
     // in JVM projects, has different languages, such as Java, Groovy, Kotlin...
     pub language: Vec<String>,
+    /// relative to `path`, by the conventions of the build tool, or by the build files
+    #[serde(default)]
+    pub content_root: ContentRoot,
 }
 
 pub struct FrameworkDetector<'a> {
     pub tags: BTreeMap<&'a str, bool>,
     pub frameworks: Vec<Framework>,
     pub java_facets: Vec<JavaFacet>,
+    /// a reactor for each root `pom.xml`, the poms of their modules are not roots
+    pub maven_facets: Vec<MavenFacet>,
+    /// one for each `package.json`, in the order of `frameworks`
+    pub javascript_facets: Vec<JavaScriptFacet>,
+    /// one for each `Cargo.toml`, in the order of `frameworks`
+    pub rust_facets: Vec<RustFacet>,
+    /// one for each `go.mod`, in the order of `frameworks`
+    pub go_facets: Vec<GoFacet>,
+    /// one for each Python project, in the order of `frameworks`
+    pub python_facets: Vec<PythonFacet>,
+    /// one for each solution, or each project file out of solutions
+    pub dotnet_facets: Vec<DotnetFacet>,
+    /// one for each `composer.json`, in the order of `frameworks`
+    pub php_facets: Vec<PhpFacet>,
+    /// one for each `Gemfile`, in the order of `frameworks`
+    pub ruby_facets: Vec<RubyFacet>,
+    /// whether the test files of all languages are analysed, `JavaFacet.include_test` follows it
+    pub include_test: bool,
 }
 
 impl<'a> FrameworkDetector<'a> {
@@ -29,33 +134,296 @@ impl<'a> FrameworkDetector<'a> {
             tags: Default::default(),
             frameworks: vec![],
             java_facets: vec![],
+            maven_facets: vec![],
+            javascript_facets: vec![],
+            rust_facets: vec![],
+            go_facets: vec![],
+            python_facets: vec![],
+            dotnet_facets: vec![],
+            php_facets: vec![],
+            ruby_facets: vec![],
+            include_test: false,
         }
     }
 
     pub fn run(&mut self, path: String) {
-        self.light_detector(path)
+        self.light_detector(path.clone());
+        self.deep_detector(path);
+        self.build_frameworks_info();
     }
 
-    fn deep_detector(&mut self, _path: String) {}
+    /// every build root below `path`, the files in ignore files and `IGNORED_DIRS` are skipped
+    fn deep_detector(&mut self, path: String) {
+        let root = PathBuf::from(&path);
+        let mut files: Vec<PathBuf> = Finder::walk_filter_files(&root)
+            .into_iter()
+            .filter(|file| file.is_file())
+            .collect();
+
+        let mut roots: BTreeMap<(PathBuf, &'a str), Vec<String>> = Default::default();
+        for file in files.iter() {
+            let name = match file.file_name().and_then(|name| name.to_str()) {
+                Some(name) => name,
+                None => continue,
+            };
+
+            for (tag, framework_files) in FRAMEWORK_FILES.iter() {
+                if !framework_files.contains(&name) {
+                    continue;
+                }
+                if let Some(dir) = file.parent() {
+                    roots
+                        .entry((dir.to_path_buf(), *tag))
+                        .or_default()
+                        .push(name.to_string());
+                }
+            }
+
+            let extension = file.extension().and_then(|ext| ext.to_str());
+            for (tag, extensions) in FRAMEWORK_EXTENSIONS.iter() {
+                if !extension.is_some_and(|ext| extensions.contains(&ext)) {
+                    continue;
+                }
+                if let Some(dir) = file.parent() {
+                    roots
+                        .entry((dir.to_path_buf(), *tag))
+                        .or_default()
+                        .push(name.to_string());
+                }
+            }
+        }
+
+        let mut build_roots: BTreeSet<PathBuf> = roots.keys().map(|(dir, _)| dir.clone()).collect();
+        build_roots.insert(root.clone());
+        files.retain(|file| !FrameworkDetector::is_ignored(file, &build_roots));
+        roots.retain(|(dir, _), _| !FrameworkDetector::is_ignored(dir, &build_roots));
+
+        for ((dir, tag), mut framework_files) in roots.into_iter() {
+            framework_files.sort();
+            let language: BTreeSet<&str> = files
+                .iter()
+                .filter(|file| file.starts_with(&dir))
+                .filter_map(|file| file.extension().and_then(|ext| ext.to_str()))
+                .filter_map(|ext| {
+                    LANGUAGE_EXTENSIONS
+                        .iter()
+                        .find(|(extension, _)| *extension == ext)
+                        .map(|(_, language)| *language)
+                })
+                .collect();
+
+            self.tags.insert(tag, true);
+            self.frameworks.push(Framework {
+                name: tag.to_string(),
+                path: dir.display().to_string(),
+                relative_path: relative_path(&dir, &root),
+                framework_files,
+                language: language.into_iter().map(|l| l.to_string()).collect(),
+                content_root: ContentRoot::infer(tag, &dir),
+            });
+        }
+    }
+
+    /// under one of `IGNORED_DIRS` right in a build root, so that `src/build` of sources is kept
+    pub fn is_ignored(path: &Path, build_roots: &BTreeSet<PathBuf>) -> bool {
+        path.ancestors().skip(1).any(|dir| {
+            let ignored = dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| IGNORED_DIRS.contains(&name));
+            ignored
+                && dir
+                    .parent()
+                    .is_some_and(|parent| build_roots.contains(parent))
+        })
+    }
 
     fn build_frameworks_info(&mut self) {
-        if self.tags.contains_key("workspace.java.gradle")
-            || self.tags.contains_key("workspace.java.pom")
-        {
+        let is_gradle = self.tags.get("workspace.java.gradle") == Some(&true);
+        let is_maven = self.tags.get("workspace.java.pom") == Some(&true);
+        if is_gradle || is_maven {
             let facet = JavaFacet {
                 jvm: JvmFacet {
-                    is_gradle: self.tags.contains_key("workspace.java.gradle"),
-                    is_maven: self.tags.contains_key("workspace.java.pom"),
+                    is_gradle,
+                    is_maven,
                     has_java: false,
                     has_groovy: false,
                     has_kotlin: false,
                     has_scala: false,
                 },
-                include_test: false,
+                include_test: self.include_test,
             };
 
             self.java_facets.push(facet)
         }
+
+        if is_maven {
+            self.build_maven_facets();
+        }
+        self.build_dotnet_facets();
+
+        for framework in self.frameworks.iter() {
+            let dir = Path::new(&framework.path);
+            match framework.name.as_str() {
+                "workspace.npm" => {
+                    if let Ok(facet) = JavaScriptFacet::from_path(dir) {
+                        self.javascript_facets.push(facet);
+                    }
+                }
+                "workspace.rust.cargo" => {
+                    if let Ok(facet) = RustFacet::from_path(dir) {
+                        self.rust_facets.push(facet);
+                    }
+                }
+                "workspace.go" => {
+                    if let Ok(facet) = GoFacet::from_path(dir) {
+                        self.go_facets.push(facet);
+                    }
+                }
+                "workspace.python" => {
+                    if let Ok(facet) = PythonFacet::from_path(dir) {
+                        self.python_facets.push(facet);
+                    }
+                }
+                "workspace.php.composer" => {
+                    if let Ok(facet) = PhpFacet::from_path(dir) {
+                        self.php_facets.push(facet);
+                    }
+                }
+                "workspace.ruby.bundler" => {
+                    if let Ok(facet) = RubyFacet::from_path(dir) {
+                        self.ruby_facets.push(facet);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (tag, _, _) in python_facet::FRAMEWORKS.iter() {
+            let has_framework = self
+                .python_facets
+                .iter()
+                .any(|facet| facet.frameworks.iter().any(|framework| framework == tag));
+            self.tags.insert(tag, has_framework);
+        }
+    }
+
+    fn build_maven_facets(&mut self) {
+        for framework in self.frameworks.iter() {
+            if framework.name != "workspace.java.pom" {
+                continue;
+            }
+
+            let dir = Path::new(&framework.path);
+            let is_module = self.maven_facets.iter().any(|facet| {
+                facet
+                    .modules
+                    .iter()
+                    .any(|module| Path::new(&facet.root).join(&module.path) == dir)
+            });
+            if is_module {
+                continue;
+            }
+
+            if let Ok(facet) = MavenFacet::from_path(dir) {
+                self.maven_facets.push(facet);
+            }
+        }
+
+        // the dirs in poms win over the conventions
+        for framework in self.frameworks.iter_mut() {
+            let dir = Path::new(&framework.path);
+            let module = self.maven_facets.iter().find_map(|facet| {
+                facet
+                    .modules
+                    .iter()
+                    .find(|module| Path::new(&facet.root).join(&module.path) == dir)
+            });
+            let content_root = module.and_then(|module| module.module.content_roots.first());
+            if let (Some(module), Some(content_root)) = (module, content_root) {
+                framework.content_root = content_root.relative_to(&module.path);
+            }
+        }
+    }
+
+    /// by the content root of the deepest build root which has the file
+    pub fn source_kind(&self, path: &Path) -> SourceKind {
+        self.framework_of(path)
+            .map(|(framework, relative)| framework.content_root.kind_of(&relative))
+            .unwrap_or(SourceKind::Main)
+    }
+
+    /// in a named source dir of the content root of the deepest build root which has the file
+    pub fn is_in_source_dirs(&self, path: &Path) -> bool {
+        self.framework_of(path)
+            .map(|(framework, relative)| framework.content_root.is_in_source_dirs(&relative))
+            .unwrap_or(false)
+    }
+
+    /// the files of `root` to analyse, the ones in `exclude_dirs` of their content root, or in
+    /// `IGNORED_DIRS` of a build root are left out as `deep_detector` does, but generated
+    /// sources are kept
+    pub fn analysed_files(&self, root: &Path, files: Vec<PathBuf>) -> Vec<PathBuf> {
+        let mut build_roots: BTreeSet<PathBuf> = self
+            .frameworks
+            .iter()
+            .map(|framework| PathBuf::from(&framework.path))
+            .collect();
+        build_roots.insert(root.to_path_buf());
+
+        files
+            .into_iter()
+            .filter(|file| match self.framework_of(file) {
+                Some((framework, relative)) if framework.content_root.is_generated(&relative) => {
+                    true
+                }
+                Some((framework, relative)) if framework.content_root.is_excluded(&relative) => {
+                    false
+                }
+                _ => !FrameworkDetector::is_ignored(file, &build_roots),
+            })
+            .collect()
+    }
+
+    /// the deepest build root which has the file, with the `/` separated path relative to it
+    fn framework_of(&self, path: &Path) -> Option<(&Framework, String)> {
+        let framework = self
+            .frameworks
+            .iter()
+            .filter(|framework| path.starts_with(&framework.path))
+            .max_by_key(|framework| Path::new(&framework.path).components().count())?;
+        let relative = path.strip_prefix(&framework.path).unwrap_or(path);
+        Some((framework, relative.to_string_lossy().replace('\\', "/")))
+    }
+
+    /// the dirs of projects which are in a solution of a parent dir have no facets of their own
+    fn build_dotnet_facets(&mut self) {
+        let mut roots: Vec<PathBuf> = vec![];
+        for framework in self.frameworks.iter() {
+            if framework.name != "workspace.dotnet" {
+                continue;
+            }
+
+            let dir = Path::new(&framework.path);
+            let is_project = self
+                .dotnet_facets
+                .iter()
+                .zip(roots.iter())
+                .any(|(facet, root)| {
+                    facet
+                        .projects
+                        .iter()
+                        .any(|project| root.join(&project.path).parent() == Some(dir))
+                });
+            if is_project {
+                continue;
+            }
+
+            if let Ok(facet) = DotnetFacet::from_path(dir) {
+                self.dotnet_facets.push(facet);
+                roots.push(dir.to_path_buf());
+            }
+        }
     }
 
     fn light_detector(&mut self, path: String) {
@@ -85,13 +453,36 @@ impl<'a> FrameworkDetector<'a> {
 
         self.tags.insert(
             "workspace.go",
-            name_set.contains("go.mod") || name_set.contains("main.got"),
+            name_set.contains("go.mod") || name_set.contains("main.go"),
         );
 
         self.tags
             .insert("workspace.rust.cargo", name_set.contains("Cargo.toml"));
 
-        self.build_frameworks_info();
+        self.tags.insert(
+            "workspace.python",
+            name_set.contains("requirements.txt")
+                || name_set.contains("pyproject.toml")
+                || name_set.contains("setup.py")
+                || name_set.contains("setup.cfg")
+                || name_set.contains("Pipfile"),
+        );
+
+        self.tags
+            .insert("workspace.php.composer", name_set.contains("composer.json"));
+
+        self.tags
+            .insert("workspace.ruby.bundler", name_set.contains("Gemfile"));
+
+        for (tag, extensions) in FRAMEWORK_EXTENSIONS.iter() {
+            let has_file = name_set.iter().any(|name| {
+                Path::new(name)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| extensions.contains(&ext))
+            });
+            self.tags.insert(tag, has_file);
+        }
     }
 
     pub fn build_level_one_name_set(path: String) -> HashSet<String, RandomState> {
@@ -119,7 +510,11 @@ impl<'a> FrameworkDetector<'a> {
 #[cfg(test)]
 mod tests {
     use crate::framework_detector::FrameworkDetector;
-    use std::path::PathBuf;
+    use scie_infra::paths::relative_path;
+    use scie_model::artifact::SourceKind;
+    use std::collections::BTreeSet;
+    use std::fs;
+    use std::path::{Path, PathBuf};
 
     fn build_test_detector<'a>() -> FrameworkDetector<'a> {
         let root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -157,4 +552,238 @@ mod tests {
 
         assert_eq!(1, detector.java_facets.len());
     }
+
+    #[test]
+    fn should_build_maven_facets_of_root_poms() {
+        let root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("java")
+            .join("maven");
+
+        let mut detector = FrameworkDetector::new();
+        detector.run(root_dir.display().to_string());
+
+        assert!(detector.tags.get("workspace.java.pom").unwrap());
+        assert_eq!(1, detector.maven_facets.len());
+        assert_eq!(3, detector.maven_facets[0].modules.len());
+    }
+
+    #[test]
+    fn should_build_go_facets_of_modules() {
+        let root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("go");
+
+        let mut detector = FrameworkDetector::new();
+        detector.run(root_dir.display().to_string());
+
+        assert!(detector.tags.get("workspace.go").unwrap());
+        let modules: Vec<&str> = detector
+            .go_facets
+            .iter()
+            .map(|facet| facet.module.as_str())
+            .collect();
+        assert_eq!(
+            vec!["github.com/example/model", "github.com/example/shop"],
+            modules
+        );
+    }
+
+    #[test]
+    fn should_tag_python_frameworks() {
+        let root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("python");
+
+        let mut detector = FrameworkDetector::new();
+        detector.run(root_dir.display().to_string());
+
+        assert!(detector.tags.get("workspace.python").unwrap());
+        assert!(detector.tags.get("workspace.python.django").unwrap());
+        assert!(detector.tags.get("workspace.python.fastapi").unwrap());
+        assert!(detector.tags.get("workspace.python.flask").unwrap());
+        assert_eq!(3, detector.python_facets.len());
+        assert_eq!(vec!["Python"], detector.frameworks[0].language);
+    }
+
+    #[test]
+    fn should_build_dotnet_facets_of_solutions() {
+        let root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("dotnet");
+
+        let mut detector = FrameworkDetector::new();
+        detector.run(root_dir.display().to_string());
+
+        assert!(detector.tags.get("workspace.dotnet").unwrap());
+        assert_eq!(1, detector.dotnet_facets.len());
+        assert_eq!(3, detector.dotnet_facets[0].projects.len());
+        assert_eq!(vec!["C#", "F#"], detector.frameworks[0].language);
+    }
+
+    #[test]
+    fn should_build_composer_and_bundler_facets() {
+        let root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects");
+
+        let mut detector = FrameworkDetector::new();
+        detector.run(root_dir.join("php").display().to_string());
+        assert!(detector.tags.get("workspace.php.composer").unwrap());
+        assert_eq!(3, detector.php_facets.len());
+        assert_eq!("example/shop", detector.php_facets[0].name);
+
+        let mut detector = FrameworkDetector::new();
+        detector.run(root_dir.join("ruby").display().to_string());
+        assert!(detector.tags.get("workspace.ruby.bundler").unwrap());
+        assert_eq!(1, detector.ruby_facets.len());
+        assert_eq!(vec!["Ruby"], detector.frameworks[0].language);
+    }
+
+    #[test]
+    fn should_detect_build_roots_in_sub_dirs() {
+        let root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects")
+            .join("polyglot");
+
+        let mut detector = FrameworkDetector::new();
+        detector.run(root_dir.display().to_string());
+
+        let frameworks: Vec<(&str, &str)> = detector
+            .frameworks
+            .iter()
+            .map(|framework| (framework.name.as_str(), framework.relative_path.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("workspace.java.gradle", "backend"),
+                ("workspace.npm", "frontend"),
+                ("workspace.rust.cargo", "tools"),
+            ],
+            frameworks
+        );
+
+        let backend = &detector.frameworks[0];
+        assert_eq!(
+            vec!["build.gradle", "settings.gradle"],
+            backend.framework_files
+        );
+        assert_eq!(vec!["Java", "Kotlin"], backend.language);
+        assert_eq!(vec!["TypeScript"], detector.frameworks[1].language);
+
+        assert!(detector.tags.get("workspace.npm").unwrap());
+        assert_eq!(&false, detector.tags.get("workspace.java.pom").unwrap());
+        assert_eq!(1, detector.java_facets.len());
+
+        let frontend = &detector.javascript_facets[0];
+        assert_eq!("frontend", frontend.name);
+        assert_eq!("left-pad", frontend.libraries[0].name);
+        assert_eq!("tools", detector.rust_facets[0].name);
+    }
+
+    #[test]
+    fn should_ignore_dirs_only_in_build_roots() {
+        let build_roots: BTreeSet<PathBuf> = vec![PathBuf::from("shop"), PathBuf::from("shop/web")]
+            .into_iter()
+            .collect();
+        let ignored = |path: &str| FrameworkDetector::is_ignored(Path::new(path), &build_roots);
+
+        assert!(ignored("shop/target/classes/App.class"));
+        assert!(ignored("shop/web/node_modules/left-pad/index.js"));
+        assert!(!ignored("shop/src/build/gradle_parser.rs"));
+        assert!(!ignored("shop/web/src/vendor/jquery.js"));
+        assert!(!ignored("shop/web/index.js"));
+    }
+
+    #[test]
+    fn should_leave_out_excluded_files_but_generated_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let files = [
+            "build.gradle",
+            "src/main/java/App.java",
+            "build/generated/sources/Api.java",
+            "build/tmp/Stub.java",
+            "web/package.json",
+            "web/node_modules/left-pad/index.js",
+            "web/src/vendor/jquery.js",
+        ];
+        for file in files.iter() {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let mut detector = FrameworkDetector::new();
+        detector.run(root.display().to_string());
+        let mut analysed: Vec<String> = detector
+            .analysed_files(root, files.iter().map(|file| root.join(file)).collect())
+            .iter()
+            .map(|file| relative_path(file, root))
+            .collect();
+        analysed.sort();
+        assert_eq!(
+            vec![
+                "build.gradle",
+                "build/generated/sources/Api.java",
+                "src/main/java/App.java",
+                "web/package.json",
+                "web/src/vendor/jquery.js",
+            ],
+            analysed
+        );
+    }
+
+    #[test]
+    fn should_tag_files_by_content_roots_of_modules() {
+        let root_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("fixtures")
+            .join("projects");
+
+        let mut detector = FrameworkDetector::new();
+        detector.run(root_dir.join("polyglot").display().to_string());
+        let kind = |path: &str| detector.source_kind(&root_dir.join("polyglot").join(path));
+        assert_eq!(
+            SourceKind::Main,
+            kind("backend/src/main/java/com/shop/App.java")
+        );
+        assert_eq!(
+            SourceKind::Test,
+            kind("backend/src/test/java/com/shop/AppTest.java")
+        );
+        assert_eq!(
+            SourceKind::Resource,
+            kind("backend/src/main/resources/application.properties")
+        );
+        assert_eq!(
+            SourceKind::Test,
+            kind("frontend/src/__tests__/index.test.ts")
+        );
+        assert_eq!(SourceKind::Test, kind("tools/tests/cli.rs"));
+
+        let mut detector = FrameworkDetector::new();
+        detector.run(root_dir.join("java").join("maven").display().to_string());
+        let web = &detector.frameworks[2].content_root;
+        assert_eq!(vec!["src/java"], web.source_dirs);
+        assert_eq!(vec!["test"], web.test_source_dirs);
+    }
 }
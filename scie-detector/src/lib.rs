@@ -13,3 +13,4 @@ pub mod framework_detector;
 pub mod content_root;
 pub mod dependency;
 pub mod facet;
+pub mod test_classifier;
@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use regex::Regex;
+use scie_model::artifact::{SourceKind, TokenCategory, TokenElement};
+
+lazy_static! {
+    /// names of test files, such as `CartTest.java`, `cart_test.go` and `cart.spec.ts`
+    static ref TEST_NAMES: Vec<Regex> = vec![
+        Regex::new(r"^(Test\w+|\w+(Test|Tests|IT|Spec))\.(java|kt|groovy|scala)$").unwrap(),
+        Regex::new(r"_test\.go$").unwrap(),
+        Regex::new(r"^(test_\w+|\w+_test|conftest)\.py$").unwrap(),
+        Regex::new(r"\.(spec|test)\.(js|jsx|ts|tsx|mjs)$").unwrap(),
+        Regex::new(r"_(spec|test)\.rb$").unwrap(),
+        Regex::new(r"Test\.php$").unwrap(),
+        Regex::new(r"Tests?\.(cs|fs|vb)$").unwrap(),
+        Regex::new(r"^test_\w+\.(c|cc|cpp)$|_test\.(c|cc|cpp)$").unwrap(),
+    ];
+
+    /// code of tests by extensions, such as `@Test` and `describe(`
+    static ref TEST_CODE: Vec<(&'static [&'static str], Regex)> = vec![
+        (
+            &["java", "kt", "groovy", "scala"][..],
+            Regex::new(r"@(Test|ParameterizedTest|RepeatedTest)\b").unwrap(),
+        ),
+        (&["rs"][..], Regex::new(r"#\[(\w+::)?test\]").unwrap()),
+        (
+            &["js", "jsx", "ts", "tsx", "mjs"][..],
+            Regex::new(r"^\s*(describe|it|test)(\.\w+)?\s*\(").unwrap(),
+        ),
+        (
+            &["py"][..],
+            Regex::new(r"^\s*(import|from)\s+(pytest|unittest)\b").unwrap(),
+        ),
+        (
+            &["go"][..],
+            Regex::new(r"func\s+Test\w*\s*\(\s*\w+\s+\*testing\.T\s*\)").unwrap(),
+        ),
+        (
+            &["cs", "fs", "vb"][..],
+            Regex::new(r"[\[<](Fact|Theory|Test|TestMethod|TestCase)\b").unwrap(),
+        ),
+        (
+            &["php"][..],
+            Regex::new(r"extends\s+\\?(PHPUnit\\Framework\\)?TestCase\b").unwrap(),
+        ),
+        (
+            &["rb"][..],
+            Regex::new(r"^\s*(RSpec\.)?describe\b|<\s*(Minitest::Test|ActiveSupport::TestCase)\b")
+                .unwrap(),
+        ),
+    ];
+
+    /// Rust keeps unit tests in the files of their code
+    static ref RUST_TEST_MODULE: Regex = Regex::new(r"#\[cfg\(test\)\]").unwrap();
+}
+
+/// whether a file is a test, for every language which the detector knows
+pub struct TestClassifier {}
+
+impl TestClassifier {
+    /// in a test dir of its content root, or named as a test or with the code of tests when
+    /// it is not in a named source dir, where the location wins
+    pub fn is_test(
+        path: &Path,
+        kind: SourceKind,
+        in_source_dirs: bool,
+        elements: &[TokenElement],
+    ) -> bool {
+        if kind == SourceKind::Test {
+            return true;
+        }
+        if kind != SourceKind::Main || in_source_dirs {
+            return false;
+        }
+
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_string())
+            .unwrap_or_default();
+        TestClassifier::is_test_name(&name) || TestClassifier::has_test_code(&extension, elements)
+    }
+
+    pub fn is_test_name(name: &str) -> bool {
+        TEST_NAMES.iter().any(|regex| regex.is_match(name))
+    }
+
+    /// by the lines of tokens out of comments and strings, a Rust file with a
+    /// `#[cfg(test)]` module is still the code which it tests
+    pub fn has_test_code(extension: &str, elements: &[TokenElement]) -> bool {
+        let regex = match TEST_CODE
+            .iter()
+            .find(|(extensions, _)| extensions.contains(&extension))
+        {
+            Some((_, regex)) => regex,
+            None => return false,
+        };
+
+        let mut lines: BTreeMap<i32, String> = Default::default();
+        for element in elements.iter() {
+            let line = lines.entry(element.line_num).or_default();
+            match element.category {
+                TokenCategory::Comment | TokenCategory::String => line.push(' '),
+                _ => line.push_str(&element.value),
+            }
+        }
+
+        if extension == "rs" && lines.values().any(|line| RUST_TEST_MODULE.is_match(line)) {
+            return false;
+        }
+        lines.values().any(|line| regex.is_match(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_classifier::TestClassifier;
+    use scie_model::artifact::{SourceKind, TokenCategory, TokenElement};
+    use std::path::Path;
+
+    fn elements(code: &str) -> Vec<TokenElement> {
+        code.lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let mut element = TokenElement::new(
+                    index as i32 + 1,
+                    0,
+                    line.len() as i32,
+                    line.to_string(),
+                    vec![],
+                );
+                if line.trim_start().starts_with("//") {
+                    element.category = TokenCategory::Comment;
+                }
+                element
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_classify_test_names_of_languages() {
+        assert!(TestClassifier::is_test_name("CartTest.java"));
+        assert!(TestClassifier::is_test_name("CartSpec.groovy"));
+        assert!(TestClassifier::is_test_name("cart_test.go"));
+        assert!(TestClassifier::is_test_name("test_cart.py"));
+        assert!(TestClassifier::is_test_name("cart.spec.ts"));
+        assert!(TestClassifier::is_test_name("cart.test.jsx"));
+        assert!(TestClassifier::is_test_name("cart_spec.rb"));
+        assert!(TestClassifier::is_test_name("CartTest.php"));
+        assert!(TestClassifier::is_test_name("CartTests.cs"));
+
+        assert!(!TestClassifier::is_test_name("Cart.java"));
+        assert!(!TestClassifier::is_test_name("testing.go"));
+        assert!(!TestClassifier::is_test_name("contest.py"));
+        assert!(!TestClassifier::is_test_name("cart.ts"));
+    }
+
+    #[test]
+    fn should_classify_by_code_of_tests() {
+        let java = elements("class Cart {\n    @Test\n    void add() {}\n}");
+        assert!(TestClassifier::has_test_code("java", &java));
+
+        let js = elements("describe('cart', () => {\n  it('adds', () => {});\n});");
+        assert!(TestClassifier::has_test_code("js", &js));
+
+        let commented = elements("// describe('cart')\nexport const cart = {};");
+        assert!(!TestClassifier::has_test_code("js", &commented));
+
+        let unit = elements(
+            "fn add() {}\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn should_add() {}\n}",
+        );
+        assert!(!TestClassifier::has_test_code("rs", &unit));
+        let integration = elements("#[test]\nfn should_run() {}");
+        assert!(TestClassifier::has_test_code("rs", &integration));
+    }
+
+    #[test]
+    fn should_follow_kinds_of_content_roots() {
+        let path = Path::new("src/cart.rs");
+        assert!(TestClassifier::is_test(path, SourceKind::Test, false, &[]));
+        assert!(!TestClassifier::is_test(path, SourceKind::Main, false, &[]));
+        assert!(!TestClassifier::is_test(
+            Path::new("build/CartTest.java"),
+            SourceKind::Generated,
+            false,
+            &[]
+        ));
+    }
+
+    #[test]
+    fn should_follow_named_source_dirs_over_names_and_code() {
+        let factory = Path::new("src/main/java/TestDataFactory.java");
+        assert!(!TestClassifier::is_test(
+            factory,
+            SourceKind::Main,
+            true,
+            &[]
+        ));
+        assert!(TestClassifier::is_test(
+            factory,
+            SourceKind::Main,
+            false,
+            &[]
+        ));
+
+        let js = elements("test('adds', () => {});");
+        let module = Path::new("src/cart.js");
+        assert!(!TestClassifier::is_test(
+            module,
+            SourceKind::Main,
+            true,
+            &js
+        ));
+        assert!(TestClassifier::is_test(
+            module,
+            SourceKind::Main,
+            false,
+            &js
+        ));
+
+        let python = Path::new("test_cart.py");
+        assert!(TestClassifier::is_test(
+            python,
+            SourceKind::Main,
+            false,
+            &[]
+        ));
+    }
+}
@@ -1,5 +1,5 @@
 use core::cmp;
-use scie_scanner::scanner::scie_scanner::IOnigCaptureIndex;
+use scie_scanner::scanner::scie_scanner::{IOnigCaptureIndex, ScanResult};
 use std::collections::HashMap as Map;
 use std::fs::File;
 use std::io::Read;
@@ -9,6 +9,7 @@ use std::path::PathBuf;
 use crate::grammar::line_tokens::{IToken, LineTokens, TokenTypeMatcher};
 use crate::grammar::local_stack_element::LocalStackElement;
 use crate::grammar::rule_container::RuleContainer;
+use crate::grammar::tokenize_state::fnv1a_64;
 use crate::grammar::{MatchRuleResult, ScopeListElement, StackElement};
 use crate::inter::{IRawGrammar, IRawRepository, IRawRepositoryMap, IRawRule};
 use crate::rule::abstract_rule::RuleEnum;
@@ -37,6 +38,28 @@ pub struct CheckWhileConditionResult {
 pub struct TokenizeResult {
     pub tokens: Vec<IToken>,
     pub rule_stack: Option<StackElement>,
+    pub diagnostics: Vec<TokenizeDiagnostic>,
+}
+
+/// the ways a grammar can loop forever at the same position, same as vscode-textmate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum EndlessLoopKind {
+    /// a rule is pushed & popped without advancing
+    PushPopWithoutAdvance,
+    /// a begin/end rule is pushed again at the same position
+    PushSameBeginEndRule,
+    /// a begin/while rule is pushed again at the same position
+    PushSameBeginWhileRule,
+    /// a match rule matches empty, without pushing/popping
+    MatchWithoutAdvance,
+}
+
+/// the tokenizer breaks an endless loop of `rule_id` at `line_pos`, and skips to the end of line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TokenizeDiagnostic {
+    pub kind: EndlessLoopKind,
+    pub rule_id: i32,
+    pub line_pos: i32,
 }
 
 #[derive(Debug, Clone)]
@@ -49,6 +72,9 @@ pub struct Grammar {
     pub rule_container: Box<RuleContainer>,
     pub scope_name_map: Map<String, i32>,
     pub _token_type_matchers: Vec<TokenTypeMatcher>,
+    pub diagnostics: Vec<TokenizeDiagnostic>,
+    /// of the raw grammar, see `fingerprint`
+    fingerprint: String,
 }
 
 pub fn init_grammar(raw_grammar: IRawGrammar, _base: Option<IRawRule>) -> IRawGrammar {
@@ -78,6 +104,8 @@ pub fn init_grammar(raw_grammar: IRawGrammar, _base: Option<IRawRule>) -> IRawGr
 
 impl Grammar {
     pub fn new(raw_grammar: IRawGrammar) -> Self {
+        let raw_json = serde_json::to_string(&raw_grammar).unwrap();
+        let fingerprint = format!("{:016x}", fnv1a_64(raw_json.as_bytes()));
         let inited_grammar = init_grammar(raw_grammar, None);
 
         let mut _empty_rule = Map::new();
@@ -89,6 +117,8 @@ impl Grammar {
             rule_container: Box::new(Default::default()),
             scope_name_map: Map::new(),
             _token_type_matchers: vec![],
+            diagnostics: vec![],
+            fingerprint,
             _empty_rule,
             rules: vec![],
         };
@@ -97,29 +127,19 @@ impl Grammar {
         grammar
     }
 
+    /// identity of the raw grammar, which is computed once, a tokenize state can only be
+    /// restored by a grammar with the same one
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
     fn tokenize(
         &mut self,
         line_text: &str,
         prev_state: &mut Option<StackElement>,
         emit_binary_tokens: bool,
     ) -> TokenizeResult {
-        if self.root_id == -1 {
-            let repository = self.grammar.repository.clone().unwrap();
-            let based = repository.clone().map.self_s.unwrap();
-            self.root_id = RuleFactory::get_compiled_rule_id(
-                *based.clone(),
-                self,
-                &mut repository.clone(),
-                "",
-            );
-
-            for (id, rule) in self.rule_container.rule_id2desc.iter() {
-                if rule.get_rule()._name.is_some() {
-                    self.scope_name_map
-                        .insert(rule.get_rule()._name.as_ref().unwrap().clone(), *id);
-                }
-            }
-        }
+        self.compile_root_rule();
 
         let mut is_first_line: bool = false;
 
@@ -182,6 +202,26 @@ impl Grammar {
         TokenizeResult {
             tokens: vec.clone(),
             rule_stack: next_state,
+            diagnostics: self.diagnostics.drain(..).collect(),
+        }
+    }
+
+    /// rule ids are assigned when the root rule is compiled, all in one go
+    pub fn compile_root_rule(&mut self) {
+        if self.root_id != -1 {
+            return;
+        }
+
+        let repository = self.grammar.repository.clone().unwrap();
+        let based = repository.clone().map.self_s.unwrap();
+        self.root_id =
+            RuleFactory::get_compiled_rule_id(*based.clone(), self, &mut repository.clone(), "");
+
+        for (id, rule) in self.rule_container.rule_id2desc.iter() {
+            if rule.get_rule()._name.is_some() {
+                self.scope_name_map
+                    .insert(rule.get_rule()._name.as_ref().unwrap().clone(), *id);
+            }
         }
     }
 
@@ -224,6 +264,7 @@ impl Grammar {
             let capture_result = r.unwrap();
             let capture_indices = capture_result.capture_indices;
             let matched_rule_id = capture_result.matched_rule_id;
+            let has_advanced = capture_indices[0].end > line_pos as usize;
             if matched_rule_id == -1 {
                 let _popped_rule = self.get_rule(stack.rule_id);
                 if _popped_rule.get_rule()._type == "BeginEndRule" {
@@ -247,19 +288,32 @@ impl Grammar {
                     );
 
                     line_tokens.produce(&mut stack, capture_indices[0].end as i32);
-                    let popped_anchor_pos = stack.anchor_pos.clone();
+                    let mut popped = stack.clone();
                     if let Some(_stack) = stack.pop() {
                         stack = _stack;
                     }
-                    anchor_position = popped_anchor_pos;
+                    anchor_position = popped.anchor_pos;
+
+                    if !has_advanced && popped.enter_pos == line_pos {
+                        // the grammar pushed & popped a rule without advancing, assume the
+                        // intent was to continue in this state
+                        self.record_endless_loop(
+                            EndlessLoopKind::PushPopWithoutAdvance,
+                            popped.rule_id,
+                            line_pos,
+                        );
+                        line_tokens.produce(&mut popped, line_length as i32);
+                        return Some(popped);
+                    }
                 } else {
-                    println!("_popped_rule {:?}", _popped_rule.clone());
+                    eprintln!("_popped_rule {:?}", _popped_rule.clone());
                     _stop = true;
                     return Some(stack);
                 }
             } else {
                 let rule = self.get_rule(matched_rule_id);
                 line_tokens.produce(&mut stack, capture_indices[0].start as i32);
+                let before_push = stack.clone();
                 let scope_name =
                     rule.get_name(Some(String::from(line_text)), Some(&capture_indices));
                 let name_scopes_list = stack.content_name_scopes_list.push(scope_name);
@@ -307,6 +361,19 @@ impl Grammar {
                                 ),
                             );
                         }
+
+                        if !has_advanced && before_push.has_same_rule_as(&stack) {
+                            self.record_endless_loop(
+                                EndlessLoopKind::PushSameBeginEndRule,
+                                matched_rule_id,
+                                line_pos,
+                            );
+                            return Some(Grammar::stop_at_line_end(
+                                stack.safe_pop(),
+                                line_tokens,
+                                line_length,
+                            ));
+                        }
                     }
                     RuleEnum::BeginWhileRule(rule) => {
                         let push_rule = rule.clone();
@@ -329,6 +396,19 @@ impl Grammar {
 
                         let content_name_scopes_list = name_scopes_list.push(content_name);
                         stack = stack.set_content_name_scopes_list(content_name_scopes_list);
+
+                        if !has_advanced && before_push.has_same_rule_as(&stack) {
+                            self.record_endless_loop(
+                                EndlessLoopKind::PushSameBeginWhileRule,
+                                matched_rule_id,
+                                line_pos,
+                            );
+                            return Some(Grammar::stop_at_line_end(
+                                stack.safe_pop(),
+                                line_tokens,
+                                line_length,
+                            ));
+                        }
                     }
                     RuleEnum::MatchRule(match_rule) => {
                         let captures = &match_rule.captures.clone();
@@ -345,6 +425,20 @@ impl Grammar {
                         if let Some(_stack) = stack.pop() {
                             stack = _stack;
                         }
+
+                        if !has_advanced {
+                            // neither advancing nor pushing/popping
+                            self.record_endless_loop(
+                                EndlessLoopKind::MatchWithoutAdvance,
+                                matched_rule_id,
+                                line_pos,
+                            );
+                            return Some(Grammar::stop_at_line_end(
+                                stack.safe_pop(),
+                                line_tokens,
+                                line_length,
+                            ));
+                        }
                     }
                     _ => {
                         panic!("todo: RuleEnum - Others");
@@ -352,7 +446,7 @@ impl Grammar {
                 }
             }
 
-            if capture_indices[0].end > line_pos as usize {
+            if has_advanced {
                 line_pos = capture_indices[0].end as i32;
                 is_first_line = false;
             }
@@ -360,6 +454,23 @@ impl Grammar {
         Some(stack)
     }
 
+    fn stop_at_line_end(
+        mut stack: StackElement,
+        line_tokens: &mut LineTokens,
+        line_length: usize,
+    ) -> StackElement {
+        line_tokens.produce(&mut stack, line_length as i32);
+        stack
+    }
+
+    fn record_endless_loop(&mut self, kind: EndlessLoopKind, rule_id: i32, line_pos: i32) {
+        self.diagnostics.push(TokenizeDiagnostic {
+            kind,
+            rule_id,
+            line_pos,
+        });
+    }
+
     pub fn handle_captures<'a>(
         grammar: &mut Grammar,
         line_text: &'a str,
@@ -457,7 +568,7 @@ impl Grammar {
                     ));
                 }
             } else {
-                println!("lose rule: {:?}", captures[i].clone());
+                eprintln!("lose rule: {:?}", captures[i].clone());
             }
         }
 
@@ -573,19 +684,15 @@ impl Grammar {
             self.rule_container
                 .compile_rule(stack, is_first_line, line_pos == anchor_position);
 
-        let r = rule_scanner
-            .scanner
-            .find_next_match_sync(line_text, line_pos);
+        let r = rule_scanner.scanner.find_next_match(line_text, line_pos);
 
-        if let Some(result) = r {
-            let match_rule_result = MatchRuleResult {
+        match r {
+            ScanResult::Matched(result) => Some(MatchRuleResult {
                 capture_indices: result.capture_indices,
                 matched_rule_id: rule_scanner.rules[result.index],
-            };
-
-            Some(match_rule_result)
-        } else {
-            None
+            }),
+            // a search which hits the backtracking limits degrades to no match
+            ScanResult::NoMatch | ScanResult::Aborted(_) => None,
         }
     }
 
@@ -689,9 +796,12 @@ impl IRuleRegistry for Grammar {
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
     use std::fs::File;
     use std::io::Write;
+    use std::path::PathBuf;
 
+    use crate::grammar::grammar::EndlessLoopKind;
     use crate::grammar::line_tokens::IToken;
     use crate::grammar::{Grammar, StackElement};
     use crate::rule::abstract_rule::RuleEnum;
@@ -753,7 +863,7 @@ return 0;
     #[test]
     fn should_build_json_grammar() {
         let code = "{}";
-        let grammar = Grammar::from_code("extensions/json/syntaxes/json.tmLanguage.json", code);
+        let grammar = Grammar::from_code("extensions/json/syntaxes/JSON.tmLanguage.json", code);
         assert_eq!(grammar.rule_container.rule_id2desc.len(), 35);
         debug_output(&grammar, String::from("program.json"));
     }
@@ -877,6 +987,25 @@ hellomake: $(OBJ)
         assert_eq!(String::from("6,14"), x.join(","));
     }
 
+    #[test]
+    fn should_break_endless_loop_for_forever_grammar() {
+        let mut grammar = Grammar::for_test("fixtures/test-cases/first-mate/fixtures/forever.json");
+        let result = grammar.tokenize_line("forever and ever", &mut None);
+
+        assert_eq!(1, result.diagnostics.len());
+        let diagnostic = &result.diagnostics[0];
+        assert_eq!(EndlessLoopKind::PushSameBeginEndRule, diagnostic.kind);
+        assert_eq!(0, diagnostic.line_pos);
+        assert_eq!(
+            "BeginEndRule",
+            grammar.get_rule(diagnostic.rule_id).get_rule()._type
+        );
+
+        let last = result.tokens.last().unwrap();
+        assert_eq!("forever and ever".len() + 1, last.end_index as usize);
+        assert!(grammar.diagnostics.is_empty());
+    }
+
     #[test]
     fn should_build_for_groovy() {
         let mut grammar = Grammar::for_test("extensions/groovy/syntaxes/groovy.tmLanguage.json");
@@ -888,4 +1017,35 @@ hellomake: $(OBJ)
         assert_eq!(9, tokens[2].start_index);
         assert_eq!(13, tokens[3].start_index);
     }
+
+    #[test]
+    fn should_tokenize_colorize_fixtures_of_python_and_ruby() {
+        let fixtures = [
+            (
+                "extensions/python/syntaxes/MagicPython.tmLanguage.json",
+                "extensions/python/test/colorize-fixtures/test.py",
+            ),
+            (
+                "extensions/ruby/syntaxes/ruby.tmLanguage.json",
+                "extensions/ruby/test/colorize-fixtures/test.rb",
+            ),
+        ];
+
+        for (grammar_path, fixture) in fixtures.iter() {
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .parent()
+                .unwrap()
+                .join(fixture);
+            let code = fs::read_to_string(path).unwrap();
+
+            let mut grammar = Grammar::for_test(grammar_path);
+            let mut rule_stack = Some(StackElement::null());
+            for line in code.lines() {
+                let result = grammar.tokenize_line(line, &mut rule_stack);
+                assert!(!result.tokens.is_empty());
+                rule_stack = result.rule_stack;
+            }
+            assert!(grammar.diagnostics.is_empty(), "{}", fixture);
+        }
+    }
 }
@@ -4,6 +4,7 @@ pub use self::grammar::Grammar;
 pub use self::scope_list_element::ScopeListElement;
 pub use self::scope_metadata::ScopeMetadata;
 pub use self::stack_element::StackElement;
+pub use self::tokenize_state::{TokenizeState, TokenizeStateError};
 
 pub mod grammar;
 pub mod grammar_reader;
@@ -14,6 +15,7 @@ pub mod rule_container;
 pub mod scope_list_element;
 pub mod scope_metadata;
 pub mod stack_element;
+pub mod tokenize_state;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct MatchRuleResult {
@@ -1,7 +1,7 @@
 use crate::grammar::ScopeListElement;
 
 // todo: change to rccall https://stackoverflow.com/questions/36167160/how-do-i-express-mutually-recursive-data-structures-in-safe-rust
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StackElement {
     // todo: change to really parent: https://stackoverflow.com/questions/58683326/how-to-have-multiple-references-for-a-single-node-in-a-tree-structure-using-rust
     pub parent: Option<Box<StackElement>>,
@@ -43,6 +43,32 @@ impl StackElement {
         return None;
     }
 
+    /// pop unless it is the root element
+    pub fn safe_pop(&self) -> StackElement {
+        match self.pop() {
+            None => self.clone(),
+            Some(parent) => parent,
+        }
+    }
+
+    /// the same rule is on the stack and entered at the same position as `other`
+    pub fn has_same_rule_as(&self, other: &StackElement) -> bool {
+        let mut element = Some(self);
+        while let Some(el) = element {
+            if el.enter_pos != other.enter_pos {
+                break;
+            }
+
+            if el.rule_id == other.rule_id {
+                return true;
+            }
+
+            element = el.parent.as_deref();
+        }
+
+        false
+    }
+
     pub fn new(
         parent: Option<Box<StackElement>>,
         rule_id: i32,
@@ -195,4 +221,23 @@ mod tests {
         assert_eq!(parent.anchor_pos, -1);
         assert_eq!(parent.enter_pos, -1);
     }
+
+    #[test]
+    fn should_find_same_rule_at_same_position() {
+        let element = ScopeListElement::new(None, String::from("scope"));
+        let root =
+            StackElement::null().push(1, 0, 0, false, None, element.clone(), element.clone());
+        let first = root.push(2, 3, 0, false, None, element.clone(), element.clone());
+
+        let same = first.push(2, 3, 0, false, None, element.clone(), element.clone());
+        assert!(first.has_same_rule_as(&same));
+
+        let advanced = first.push(2, 4, 0, false, None, element.clone(), element.clone());
+        assert!(!first.has_same_rule_as(&advanced));
+
+        let other = first.push(3, 3, 0, false, None, element.clone(), element.clone());
+        assert!(!first.has_same_rule_as(&other));
+
+        assert_eq!(StackElement::null(), root.safe_pop().safe_pop());
+    }
 }
@@ -0,0 +1,199 @@
+use crate::grammar::{Grammar, StackElement};
+use std::fmt;
+
+/// bump it when the layout of `StackElement` or the rule id assignment changes
+pub const TOKENIZE_STATE_VERSION: u32 = 1;
+
+/// A persisted tokenizer state, the rule ids of `stack` only make sense for the grammar
+/// with the same `fingerprint`, so tokenization can pause at line N and resume in another
+/// process with the same grammar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenizeState {
+    pub version: u32,
+    pub fingerprint: String,
+    pub stack: StackElement,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenizeStateError {
+    Parse(String),
+    Version { expected: u32, actual: u32 },
+    Fingerprint { expected: String, actual: String },
+    UnknownRule(i32),
+}
+
+impl fmt::Display for TokenizeStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenizeStateError::Parse(err) => write!(f, "invalid tokenize state: {}", err),
+            TokenizeStateError::Version { expected, actual } => write!(
+                f,
+                "tokenize state version {} is not supported, expected {}",
+                actual, expected
+            ),
+            TokenizeStateError::Fingerprint { expected, actual } => write!(
+                f,
+                "tokenize state is for grammar {}, but current grammar is {}",
+                actual, expected
+            ),
+            TokenizeStateError::UnknownRule(rule_id) => {
+                write!(f, "tokenize state refers to unknown rule {}", rule_id)
+            }
+        }
+    }
+}
+
+impl TokenizeState {
+    pub fn new(grammar: &Grammar, stack: StackElement) -> Self {
+        TokenizeState {
+            version: TOKENIZE_STATE_VERSION,
+            fingerprint: grammar.fingerprint().to_string(),
+            stack,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).unwrap()
+    }
+
+    pub fn from_json(data: &str) -> Result<Self, TokenizeStateError> {
+        serde_json::from_str(data).map_err(|err| TokenizeStateError::Parse(err.to_string()))
+    }
+
+    /// check the state is made by `grammar`, and return the stack to resume with
+    pub fn restore(self, grammar: &mut Grammar) -> Result<StackElement, TokenizeStateError> {
+        if self.version != TOKENIZE_STATE_VERSION {
+            return Err(TokenizeStateError::Version {
+                expected: TOKENIZE_STATE_VERSION,
+                actual: self.version,
+            });
+        }
+
+        if self.fingerprint != grammar.fingerprint() {
+            return Err(TokenizeStateError::Fingerprint {
+                expected: grammar.fingerprint().to_string(),
+                actual: self.fingerprint,
+            });
+        }
+
+        // the null stack is the state before the first line
+        if self.stack != StackElement::null() {
+            grammar.compile_root_rule();
+            let mut element = Some(&self.stack);
+            while let Some(el) = element {
+                if !grammar
+                    .rule_container
+                    .rule_id2desc
+                    .contains_key(&el.rule_id)
+                {
+                    return Err(TokenizeStateError::UnknownRule(el.rule_id));
+                }
+                element = el.parent.as_deref();
+            }
+        }
+
+        Ok(self.stack)
+    }
+}
+
+/// FNV-1a, which is stable across processes and Rust versions unlike `DefaultHasher`
+pub fn fnv1a_64(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+impl Grammar {
+    pub fn save_state(&self, stack: &StackElement) -> String {
+        TokenizeState::new(self, stack.clone()).to_json()
+    }
+
+    pub fn restore_state(&mut self, data: &str) -> Result<StackElement, TokenizeStateError> {
+        TokenizeState::from_json(data)?.restore(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grammar::tokenize_state::{fnv1a_64, TokenizeState};
+    use crate::grammar::{Grammar, StackElement, TokenizeStateError};
+
+    const MAKEFILE: &str = "extensions/make/syntaxes/make.tmLanguage.json";
+
+    fn token_lengths(grammar: &mut Grammar, lines: &[&str], state: StackElement) -> Vec<usize> {
+        let mut rule_stack = Some(state);
+        let mut lengths = vec![];
+        for line in lines {
+            let result = grammar.tokenize_line(line, &mut rule_stack);
+            rule_stack = result.rule_stack;
+            lengths.push(result.tokens.len());
+        }
+        lengths
+    }
+
+    #[test]
+    fn should_hash_by_fnv1a() {
+        assert_eq!(0xcbf29ce484222325, fnv1a_64(b""));
+        assert_eq!(0xaf63dc4c8601ec8c, fnv1a_64(b"a"));
+    }
+
+    #[test]
+    fn should_resume_tokenize_in_another_grammar_instance() {
+        let lines = vec!["hellomake: $(OBJ)", "\t$(CC) -o $@ $^ $(CFLAGS)"];
+
+        let mut grammar = Grammar::for_test(MAKEFILE);
+        let expected = token_lengths(&mut grammar, &lines, StackElement::null());
+
+        let mut first = Grammar::for_test(MAKEFILE);
+        let result = first.tokenize_line(lines[0], &mut Some(StackElement::null()));
+        let data = first.save_state(&result.rule_stack.unwrap());
+
+        let mut second = Grammar::for_test(MAKEFILE);
+        let stack = second.restore_state(&data).unwrap();
+        let resumed = token_lengths(&mut second, &lines[1..], stack);
+
+        assert_eq!(expected[1..].to_vec(), resumed);
+    }
+
+    #[test]
+    fn should_reject_state_of_other_grammar() {
+        let mut makefile = Grammar::for_test(MAKEFILE);
+        let result = makefile.tokenize_line("hellomake: $(OBJ)", &mut None);
+        let data = makefile.save_state(&result.rule_stack.unwrap());
+
+        let mut json = Grammar::for_test("extensions/json/syntaxes/JSON.tmLanguage.json");
+        match json.restore_state(&data) {
+            Err(TokenizeStateError::Fingerprint { .. }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_keep_fingerprint_after_tokenize() {
+        let mut grammar = Grammar::for_test(MAKEFILE);
+        let fingerprint = grammar.fingerprint().to_string();
+        grammar.tokenize_line("hellomake: $(OBJ)", &mut None);
+
+        assert_eq!(fingerprint, grammar.fingerprint());
+        assert_eq!(fingerprint, Grammar::for_test(MAKEFILE).fingerprint());
+    }
+
+    #[test]
+    fn should_reject_other_version() {
+        let mut grammar = Grammar::for_test(MAKEFILE);
+        let mut state = TokenizeState::new(&grammar, StackElement::null());
+        state.version = 0;
+
+        let result = grammar.restore_state(&state.to_json());
+        assert_eq!(
+            Err(TokenizeStateError::Version {
+                expected: 1,
+                actual: 0
+            }),
+            result
+        );
+    }
+}
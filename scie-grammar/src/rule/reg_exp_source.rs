@@ -179,23 +179,24 @@ impl RegExpSource {
         let mut has_anchor = false;
 
         let result: String;
-        let length = exp_source.len();
+        // by bytes, the escapes are ASCII, so the slices are always on char boundaries
+        let bytes = exp_source.as_bytes();
+        let length = bytes.len();
         let mut output: Vec<&str> = vec![];
         let mut last_pushed_pos = 0;
 
         let mut pos = 0;
-        let chars: Vec<char> = exp_source.chars().collect();
 
         while pos < length {
-            let ch = chars[pos];
-            if ch == '\\' {
+            let ch = bytes[pos];
+            if ch == b'\\' {
                 if pos + 1 < length {
-                    let next_char = chars[pos + 1];
-                    if next_char == 'z' {
+                    let next_char = bytes[pos + 1];
+                    if next_char == b'z' {
                         output.push(&exp_source[last_pushed_pos..pos]);
                         output.push("$(?!\n)(?<!\n)");
                         last_pushed_pos = pos + 2;
-                    } else if next_char == 'G' || next_char == 'A' {
+                    } else if next_char == b'G' || next_char == b'A' {
                         has_anchor = true
                     }
 
@@ -233,7 +234,8 @@ impl RegExpSource {
     }
 
     fn build_anchor_cache(&self) -> AnchorCache {
-        let length = self.source.len();
+        let chars: Vec<char> = self.source.chars().collect();
+        let length = chars.len();
 
         let mut a0_g0_result: Vec<String> = vec![];
         let mut a0_g1_result: Vec<String> = vec![];
@@ -248,7 +250,6 @@ impl RegExpSource {
         let mut pos = 0;
         let mut ch: char;
         let mut next_char: char;
-        let chars: Vec<char> = self.source.chars().collect();
 
         while pos < length {
             ch = chars[pos];
@@ -332,30 +333,46 @@ impl RegExpSource {
     ) -> String {
         let captured_values: Vec<String> = capture_indices
             .into_iter()
-            .map(|x| {
-                return line_text[x.start..x.end].to_string();
-            })
+            .map(|x| RegExpSource::utf16_substring(line_text, x.start, x.end))
             .collect();
 
         let result = BACK_REFERENCING_END
-            .replace(&*self.source, |caps: &Captures| {
+            .replace_all(&*self.source, |caps: &Captures| {
                 let index = caps.get(1).unwrap().as_str().parse::<usize>().unwrap();
                 let mut chars = "";
                 if captured_values.get(index).is_some() {
                     chars = &*captured_values[index];
                 };
 
-                return REG_EXP_REGEX.replace(&chars, "\\$&").to_string();
+                return REG_EXP_REGEX.replace_all(&chars, "\\$0").to_string();
             })
             .to_string();
 
         return result;
     }
+
+    /// `start` and `end` are the UTF-16 offsets of the scanner, the ones of unmatched groups
+    /// are out of the line, so they are empty
+    fn utf16_substring(text: &str, start: usize, end: usize) -> String {
+        let mut offset = 0;
+        let mut output = String::new();
+        for ch in text.chars() {
+            if offset >= end {
+                break;
+            }
+            if offset >= start {
+                output.push(ch);
+            }
+            offset += ch.len_utf16();
+        }
+        output
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::rule::RegExpSource;
+    use scie_scanner::scanner::scie_scanner::IOnigCaptureIndex;
 
     #[test]
     fn should_change_resource_for_g() {
@@ -405,6 +422,40 @@ mod tests {
         assert_eq!("(^[ ]*|\\G\\s*)([^\\s]+)\\s*(=|\\?=|:=|\\+=)", cache.a1_g1);
     }
 
+    #[test]
+    fn should_keep_anchors_after_non_ascii_chars() {
+        let source = RegExpSource::new(String::from("(«)\\G\\z"), 1);
+        assert!(source.has_anchor);
+        assert_eq!("(«)\\G$(?!\n)(?<!\n)", source.source);
+        assert_eq!(
+            "(«)\\\u{ffff}$(?!\n)(?<!\n)",
+            source._anchor_cache.unwrap().a0_g0
+        );
+    }
+
+    #[test]
+    fn should_resolve_back_refs_of_unmatched_and_non_ascii_groups() {
+        let source = RegExpSource::new(String::from("\\1\\2"), 1);
+        let capture = |start: usize, end: usize| IOnigCaptureIndex {
+            start,
+            end,
+            length: end.wrapping_sub(start),
+        };
+
+        let resolved = source.resolve_back_references(
+            "é<<é",
+            vec![
+                capture(0, 4),
+                capture(1, 3),
+                capture(usize::MAX, usize::MAX),
+            ],
+        );
+        assert_eq!("<<", resolved);
+
+        let resolved = source.resolve_back_references("a.b.", vec![capture(0, 4), capture(0, 4)]);
+        assert_eq!("a\\.b\\.", resolved);
+    }
+
     #[test]
     fn should_return_true_when_has_back_refs() {
         let source = RegExpSource::new(String::from("(>(<)/)(\\2)(>)"), 1);
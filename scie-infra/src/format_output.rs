@@ -1,18 +1,55 @@
 pub struct FormatOutput {}
 
 impl FormatOutput {
-    pub fn hashmap() {
-        println!(
-            "{0: <10} | {1: <10} | {2: <10} | {3: <10}",
-            "total", "blanks", "comments", "code"
-        );
-        println!("{0: <10} | {1: <10} | {2: <10} | {3: <10}", 0, 0, 0, 0);
-        println!("{0: <10} | {1: <10} | {2: <10} | {3: <10}", 77, 0, 3, 74);
-        println!("{0: <10} | {1: <10} | {2: <10} | {3: <10}", 112, 0, 6, 106);
-        println!(
-            "{0: <10} | {1: <10} | {2: <10} | {3: <10}",
-            460, 0, 10, 1371
-        );
+    /// left aligned columns, each one is as wide as its longest cell
+    pub fn table(headers: &[&str], rows: &[Vec<String>]) -> String {
+        let mut widths: Vec<usize> = headers.iter().map(|header| header.len().max(10)).collect();
+        for row in rows.iter() {
+            for (index, cell) in row.iter().enumerate() {
+                if index < widths.len() {
+                    widths[index] = widths[index].max(cell.chars().count());
+                }
+            }
+        }
+
+        let format_row = |cells: Vec<String>| -> String {
+            let columns: Vec<String> = cells
+                .iter()
+                .enumerate()
+                .map(|(index, cell)| format!("{0: <1$}", cell, widths[index]))
+                .collect();
+            format!("{}\n", columns.join(" | ").trim_end())
+        };
+
+        let mut output = format_row(headers.iter().map(|header| header.to_string()).collect());
+        let line: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+        output.push_str(&format!("{}\n", line.join("-|-")));
+        for row in rows.iter() {
+            output.push_str(&format_row(row.clone()));
+        }
+
+        output
+    }
+
+    /// RFC 4180, cells with a comma, quote or line break are quoted
+    pub fn csv(headers: &[&str], rows: &[Vec<String>]) -> String {
+        let escape = |cell: &str| -> String {
+            if cell.contains(',') || cell.contains('"') || cell.contains('\n') {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.to_string()
+            }
+        };
+
+        let mut output = String::new();
+        let header_cells: Vec<String> = headers.iter().map(|header| escape(header)).collect();
+        output.push_str(&format!("{}\n", header_cells.join(",")));
+        for row in rows.iter() {
+            let cells: Vec<String> = row.iter().map(|cell| escape(cell)).collect();
+            output.push_str(&format!("{}\n", cells.join(",")));
+        }
+
+        output
     }
 }
 
@@ -21,7 +58,18 @@ mod tests {
     use crate::format_output::FormatOutput;
 
     #[test]
-    fn should_filter_gitignore_rules() {
-        FormatOutput::hashmap();
+    fn should_align_table_columns() {
+        let rows = vec![vec![String::from("java"), String::from("77")]];
+        let output = FormatOutput::table(&["language", "code"], &rows);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!("language   | code", lines[0]);
+        assert_eq!("java       | 77", lines[2]);
+    }
+
+    #[test]
+    fn should_quote_csv_cells() {
+        let rows = vec![vec![String::from("a,b"), String::from("say \"hi\"")]];
+        let output = FormatOutput::csv(&["path", "text"], &rows);
+        assert_eq!("path,text\n\"a,b\",\"say \"\"hi\"\"\"\n", output);
     }
 }
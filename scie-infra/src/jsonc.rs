@@ -0,0 +1,119 @@
+/// Strip the comments and trailing commas of JSON with comments, which is used by VS Code for
+/// `language-configuration.json` files, so that `serde_json` can parse it.
+pub fn strip_jsonc(code: &str) -> String {
+    let chars: Vec<char> = code.chars().collect();
+    let mut output = String::with_capacity(code.len());
+    let mut in_string = false;
+    let mut index = 0;
+
+    while index < chars.len() {
+        let char = chars[index];
+        let next = chars.get(index + 1).copied();
+
+        if in_string {
+            output.push(char);
+            if char == '\\' {
+                if let Some(escaped) = next {
+                    output.push(escaped);
+                    index += 1;
+                }
+            } else if char == '"' {
+                in_string = false;
+            }
+            index += 1;
+            continue;
+        }
+
+        match (char, next) {
+            ('"', _) => {
+                in_string = true;
+                output.push(char);
+            }
+            ('/', Some('/')) => {
+                while index < chars.len() && chars[index] != '\n' {
+                    index += 1;
+                }
+                continue;
+            }
+            ('/', Some('*')) => {
+                index += 2;
+                while index < chars.len()
+                    && !(chars[index] == '*' && chars.get(index + 1) == Some(&'/'))
+                {
+                    index += 1;
+                }
+                index += 2;
+                continue;
+            }
+            _ => output.push(char),
+        }
+
+        index += 1;
+    }
+
+    remove_trailing_commas(&output)
+}
+
+fn remove_trailing_commas(code: &str) -> String {
+    let chars: Vec<char> = code.chars().collect();
+    let mut output = String::with_capacity(code.len());
+    let mut in_string = false;
+    let mut index = 0;
+
+    while index < chars.len() {
+        let char = chars[index];
+        if in_string {
+            if char == '\\' {
+                output.push(char);
+                index += 1;
+                if index < chars.len() {
+                    output.push(chars[index]);
+                }
+                index += 1;
+                continue;
+            }
+            if char == '"' {
+                in_string = false;
+            }
+        } else if char == '"' {
+            in_string = true;
+        } else if char == ',' {
+            let next = chars[index + 1..].iter().find(|c| !c.is_whitespace());
+            if next == Some(&'}') || next == Some(&']') {
+                index += 1;
+                continue;
+            }
+        }
+
+        output.push(char);
+        index += 1;
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::jsonc::strip_jsonc;
+
+    #[test]
+    fn should_strip_comments_out_of_strings() {
+        let code = r##"{
+    "lineComment": "//", // "#"
+    /* block
+       comment */
+    "url": "http://a/*b*/"
+}"##;
+        let value: serde_json::Value = serde_json::from_str(&strip_jsonc(code)).unwrap();
+        assert_eq!("//", value["lineComment"]);
+        assert_eq!("http://a/*b*/", value["url"]);
+    }
+
+    #[test]
+    fn should_remove_trailing_commas() {
+        let code = r#"{ "brackets": [ ["{", "}"], ["[", "]"], ], "text": ",]", }"#;
+        let value: serde_json::Value = serde_json::from_str(&strip_jsonc(code)).unwrap();
+        assert_eq!(2, value["brackets"].as_array().unwrap().len());
+        assert_eq!(",]", value["text"]);
+    }
+}
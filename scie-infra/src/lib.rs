@@ -3,3 +3,8 @@ extern crate lazy_static;
 
 pub mod finder;
 pub mod format_output;
+pub mod jsonc;
+pub mod paths;
+pub mod plist;
+pub mod toml;
+pub mod xml;
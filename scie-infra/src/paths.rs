@@ -0,0 +1,54 @@
+use std::path::{Component, Path, PathBuf};
+
+/// `/` separated, empty for the root
+pub fn relative_path(path: &Path, root: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let parts: Vec<String> = relative
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy().to_string())
+        .collect();
+    parts.join("/")
+}
+
+/// `.` and `..` are removed without reading the disk
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            _ => normalized.push(component.as_os_str()),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::paths::{normalize, relative_path};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn should_join_relative_path_with_slash() {
+        let root = Path::new("shop");
+        assert_eq!("", relative_path(Path::new("shop"), root));
+        assert_eq!(
+            "api/src",
+            relative_path(&root.join("api").join("src"), root)
+        );
+        assert_eq!("other", relative_path(Path::new("other"), root));
+    }
+
+    #[test]
+    fn should_normalize_without_disk() {
+        assert_eq!(
+            PathBuf::from("shop/core"),
+            normalize(Path::new("shop/api/./../core"))
+        );
+        assert_eq!(PathBuf::from("../lib"), normalize(Path::new("../lib")));
+    }
+}
@@ -0,0 +1,113 @@
+use serde_json::{Map, Number, Value};
+
+use crate::xml::XmlElement;
+
+/// Parse a XML property list, such as a `.tmLanguage` grammar, into a JSON value, so that it
+/// is read as the JSON grammars, dates and data are kept as strings.
+pub fn parse_plist(code: &str) -> Result<Value, String> {
+    let root = XmlElement::parse(code)?;
+    if root.name != "plist" {
+        return Err(format!("not a plist: {}", root.name));
+    }
+
+    match root.children.first() {
+        Some(element) => to_json(element),
+        None => Ok(Value::Null),
+    }
+}
+
+fn to_json(element: &XmlElement) -> Result<Value, String> {
+    let value = match element.name.as_str() {
+        "dict" => {
+            let mut map = Map::new();
+            let mut children = element.children.iter();
+            while let Some(key) = children.next() {
+                if key.name != "key" {
+                    return Err(format!("expected key in dict, found: {}", key.name));
+                }
+                let value = children
+                    .next()
+                    .ok_or_else(|| format!("no value of key: {}", key.text))?;
+                map.insert(key.text.clone(), to_json(value)?);
+            }
+            Value::Object(map)
+        }
+        "array" => Value::Array(
+            element
+                .children
+                .iter()
+                .map(to_json)
+                .collect::<Result<Vec<Value>, String>>()?,
+        ),
+        "string" | "date" | "data" => Value::String(element.text.clone()),
+        "integer" => element
+            .text
+            .trim()
+            .parse::<i64>()
+            .map(|integer| Value::Number(Number::from(integer)))
+            .map_err(|err| format!("{}: {}", element.text, err))?,
+        "real" => element
+            .text
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        name => return Err(format!("unknown plist element: {}", name)),
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::plist::parse_plist;
+
+    #[test]
+    fn should_parse_dicts_arrays_and_values() {
+        let code = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>scopeName</key>
+    <string>source.ini</string>
+    <key>patterns</key>
+    <array>
+        <dict>
+            <key>include</key>
+            <string>#comment</string>
+        </dict>
+        <dict>
+            <key>match</key>
+            <string>^\s*(\[)(.*?)(\])</string>
+        </dict>
+    </array>
+    <key>version</key>
+    <integer>2</integer>
+    <key>ratio</key>
+    <real>0.5</real>
+    <key>hidden</key>
+    <true/>
+</dict>
+</plist>
+"#;
+
+        let value = parse_plist(code).unwrap();
+        assert_eq!("source.ini", value["scopeName"]);
+        assert_eq!("#comment", value["patterns"][0]["include"]);
+        assert_eq!("^\\s*(\\[)(.*?)(\\])", value["patterns"][1]["match"]);
+        assert_eq!(2, value["version"]);
+        assert_eq!(0.5, value["ratio"]);
+        assert_eq!(true, value["hidden"]);
+    }
+
+    #[test]
+    fn should_fail_on_other_documents() {
+        assert!(parse_plist("<project></project>").is_err());
+        assert!(parse_plist("<plist><dict><string>a</string></dict></plist>").is_err());
+        assert!(parse_plist("<plist><dict><key>a</key></dict></plist>").is_err());
+        assert!(parse_plist("scopeName: source.yaml").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use serde_json::{Map, Number, Value};
+
+/// Parse TOML into a JSON value, so that manifests such as `Cargo.toml` and `pyproject.toml`
+/// are read as the other JSON ones, dates and times are kept as strings.
+pub fn parse_toml(code: &str) -> Result<Value, String> {
+    let value: ::toml::Value = code.parse().map_err(|err| format!("{}", err))?;
+    Ok(to_json(value))
+}
+
+fn to_json(value: ::toml::Value) -> Value {
+    match value {
+        ::toml::Value::String(string) => Value::String(string),
+        ::toml::Value::Integer(integer) => Value::Number(Number::from(integer)),
+        ::toml::Value::Float(float) => Number::from_f64(float)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        ::toml::Value::Boolean(boolean) => Value::Bool(boolean),
+        ::toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        ::toml::Value::Array(array) => Value::Array(array.into_iter().map(to_json).collect()),
+        ::toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, to_json(value)))
+                .collect::<Map<String, Value>>(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::toml::parse_toml;
+
+    #[test]
+    fn should_parse_tables_and_values() {
+        let code = r#"
+# a comment
+[package]
+name = "scie" # trailing comment
+version = '0.1.0'
+edition = 2018
+authors = [
+    "Phodal Huang <h@phodal.com>",
+]
+description = """
+multi \
+    line"""
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+regex.version = "1"
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+
+[[package.metadata.bin]]
+name = "a"
+[[package.metadata.bin]]
+name = "b"
+release = 1979-05-27
+ratio = 0.5
+"#;
+
+        let value = parse_toml(code).unwrap();
+        assert_eq!("scie", value["package"]["name"]);
+        assert_eq!("0.1.0", value["package"]["version"]);
+        assert_eq!(2018, value["package"]["edition"]);
+        assert_eq!(
+            "Phodal Huang <h@phodal.com>",
+            value["package"]["authors"][0]
+        );
+        assert_eq!("multi line", value["package"]["description"]);
+        assert_eq!("derive", value["dependencies"]["serde"]["features"][0]);
+        assert_eq!("1", value["dependencies"]["regex"]["version"]);
+        assert_eq!("0.2", value["target"]["cfg(unix)"]["dependencies"]["libc"]);
+
+        let bins = value["package"]["metadata"]["bin"].as_array().unwrap();
+        assert_eq!(2, bins.len());
+        assert_eq!("b", bins[1]["name"]);
+        assert_eq!("1979-05-27", bins[1]["release"]);
+        assert_eq!(0.5, bins[1]["ratio"]);
+    }
+
+    #[test]
+    fn should_fail_on_invalid_lines() {
+        assert!(parse_toml("name = ").is_err());
+        assert!(parse_toml("name = \"unclosed").is_err());
+        assert!(parse_toml("[table").is_err());
+        assert!(parse_toml("a = 1 b = 2").is_err());
+    }
+}
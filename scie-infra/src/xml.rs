@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// An element of a XML document, which is enough to read build files, such as `pom.xml`:
+/// declarations, comments, doctypes and processing instructions are skipped, texts of mixed
+/// content are joined, and namespace prefixes are kept in names.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct XmlElement {
+    pub name: String,
+    pub attributes: BTreeMap<String, String>,
+    pub children: Vec<XmlElement>,
+    pub text: String,
+}
+
+impl XmlElement {
+    /// the root element of a document
+    pub fn parse(code: &str) -> Result<XmlElement, String> {
+        let mut reader = Reader::from_str(code);
+        let mut buf = vec![];
+        let mut stack: Vec<XmlElement> = vec![];
+        let mut root: Option<XmlElement> = None;
+
+        loop {
+            let event = reader
+                .read_event(&mut buf)
+                .map_err(|err| format!("{} at {}", err, reader.buffer_position()))?;
+            match event {
+                Event::Start(start) => stack.push(XmlElement::open(&reader, &start)?),
+                Event::Empty(start) => {
+                    let element = XmlElement::open(&reader, &start)?;
+                    XmlElement::close(element, &mut stack, &mut root)?;
+                }
+                Event::End(_) => match stack.pop() {
+                    Some(element) => XmlElement::close(element, &mut stack, &mut root)?,
+                    None => return Err("unexpected end of element".to_string()),
+                },
+                Event::Text(text) => {
+                    let text = text
+                        .unescape_and_decode(&reader)
+                        .map_err(|err| err.to_string())?;
+                    match stack.last_mut() {
+                        Some(element) => element.text.push_str(&text),
+                        None if text.trim().is_empty() => {}
+                        None => return Err(format!("unexpected text: {}", text.trim())),
+                    }
+                }
+                Event::CData(data) => {
+                    let data = reader.decode(&data).map_err(|err| err.to_string())?;
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(data);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        if let Some(element) = stack.last() {
+            return Err(format!("unclosed element: {}", element.name));
+        }
+        root.ok_or_else(|| "no root element".to_string())
+    }
+
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    pub fn children_by_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// by `/` separated names, such as `build/sourceDirectory`
+    pub fn find(&self, path: &str) -> Option<&XmlElement> {
+        path.split('/')
+            .try_fold(self, |element, name| element.child(name))
+    }
+
+    /// trimmed text of the element at `path`
+    pub fn text_of(&self, path: &str) -> Option<String> {
+        self.find(path)
+            .map(|element| element.text.trim().to_string())
+    }
+
+    fn open(reader: &Reader<&[u8]>, start: &BytesStart) -> Result<XmlElement, String> {
+        let mut element = XmlElement {
+            name: reader
+                .decode(start.name())
+                .map_err(|err| err.to_string())?
+                .to_string(),
+            ..Default::default()
+        };
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(|err| err.to_string())?;
+            let key = reader
+                .decode(attribute.key)
+                .map_err(|err| err.to_string())?
+                .to_string();
+            let value = attribute
+                .unescape_and_decode_value(reader)
+                .map_err(|err| err.to_string())?;
+            element.attributes.insert(key, value);
+        }
+        Ok(element)
+    }
+
+    /// into its parent, or as the root if there is no one
+    fn close(
+        element: XmlElement,
+        stack: &mut [XmlElement],
+        root: &mut Option<XmlElement>,
+    ) -> Result<(), String> {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(element),
+            None if root.is_none() => *root = Some(element),
+            None => return Err(format!("unexpected content after root: {}", element.name)),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::xml::XmlElement;
+
+    #[test]
+    fn should_parse_elements_attributes_and_texts() {
+        let code = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- a comment -->
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+    <name>a &amp; b &#x41;</name>
+    <description><![CDATA[<tag> & more]]></description>
+    <modules>
+        <module>core</module>
+        <!-- <module>old</module> -->
+        <module>web</module>
+    </modules>
+    <build><resources><resource filtering='true'/></resources></build>
+</project>
+"#;
+
+        let project = XmlElement::parse(code).unwrap();
+        assert_eq!("project", project.name);
+        assert_eq!(
+            Some(&String::from("http://maven.apache.org/POM/4.0.0")),
+            project.attributes.get("xmlns")
+        );
+        assert_eq!(Some(String::from("a & b A")), project.text_of("name"));
+        assert_eq!(
+            Some(String::from("<tag> & more")),
+            project.text_of("description")
+        );
+
+        let modules: Vec<&str> = project
+            .child("modules")
+            .unwrap()
+            .children_by_name("module")
+            .map(|module| module.text.as_str())
+            .collect();
+        assert_eq!(vec!["core", "web"], modules);
+
+        let resource = project.find("build/resources/resource").unwrap();
+        assert_eq!("true", resource.attributes["filtering"]);
+        assert!(resource.children.is_empty());
+    }
+
+    #[test]
+    fn should_fail_on_unclosed_elements() {
+        assert!(XmlElement::parse("<a><b></a>").is_err());
+        assert!(XmlElement::parse("<a>").is_err());
+        assert!(XmlElement::parse("<a></a><b/>").is_err());
+    }
+}
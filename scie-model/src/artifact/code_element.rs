@@ -9,6 +9,30 @@ pub struct TokenElement {
     pub end_index: i32,
     pub value: String,
     pub scopes: Vec<String>,
+    /// language neutral kind of the token, by its scopes
+    #[serde(default)]
+    pub category: TokenCategory,
+}
+
+/// the kind of a token which is the same for every language, instead of the scope names of
+/// each grammar
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum TokenCategory {
+    Keyword,
+    Identifier,
+    Type,
+    FunctionDefinition,
+    FunctionCall,
+    String,
+    Number,
+    Constant,
+    Comment,
+    Operator,
+    Punctuation,
+    Whitespace,
+    #[default]
+    Other,
 }
 
 impl TokenElement {
@@ -26,6 +50,7 @@ impl TokenElement {
             end_index,
             value,
             scopes,
+            category: TokenCategory::Other,
         }
     }
 }
@@ -38,11 +63,20 @@ pub struct ElementScope {
 
 #[cfg(test)]
 mod tests {
-    use crate::artifact::TokenElement;
+    use crate::artifact::{TokenCategory, TokenElement};
 
     #[test]
     fn should_run() {
         let element = TokenElement::new(1, 1, 2, "#".to_string(), vec![]);
         assert_eq!("1:1-2", element.pos);
     }
+
+    #[test]
+    fn should_serialize_category_in_kebab_case() {
+        let mut element = TokenElement::new(1, 0, 2, "fn".to_string(), vec![]);
+        element.category = TokenCategory::FunctionDefinition;
+
+        let json = serde_json::to_string(&element).unwrap();
+        assert!(json.contains("\"category\":\"function-definition\""));
+    }
 }
@@ -1,11 +1,37 @@
-use crate::artifact::TokenElement;
+use crate::artifact::{CodeImport, CodeSymbol, TokenElement};
 use std::path::PathBuf;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct CodeFile {
     pub path: String,
     pub name: String,
+    /// id of the language which the file is tokenized by, such as `java`, empty if unknown
+    #[serde(default)]
+    pub language: String,
     pub elements: Vec<TokenElement>,
+    /// outline of the declarations, by the scopes of elements
+    #[serde(default)]
+    pub symbols: Vec<CodeSymbol>,
+    /// imports, uses and includes, by the scopes of elements
+    #[serde(default)]
+    pub imports: Vec<CodeImport>,
+    /// by the content roots of the module which the file belongs to
+    #[serde(default)]
+    pub kind: SourceKind,
+    /// by the content root, the name or the code of the file, such as `@Test` or `describe(`
+    #[serde(default)]
+    pub is_test: bool,
+}
+
+/// what a file is for in its module, such as `src/main/java` or `src/test/java` in Maven
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourceKind {
+    #[default]
+    Main,
+    Test,
+    Resource,
+    Generated,
 }
 
 impl CodeFile {
@@ -14,7 +40,12 @@ impl CodeFile {
         CodeFile {
             path: path.display().to_string(),
             name: String::from(name),
+            language: String::new(),
             elements: vec![],
+            symbols: vec![],
+            imports: vec![],
+            kind: SourceKind::Main,
+            is_test: false,
         }
     }
 }
@@ -0,0 +1,13 @@
+/// an import, use or include statement of a file, such as `java.util.List` or `stdio.h`
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct CodeImport {
+    /// as written, without quotes and whitespaces
+    pub source: String,
+    pub line_num: i32,
+}
+
+impl CodeImport {
+    pub fn new(source: String, line_num: i32) -> CodeImport {
+        CodeImport { source, line_num }
+    }
+}
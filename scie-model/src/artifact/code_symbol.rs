@@ -0,0 +1,38 @@
+/// a declaration in a file, the children are the declarations in its body
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct CodeSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub start_line: i32,
+    pub end_line: i32,
+    pub children: Vec<CodeSymbol>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum SymbolKind {
+    Package,
+    Module,
+    Class,
+    Interface,
+    Enum,
+    Struct,
+    Trait,
+    Impl,
+    /// type alias or definition, such as `type Id int` in Go
+    Type,
+    Function,
+    Method,
+}
+
+impl CodeSymbol {
+    pub fn new(name: String, kind: SymbolKind, start_line: i32) -> CodeSymbol {
+        CodeSymbol {
+            name,
+            kind,
+            start_line,
+            end_line: start_line,
+            children: vec![],
+        }
+    }
+}
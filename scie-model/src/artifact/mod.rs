@@ -2,9 +2,13 @@ use serde::{Deserialize, Serialize};
 
 pub mod code_element;
 pub mod code_file;
+pub mod code_import;
+pub mod code_symbol;
 
-pub use code_element::TokenElement;
-pub use code_file::CodeFile;
+pub use code_element::{TokenCategory, TokenElement};
+pub use code_file::{CodeFile, SourceKind};
+pub use code_import::CodeImport;
+pub use code_symbol::{CodeSymbol, SymbolKind};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Workspace {
@@ -17,6 +21,11 @@ pub struct Workspace {
 pub struct Project {
     #[serde(alias = "projectName")]
     pub name: String,
+    #[serde(default)]
+    pub path: String,
+    /// detected build tools and frameworks, such as `workspace.java.gradle`
+    #[serde(default)]
+    pub frameworks: Vec<String>,
     pub modules: Vec<Module>,
 }
 
@@ -24,6 +33,9 @@ pub struct Project {
 pub struct Module {
     #[serde(alias = "moduleName")]
     pub name: String,
+    /// relative to the project
+    #[serde(default)]
+    pub path: String,
     pub packages: Vec<Package>, // or dir ?
     pub artifacts: Vec<Artifact>,
 }
@@ -41,3 +53,14 @@ pub struct Package {
     pub name: String,
     pub files: Vec<CodeFile>,
 }
+
+impl Workspace {
+    pub fn code_files(&self) -> Vec<&CodeFile> {
+        self.projects
+            .iter()
+            .flat_map(|project| project.modules.iter())
+            .flat_map(|module| module.packages.iter())
+            .flat_map(|package| package.files.iter())
+            .collect()
+    }
+}
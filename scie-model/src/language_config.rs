@@ -1,4 +1,4 @@
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct CommentRule {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(alias = "lineComment")]
@@ -10,13 +10,13 @@ pub struct CommentRule {
 
 pub type CharacterPair = Vec<String>;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct IndentationRules {
     #[serde(alias = "decreaseIndentPattern")]
     pub decrease_indent_pattern: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct LanguageConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comments: Option<CommentRule>,
@@ -35,6 +35,10 @@ char *getLastOnigError() {
     return s;
 }
 
+int getLastOnigStatus() {
+    return lastOnigStatus;
+}
+
 #define MAX_REGIONS 1000
 
 long encodeOnigRegion(OnigRegion *result, int index) {
@@ -67,13 +71,13 @@ bool hasGAnchor(unsigned char *str, int len) {
     return false;
 }
 
-OnigRegExp *createOnigRegExp(unsigned char *data, int length) {
+OnigRegExp *createOnigRegExp(unsigned char *data, int length, OnigOptionType options, OnigSyntaxType *syntax) {
     OnigRegExp *result;
     regex_t *regex;
 
     lastOnigStatus = onig_new(&regex, data, data + length,
-                              ONIG_OPTION_CAPTURE_GROUP, ONIG_ENCODING_UTF8,
-                              ONIG_SYNTAX_DEFAULT, &lastOnigErrorInfo);
+                              options, ONIG_ENCODING_UTF8,
+                              syntax, &lastOnigErrorInfo);
 
     if (lastOnigStatus != ONIG_NORMAL) {
         return NULL;
@@ -111,6 +115,11 @@ OnigRegion *_searchOnigRegExp(OnigRegExp *regex, unsigned char *strData, int str
                          regex->region, ONIG_OPTION_NONE);
 
     if (status == ONIG_MISMATCH || status < 0) {
+        if (status != ONIG_MISMATCH) {
+            // search was aborted (retry or stack limit), don't cache it as a mismatch
+            lastOnigStatus = status;
+            regex->lastSearchStrCacheId = 0;
+        }
         regex->lastSearchMatched = false;
         return NULL;
     }
@@ -146,7 +155,8 @@ OnigRegion *searchOnigRegExp(OnigRegExp *regex, int strCacheId, unsigned char *s
 
 #pragma region OnigScanner
 
-long createOnigScanner(unsigned char **patterns, int *lengths, int count) {
+long createOnigScannerWithOptions(unsigned char **patterns, int *lengths, int count,
+                                  OnigOptionType options, OnigSyntaxType *syntax) {
     int i, j;
     OnigRegExp **regexes;
     regex_t **regs;
@@ -157,21 +167,31 @@ long createOnigScanner(unsigned char **patterns, int *lengths, int count) {
     regs = (regex_t **) malloc(sizeof(regex_t *) * count);
 
     for (i = 0; i < count; i++) {
-        regexes[i] = createOnigRegExp(patterns[i], lengths[i]);
-        regs[i] = regexes[i]->regex;
+        regexes[i] = createOnigRegExp(patterns[i], lengths[i], options, syntax);
         if (regexes[i] == NULL) {
             // parsing this regex failed, so clean up all the ones created so far
             for (j = 0; j < i; j++) {
-                free(regs[i]);
-                freeOnigRegExp(&regexes[i]);
+                onig_free(regs[j]);
+                freeOnigRegExp(&regexes[j]);
             }
             free(regexes);
             free(regs);
             return 0;
         }
+        regs[i] = regexes[i]->regex;
     }
 
-    onig_regset_new(&rset, count, regs);
+    lastOnigStatus = onig_regset_new(&rset, count, regs);
+    if (lastOnigStatus != ONIG_NORMAL) {
+        // some options (e.g. ONIG_OPTION_FIND_LONGEST) can't be used in a regset
+        for (j = 0; j < count; j++) {
+            onig_free(regs[j]);
+            freeOnigRegExp(&regexes[j]);
+        }
+        free(regexes);
+        free(regs);
+        return 0;
+    }
     free(regs);
 
     scanner = (OnigScanner *) malloc(sizeof(OnigScanner));
@@ -182,6 +202,10 @@ long createOnigScanner(unsigned char **patterns, int *lengths, int count) {
     return (long) scanner;
 }
 
+long createOnigScanner(unsigned char **patterns, int *lengths, int count) {
+    return createOnigScannerWithOptions(patterns, lengths, count, ONIG_OPTION_CAPTURE_GROUP, ONIG_SYNTAX_DEFAULT);
+}
+
 
 int freeOnigScanner(OnigScanner **scanner_ptr) {
     int i;
@@ -208,12 +232,16 @@ long findNextOnigScannerMatch(OnigScanner *scanner, int strCacheId, unsigned cha
     int i;
     int location;
 
+    lastOnigStatus = ONIG_NORMAL;
     if (strLength < 1000) {
         // for short strings, it is better to use the RegSet API, but for longer strings caching pays off
         bestResultIndex = onig_regset_search(scanner->rset, strData, strData + strLength, strData + position,
                                              strData + strLength,
                                              ONIG_REGSET_POSITION_LEAD, ONIG_OPTION_NONE, &bestLocation);
         if (bestResultIndex < 0) {
+            if (bestResultIndex != ONIG_MISMATCH) {
+                lastOnigStatus = bestResultIndex;
+            }
             return 0;
         }
         return encodeOnigRegion(onig_regset_get_region(scanner->rset, bestResultIndex), bestResultIndex);
@@ -4959,6 +4959,9 @@ extern "C" {
 extern "C" {
     pub fn getLastOnigError() -> *mut ::std::os::raw::c_char;
 }
+extern "C" {
+    pub fn getLastOnigStatus() -> ::std::os::raw::c_int;
+}
 extern "C" {
     pub fn encodeOnigRegion(
         result: *mut OnigRegion,
@@ -4972,6 +4975,8 @@ extern "C" {
     pub fn createOnigRegExp(
         data: *mut ::std::os::raw::c_uchar,
         length: ::std::os::raw::c_int,
+        options: OnigOptionType,
+        syntax: *mut OnigSyntaxType,
     ) -> *mut OnigRegExp;
 }
 extern "C" {
@@ -5001,6 +5006,15 @@ extern "C" {
         count: ::std::os::raw::c_int,
     ) -> ::std::os::raw::c_long;
 }
+extern "C" {
+    pub fn createOnigScannerWithOptions(
+        patterns: *mut *mut ::std::os::raw::c_uchar,
+        lengths: *mut ::std::os::raw::c_int,
+        count: ::std::os::raw::c_int,
+        options: OnigOptionType,
+        syntax: *mut OnigSyntaxType,
+    ) -> ::std::os::raw::c_long;
+}
 extern "C" {
     pub fn freeOnigScanner(scanner_ptr: *mut *mut OnigScanner) -> ::std::os::raw::c_int;
 }
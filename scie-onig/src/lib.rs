@@ -26,5 +26,6 @@ fn test_is_linked() {
         assert!(!onig_copyright().is_null());
         assert!(!onig_version().is_null());
         getLastOnigError();
+        assert_eq!(ONIG_NORMAL as i32, getLastOnigStatus());
     }
 }
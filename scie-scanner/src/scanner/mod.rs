@@ -1,3 +1,6 @@
 pub mod onig_string;
+pub mod regex_search;
+pub mod scanner_limits;
+pub mod scanner_options;
 pub mod scie_scanner;
 pub mod utf_string;
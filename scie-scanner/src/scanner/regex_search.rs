@@ -0,0 +1,413 @@
+use crate::scanner::scie_scanner::ScieScanner;
+use core::ops::Range;
+use core::slice;
+use scie_onig::{onig_foreach_name, onig_number_of_names, OnigRegex, OnigUChar};
+use std::collections::BTreeMap;
+use std::os::raw::{c_int, c_void};
+use std::rc::Rc;
+
+pub type GroupNames = BTreeMap<String, Vec<usize>>;
+
+/// One match of a scanner in a `&str`, all offsets are utf-8 byte offsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegexCaptures<'t> {
+    /// index of the matched pattern in the scanner
+    pub index: usize,
+    text: &'t str,
+    groups: Vec<Option<(usize, usize)>>,
+    names: Rc<GroupNames>,
+}
+
+impl<'t> RegexCaptures<'t> {
+    pub fn start(&self) -> usize {
+        self.groups[0].unwrap().0
+    }
+
+    pub fn end(&self) -> usize {
+        self.groups[0].unwrap().1
+    }
+
+    pub fn as_str(&self) -> &'t str {
+        &self.text[self.start()..self.end()]
+    }
+
+    /// group count, include the whole match as group `0`
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    pub fn range(&self, group: usize) -> Option<Range<usize>> {
+        match self.groups.get(group) {
+            Some(Some((start, end))) => Some(*start..*end),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, group: usize) -> Option<&'t str> {
+        self.range(group).map(|range| &self.text[range])
+    }
+
+    /// the last matched group of `name`, same as a `\k<name>` back reference
+    pub fn name(&self, name: &str) -> Option<&'t str> {
+        let groups = self.names.get(name)?;
+        groups.iter().rev().find_map(|group| self.get(*group))
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.names.keys()
+    }
+
+    /// expand `$n`, `${n}`, `${name}` and `$$` in `replacement` to `dst`
+    pub fn expand(&self, replacement: &str, dst: &mut String) {
+        let mut rest = replacement;
+        while let Some(dollar) = rest.find('$') {
+            dst.push_str(&rest[..dollar]);
+            rest = &rest[dollar + 1..];
+
+            if let Some(after) = rest.strip_prefix('$') {
+                dst.push('$');
+                rest = after;
+                continue;
+            }
+
+            if let Some(braced) = rest.strip_prefix('{') {
+                if let Some(close) = braced.find('}') {
+                    dst.push_str(self.reference(&braced[..close]));
+                    rest = &braced[close + 1..];
+                    continue;
+                }
+            }
+
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            if digits == 0 {
+                dst.push('$');
+                continue;
+            }
+
+            dst.push_str(self.reference(&rest[..digits]));
+            rest = &rest[digits..];
+        }
+
+        dst.push_str(rest);
+    }
+
+    fn reference(&self, reference: &str) -> &'t str {
+        let value = match reference.parse::<usize>() {
+            Ok(group) => self.get(group),
+            Err(_) => self.name(reference),
+        };
+
+        value.unwrap_or("")
+    }
+}
+
+/// Iterator over the non overlapping matches of a scanner in a `&str`.
+pub struct RegexMatches<'s, 't> {
+    scanner: &'s ScieScanner,
+    string_id: i32,
+    text: &'t str,
+    position: usize,
+    last_match_end: Option<usize>,
+    names: Vec<Rc<GroupNames>>,
+    aborted: bool,
+}
+
+impl<'s, 't> RegexMatches<'s, 't> {
+    /// a search hits the backtracking limits, and iteration stopped early
+    pub fn is_aborted(&self) -> bool {
+        self.aborted
+    }
+
+    /// the first char boundary after `position`, which may be in the middle of a char
+    fn next_char_boundary(&self, position: usize) -> usize {
+        (position + 1..=self.text.len())
+            .find(|index| self.text.is_char_boundary(*index))
+            .unwrap_or(position + 1)
+    }
+}
+
+impl<'s, 't> Iterator for RegexMatches<'s, 't> {
+    type Item = RegexCaptures<'t>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position <= self.text.len() {
+            let result =
+                self.scanner
+                    ._find_next_match_utf8(self.string_id, self.text, self.position as i32);
+
+            let (index, regions) = match result {
+                Ok(Some(raw_match)) => raw_match,
+                Ok(None) => return None,
+                Err(_) => {
+                    self.aborted = true;
+                    return None;
+                }
+            };
+
+            let groups: Vec<Option<(usize, usize)>> = regions
+                .iter()
+                .map(|(start, end)| {
+                    if *start < 0 || *end < 0 {
+                        None
+                    } else {
+                        Some((*start as usize, *end as usize))
+                    }
+                })
+                .collect();
+
+            let (start, end) = groups[0].unwrap();
+            if start == end {
+                self.position = self.next_char_boundary(end);
+                // empty match right after the last match is not a new match
+                if Some(end) == self.last_match_end {
+                    continue;
+                }
+            } else {
+                self.position = end;
+            }
+            self.last_match_end = Some(end);
+
+            let names = match self.names.get(index) {
+                None => Rc::new(GroupNames::new()),
+                Some(names) => names.clone(),
+            };
+
+            return Some(RegexCaptures {
+                index,
+                text: self.text,
+                groups,
+                names,
+            });
+        }
+
+        None
+    }
+}
+
+unsafe extern "C" fn collect_group_name(
+    name: *const OnigUChar,
+    name_end: *const OnigUChar,
+    group_count: c_int,
+    groups: *mut c_int,
+    _reg: OnigRegex,
+    arg: *mut c_void,
+) -> c_int {
+    let names = &mut *(arg as *mut GroupNames);
+    let name = slice::from_raw_parts(name, name_end.offset_from(name) as usize);
+    let groups = slice::from_raw_parts(groups, group_count as usize);
+
+    names.insert(
+        String::from_utf8_lossy(name).to_string(),
+        groups.iter().map(|group| *group as usize).collect(),
+    );
+    0
+}
+
+/// grammar independent regex api, compile once by `ScieScanner::try_new_with_options`,
+/// then search in `&str` with utf-8 byte offsets.
+impl ScieScanner {
+    pub fn pattern_count(&self) -> usize {
+        if self._ptr.is_null() {
+            return 0;
+        }
+
+        unsafe { (*self._ptr).count as usize }
+    }
+
+    /// named groups of the pattern at `index`, as name to group numbers
+    pub fn group_names(&self, index: usize) -> GroupNames {
+        let mut names = GroupNames::new();
+        if index >= self.pattern_count() {
+            return names;
+        }
+
+        unsafe {
+            let regex = (**(*self._ptr).regexes.add(index)).regex;
+            if onig_number_of_names(regex) > 0 {
+                onig_foreach_name(
+                    regex,
+                    Some(collect_group_name),
+                    &mut names as *mut GroupNames as *mut c_void,
+                );
+            }
+        }
+
+        names
+    }
+
+    pub fn captures_iter<'s, 't>(&'s mut self, text: &'t str) -> RegexMatches<'s, 't> {
+        self.captures_iter_at(text, 0)
+    }
+
+    /// a `byte_position` in the middle of a char starts at the next char
+    pub fn captures_iter_at<'s, 't>(
+        &'s mut self,
+        text: &'t str,
+        byte_position: usize,
+    ) -> RegexMatches<'s, 't> {
+        let position = (byte_position..text.len())
+            .find(|index| text.is_char_boundary(*index))
+            .unwrap_or(byte_position);
+        let string_id = self.last_onig_id;
+        self.last_onig_id = self.last_onig_id + 1;
+
+        let names = (0..self.pattern_count())
+            .map(|index| Rc::new(self.group_names(index)))
+            .collect();
+
+        RegexMatches {
+            scanner: self,
+            string_id,
+            text,
+            position,
+            last_match_end: None,
+            names,
+            aborted: false,
+        }
+    }
+
+    pub fn captures<'t>(&mut self, text: &'t str) -> Option<RegexCaptures<'t>> {
+        self.captures_at(text, 0)
+    }
+
+    pub fn captures_at<'t>(
+        &mut self,
+        text: &'t str,
+        byte_position: usize,
+    ) -> Option<RegexCaptures<'t>> {
+        self.captures_iter_at(text, byte_position).next()
+    }
+
+    pub fn is_match(&mut self, text: &str) -> bool {
+        self.captures(text).is_some()
+    }
+
+    pub fn replace(&mut self, text: &str, replacement: &str) -> String {
+        self.replacen(text, 1, replacement)
+    }
+
+    pub fn replace_all(&mut self, text: &str, replacement: &str) -> String {
+        self.replacen(text, 0, replacement)
+    }
+
+    /// replace the first `limit` matches, or all matches when `limit` is `0`
+    pub fn replacen(&mut self, text: &str, limit: usize, replacement: &str) -> String {
+        let mut output = String::with_capacity(text.len());
+        let mut last_end = 0;
+        for (count, captures) in self.captures_iter(text).enumerate() {
+            if limit > 0 && count >= limit {
+                break;
+            }
+
+            output.push_str(&text[last_end..captures.start()]);
+            captures.expand(replacement, &mut output);
+            last_end = captures.end();
+        }
+
+        output.push_str(&text[last_end..]);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scanner::scanner_options::ScannerOptions;
+    use crate::scanner::scie_scanner::ScieScanner;
+
+    fn compile(pattern: &str) -> ScieScanner {
+        ScieScanner::try_new_with_options(vec![String::from(pattern)], ScannerOptions::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn should_report_invalid_pattern() {
+        let result = ScieScanner::try_new_with_options(
+            vec![String::from("(unclosed")],
+            ScannerOptions::default(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn should_iterate_all_matches_by_byte_offset() {
+        let mut scanner = compile("\\d+");
+        let text = "a💻12 b34 c5";
+        let matches: Vec<(usize, usize, String)> = scanner
+            .captures_iter(text)
+            .map(|caps| (caps.start(), caps.end(), caps.as_str().to_string()))
+            .collect();
+
+        assert_eq!(3, matches.len());
+        assert_eq!((5, 7, String::from("12")), matches[0]);
+        assert_eq!((9, 11, String::from("34")), matches[1]);
+        assert_eq!((13, 14, String::from("5")), matches[2]);
+        scanner.dispose();
+    }
+
+    #[test]
+    fn should_not_loop_on_empty_matches() {
+        let mut scanner = compile("x*");
+        let ranges: Vec<(usize, usize)> = scanner
+            .captures_iter("axxb")
+            .map(|caps| (caps.start(), caps.end()))
+            .collect();
+        assert_eq!(vec![(0, 0), (1, 3), (4, 4)], ranges);
+        scanner.dispose();
+    }
+
+    #[test]
+    fn should_start_at_next_char_out_of_char_boundary() {
+        let mut scanner = compile("x*");
+        let text = "a💻b";
+        let ranges: Vec<(usize, usize)> = scanner
+            .captures_iter_at(text, 2)
+            .map(|caps| (caps.start(), caps.end()))
+            .collect();
+        assert_eq!(vec![(5, 5), (6, 6)], ranges);
+
+        let mut digits = compile("\\d");
+        let caps = digits.captures_at("💻1", 1).unwrap();
+        assert_eq!("1", caps.as_str());
+        assert!(digits.captures_at("💻1", 9).is_none());
+        scanner.dispose();
+        digits.dispose();
+    }
+
+    #[test]
+    fn should_get_named_groups() {
+        let mut scanner = compile("(?<key>\\w+)=(?<value>\\w*)");
+        let names: Vec<String> = scanner.group_names(0).keys().cloned().collect();
+        assert_eq!(vec![String::from("key"), String::from("value")], names);
+
+        let caps = scanner.captures("name=scie").unwrap();
+        assert_eq!(Some("name"), caps.name("key"));
+        assert_eq!(Some("scie"), caps.name("value"));
+        assert_eq!(None, caps.name("other"));
+        scanner.dispose();
+    }
+
+    #[test]
+    fn should_replace_with_group_references() {
+        let mut scanner = compile("(\\w+)@(\\w+)");
+        let output = scanner.replace_all("a@b, c@d", "$2@$1 $$");
+        assert_eq!("b@a $, d@c $", output);
+
+        let first = scanner.replace("a@b, c@d", "${2}");
+        assert_eq!("b, c@d", first);
+        scanner.dispose();
+    }
+
+    #[test]
+    fn should_replace_with_named_references() {
+        let mut scanner = compile("(?<key>\\w+)=(?<value>\\w+)");
+        let output = scanner.replace_all("a=1;b=2", "${value}=${key}");
+        assert_eq!("1=a;2=b", output);
+        scanner.dispose();
+    }
+}
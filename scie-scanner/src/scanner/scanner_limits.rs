@@ -0,0 +1,78 @@
+use scie_onig::{
+    onig_get_match_stack_limit_size, onig_get_retry_limit_in_match, onig_get_retry_limit_in_search,
+    onig_get_subexp_call_limit_in_search, onig_set_match_stack_limit_size,
+    onig_set_retry_limit_in_match, onig_set_retry_limit_in_search,
+    onig_set_subexp_call_limit_in_search,
+};
+#[cfg(test)]
+use std::sync::Mutex;
+
+/// held by the tests which change the limits, as they are process wide
+#[cfg(test)]
+pub(crate) static LIMITS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Backtracking guards of Oniguruma, `0` means unlimited.
+///
+/// Oniguruma keeps those limits process wide, so they apply to every scanner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ScannerLimits {
+    pub retry_limit_in_match: u64,
+    pub retry_limit_in_search: u64,
+    pub subexp_call_limit_in_search: u64,
+    pub match_stack_limit_size: u32,
+}
+
+impl Default for ScannerLimits {
+    /// same as the Oniguruma defaults
+    fn default() -> Self {
+        ScannerLimits {
+            retry_limit_in_match: 10000000,
+            retry_limit_in_search: 0,
+            subexp_call_limit_in_search: 0,
+            match_stack_limit_size: 0,
+        }
+    }
+}
+
+impl ScannerLimits {
+    pub fn current() -> Self {
+        unsafe {
+            ScannerLimits {
+                retry_limit_in_match: onig_get_retry_limit_in_match() as u64,
+                retry_limit_in_search: onig_get_retry_limit_in_search() as u64,
+                subexp_call_limit_in_search: onig_get_subexp_call_limit_in_search() as u64,
+                match_stack_limit_size: onig_get_match_stack_limit_size(),
+            }
+        }
+    }
+
+    pub fn apply(&self) {
+        unsafe {
+            onig_set_retry_limit_in_match(self.retry_limit_in_match as _);
+            onig_set_retry_limit_in_search(self.retry_limit_in_search as _);
+            onig_set_subexp_call_limit_in_search(self.subexp_call_limit_in_search as _);
+            onig_set_match_stack_limit_size(self.match_stack_limit_size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scanner::scanner_limits::{ScannerLimits, LIMITS_LOCK};
+
+    #[test]
+    fn should_apply_and_restore_limits() {
+        let _lock = LIMITS_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        let origin = ScannerLimits::current();
+        let limits = ScannerLimits {
+            retry_limit_in_match: 5000000,
+            ..origin
+        };
+
+        limits.apply();
+        assert_eq!(5000000, ScannerLimits::current().retry_limit_in_match);
+
+        origin.apply();
+        assert_eq!(origin, ScannerLimits::current());
+    }
+}
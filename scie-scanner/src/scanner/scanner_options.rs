@@ -0,0 +1,115 @@
+use core::ptr;
+use scie_onig::{
+    OnigDefaultSyntax, OnigOptionType, OnigSyntaxASIS, OnigSyntaxEmacs, OnigSyntaxGnuRegex,
+    OnigSyntaxGrep, OnigSyntaxJava, OnigSyntaxOniguruma, OnigSyntaxPerl, OnigSyntaxPerl_NG,
+    OnigSyntaxPosixBasic, OnigSyntaxPosixExtended, OnigSyntaxRuby, OnigSyntaxType,
+};
+
+pub use scie_onig::{
+    ONIG_OPTION_CAPTURE_GROUP, ONIG_OPTION_DONT_CAPTURE_GROUP, ONIG_OPTION_EXTEND,
+    ONIG_OPTION_FIND_LONGEST, ONIG_OPTION_FIND_NOT_EMPTY, ONIG_OPTION_IGNORECASE,
+    ONIG_OPTION_MULTILINE, ONIG_OPTION_NONE, ONIG_OPTION_SINGLELINE,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ScannerSyntax {
+    /// `ONIG_SYNTAX_DEFAULT`, which is Ruby unless `onig_set_default_syntax` changed it
+    Default,
+    Oniguruma,
+    Ruby,
+    Perl,
+    /// Perl + named groups
+    PerlNg,
+    Java,
+    Emacs,
+    Grep,
+    GnuRegex,
+    PosixBasic,
+    PosixExtended,
+    Asis,
+}
+
+impl ScannerSyntax {
+    pub fn as_ptr(&self) -> *mut OnigSyntaxType {
+        unsafe {
+            match self {
+                ScannerSyntax::Default => OnigDefaultSyntax,
+                ScannerSyntax::Oniguruma => ptr::addr_of_mut!(OnigSyntaxOniguruma),
+                ScannerSyntax::Ruby => ptr::addr_of_mut!(OnigSyntaxRuby),
+                ScannerSyntax::Perl => ptr::addr_of_mut!(OnigSyntaxPerl),
+                ScannerSyntax::PerlNg => ptr::addr_of_mut!(OnigSyntaxPerl_NG),
+                ScannerSyntax::Java => ptr::addr_of_mut!(OnigSyntaxJava),
+                ScannerSyntax::Emacs => ptr::addr_of_mut!(OnigSyntaxEmacs),
+                ScannerSyntax::Grep => ptr::addr_of_mut!(OnigSyntaxGrep),
+                ScannerSyntax::GnuRegex => ptr::addr_of_mut!(OnigSyntaxGnuRegex),
+                ScannerSyntax::PosixBasic => ptr::addr_of_mut!(OnigSyntaxPosixBasic),
+                ScannerSyntax::PosixExtended => ptr::addr_of_mut!(OnigSyntaxPosixExtended),
+                ScannerSyntax::Asis => ptr::addr_of_mut!(OnigSyntaxASIS),
+            }
+        }
+    }
+}
+
+/// compile options of a scanner, `options` is a set of `ONIG_OPTION_*` flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ScannerOptions {
+    pub options: OnigOptionType,
+    pub syntax: ScannerSyntax,
+}
+
+impl Default for ScannerOptions {
+    /// same as vscode-oniguruma, which the TextMate grammars are written for
+    fn default() -> Self {
+        ScannerOptions {
+            options: ONIG_OPTION_CAPTURE_GROUP,
+            syntax: ScannerSyntax::Default,
+        }
+    }
+}
+
+impl ScannerOptions {
+    pub fn new(options: OnigOptionType, syntax: ScannerSyntax) -> Self {
+        ScannerOptions { options, syntax }
+    }
+
+    pub fn with_option(mut self, option: OnigOptionType) -> Self {
+        self.options |= option;
+        self
+    }
+
+    pub fn without_option(mut self, option: OnigOptionType) -> Self {
+        self.options &= !option;
+        self
+    }
+
+    pub fn with_syntax(mut self, syntax: ScannerSyntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    pub fn has_option(&self, option: OnigOptionType) -> bool {
+        self.options & option == option
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scanner::scanner_options::{
+        ScannerOptions, ScannerSyntax, ONIG_OPTION_CAPTURE_GROUP, ONIG_OPTION_IGNORECASE,
+    };
+
+    #[test]
+    fn should_combine_options() {
+        let options = ScannerOptions::default()
+            .with_option(ONIG_OPTION_IGNORECASE)
+            .with_syntax(ScannerSyntax::Ruby);
+
+        assert!(options.has_option(ONIG_OPTION_CAPTURE_GROUP));
+        assert!(options.has_option(ONIG_OPTION_IGNORECASE));
+        assert_eq!(ScannerSyntax::Ruby, options.syntax);
+
+        let options = options.without_option(ONIG_OPTION_IGNORECASE);
+        assert!(options.has_option(ONIG_OPTION_CAPTURE_GROUP));
+        assert!(!options.has_option(ONIG_OPTION_IGNORECASE));
+    }
+}
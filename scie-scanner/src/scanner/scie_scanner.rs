@@ -1,8 +1,14 @@
 use crate::scanner::onig_string::OnigString;
+use crate::scanner::scanner_limits::ScannerLimits;
+use crate::scanner::scanner_options::{ScannerOptions, ONIG_OPTION_FIND_LONGEST};
 use core::ptr;
 use scie_onig::{
-    createOnigScanner, findNextOnigScannerMatch, freeOnigScanner, OnigScanner, MAX_REGIONS,
+    createOnigScannerWithOptions, findNextOnigScannerMatch, freeOnigScanner, getLastOnigError,
+    getLastOnigStatus, OnigScanner, MAX_REGIONS, ONIGERR_MATCH_STACK_LIMIT_OVER,
+    ONIGERR_RETRY_LIMIT_IN_MATCH_OVER, ONIGERR_RETRY_LIMIT_IN_SEARCH_OVER,
+    ONIGERR_SUBEXP_CALL_LIMIT_IN_SEARCH_OVER, ONIG_MISMATCH,
 };
+use std::ffi::CStr;
 use std::os::raw::c_int;
 
 pub type Pointer = i32;
@@ -20,17 +26,101 @@ pub struct IOnigMatch {
     pub capture_indices: Vec<IOnigCaptureIndex>,
 }
 
+/// Oniguruma gave up before it knew whether the pattern matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum MatchAborted {
+    RetryLimitInMatch,
+    RetryLimitInSearch,
+    SubexpCallLimitInSearch,
+    MatchStackLimit,
+    Other(i32),
+}
+
+impl MatchAborted {
+    pub fn from_status(status: i32) -> MatchAborted {
+        match status {
+            ONIGERR_RETRY_LIMIT_IN_MATCH_OVER => MatchAborted::RetryLimitInMatch,
+            ONIGERR_RETRY_LIMIT_IN_SEARCH_OVER => MatchAborted::RetryLimitInSearch,
+            ONIGERR_SUBEXP_CALL_LIMIT_IN_SEARCH_OVER => MatchAborted::SubexpCallLimitInSearch,
+            ONIGERR_MATCH_STACK_LIMIT_OVER => MatchAborted::MatchStackLimit,
+            _ => MatchAborted::Other(status),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum ScanResult {
+    Matched(IOnigMatch),
+    NoMatch,
+    Aborted(MatchAborted),
+}
+
+impl ScanResult {
+    /// aborted searches are handled as no match
+    pub fn into_match(self) -> Option<IOnigMatch> {
+        match self {
+            ScanResult::Matched(onig_match) => Some(onig_match),
+            ScanResult::NoMatch | ScanResult::Aborted(_) => None,
+        }
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        matches!(self, ScanResult::Aborted(_))
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct ScieScanner {
     #[serde(skip_serializing)]
     pub _ptr: *mut OnigScanner,
     pub last_onig_id: i32,
+    pub options: ScannerOptions,
 }
 
 pub type IntArray = Vec<i32>;
 
 impl ScieScanner {
-    pub fn new(mut patterns: Vec<String>) -> Self {
+    pub fn new(patterns: Vec<String>) -> Self {
+        ScieScanner::new_with_options(patterns, ScannerOptions::default())
+    }
+
+    /// `ONIG_OPTION_FIND_LONGEST` can't be used in the regset of a scanner, so it is left out,
+    /// and `options` of the scanner are the ones which are used, other invalid patterns or
+    /// options give a null scanner, which `try_new_with_options` reports
+    pub fn new_with_options(mut patterns: Vec<String>, options: ScannerOptions) -> Self {
+        let onig_scanner = ScieScanner::create(&mut patterns, &options);
+        if onig_scanner.is_null() && options.has_option(ONIG_OPTION_FIND_LONGEST) {
+            let options = options.without_option(ONIG_OPTION_FIND_LONGEST);
+            return ScieScanner::new_with_options(patterns, options);
+        }
+
+        ScieScanner {
+            last_onig_id: 1,
+            _ptr: onig_scanner,
+            options,
+        }
+    }
+
+    /// same as `new_with_options`, but reports invalid patterns and options, such as
+    /// `ONIG_OPTION_FIND_LONGEST`, instead of a null scanner or leaving them out
+    pub fn try_new_with_options(
+        mut patterns: Vec<String>,
+        options: ScannerOptions,
+    ) -> Result<Self, String> {
+        let onig_scanner = ScieScanner::create(&mut patterns, &options);
+        if onig_scanner.is_null() {
+            let error = unsafe { CStr::from_ptr(getLastOnigError()) };
+            return Err(error.to_string_lossy().to_string());
+        }
+
+        Ok(ScieScanner {
+            last_onig_id: 1,
+            _ptr: onig_scanner,
+            options,
+        })
+    }
+
+    fn create(patterns: &mut [String], options: &ScannerOptions) -> *mut OnigScanner {
         let mut str_len_arr: Vec<c_int> = vec![0; patterns.len()];
         let mut str_ptrs_arr: Vec<*mut ::std::os::raw::c_uchar> = vec![];
         str_ptrs_arr.resize_with(patterns.len(), || ptr::null_mut());
@@ -40,20 +130,28 @@ impl ScieScanner {
             str_len_arr[i] = patterns[i].len() as i32;
         }
 
-        let onig_scanner;
-
         let patterns_length_ptr = str_len_arr.as_mut_ptr();
         let patterns_ptr: *mut *mut u8 = str_ptrs_arr.as_mut_ptr();
 
-        unsafe {
-            onig_scanner =
-                createOnigScanner(patterns_ptr, patterns_length_ptr, patterns.len() as i32);
-        }
+        let onig_scanner = unsafe {
+            createOnigScannerWithOptions(
+                patterns_ptr,
+                patterns_length_ptr,
+                patterns.len() as i32,
+                options.options,
+                options.syntax.as_ptr(),
+            )
+        };
+        onig_scanner as *mut OnigScanner
+    }
 
-        ScieScanner {
-            last_onig_id: 1,
-            _ptr: onig_scanner as *mut OnigScanner,
-        }
+    pub fn limits() -> ScannerLimits {
+        ScannerLimits::current()
+    }
+
+    /// limits are shared by all scanners, see [`ScannerLimits`]
+    pub fn set_limits(limits: &ScannerLimits) {
+        limits.apply();
     }
 
     pub fn dispose(&self) {
@@ -69,10 +167,7 @@ impl ScieScanner {
         string: &str,
         start_position: i32,
     ) -> Option<IOnigMatch> {
-        let mut onig_string = OnigString::new(string, self.last_onig_id);
-        let result = self._find_next_match_sync(&mut onig_string, start_position);
-        self.last_onig_id = self.last_onig_id + 1;
-        return result;
+        self.find_next_match(string, start_position).into_match()
     }
 
     pub fn _find_next_match_sync(
@@ -80,45 +175,83 @@ impl ScieScanner {
         string: &mut OnigString,
         start_position: i32,
     ) -> Option<IOnigMatch> {
+        self._find_next_match(string, start_position).into_match()
+    }
+
+    pub fn find_next_match(&mut self, string: &str, start_position: i32) -> ScanResult {
+        let mut onig_string = OnigString::new(string, self.last_onig_id);
+        let result = self._find_next_match(&mut onig_string, start_position);
+        self.last_onig_id = self.last_onig_id + 1;
+        return result;
+    }
+
+    pub fn _find_next_match(&self, string: &mut OnigString, start_position: i32) -> ScanResult {
+        let raw_match = self._find_next_match_utf8(
+            string.id,
+            string.content,
+            string.convert_utf16offset_to_utf8(start_position),
+        );
+
+        match raw_match {
+            Ok(Some((index, regions))) => {
+                let mut capture_indices = vec![];
+                for (start, end) in regions {
+                    let start = string.convert_utf8offset_to_utf16(start);
+                    let end = string.convert_utf8offset_to_utf16(end);
+                    let length = end - start;
+
+                    capture_indices.push(IOnigCaptureIndex {
+                        start: start as usize,
+                        end: end as usize,
+                        length: length as usize,
+                    })
+                }
+
+                ScanResult::Matched(IOnigMatch {
+                    index,
+                    capture_indices,
+                })
+            }
+            Ok(None) => ScanResult::NoMatch,
+            Err(reason) => ScanResult::Aborted(reason),
+        }
+    }
+
+    /// search with utf-8 byte offsets, regions of unmatched groups are `(-1, -1)`
+    pub fn _find_next_match_utf8(
+        &self,
+        string_id: i32,
+        content: &str,
+        byte_position: i32,
+    ) -> Result<Option<(usize, Vec<(i32, i32)>)>, MatchAborted> {
         unsafe {
             let result = findNextOnigScannerMatch(
                 self._ptr,
-                string.id,
-                string.content.as_ptr() as *mut u8,
-                string.utf8length,
-                string.convert_utf16offset_to_utf8(start_position),
+                string_id,
+                content.as_ptr() as *mut u8,
+                content.len() as i32,
+                byte_position,
             );
 
             if result == 0 {
-                return None;
-            }
+                let status = getLastOnigStatus();
+                if status < 0 && status != ONIG_MISMATCH {
+                    return Err(MatchAborted::from_status(status));
+                }
 
-            let index: usize;
-            let mut capture_indices = vec![];
+                return Ok(None);
+            }
 
             let size = 2 * (1 + MAX_REGIONS);
             let result = std::slice::from_raw_parts(result as *const i32, size as usize);
-            index = result[0] as usize;
-            let count = result[1];
-            let mut offset = 1;
-            for _i in 0..count {
-                offset = offset + 1;
-                let start = string.convert_utf8offset_to_utf16(result[offset]);
-                offset = offset + 1;
-                let end = string.convert_utf8offset_to_utf16(result[offset]);
-                let length = end - start;
-
-                capture_indices.push(IOnigCaptureIndex {
-                    start: start as usize,
-                    end: end as usize,
-                    length: length as usize,
-                })
+            let index = result[0] as usize;
+            let count = result[1] as usize;
+            let mut regions = vec![];
+            for i in 0..count {
+                regions.push((result[2 * i + 2], result[2 * i + 3]));
             }
 
-            return Some(IOnigMatch {
-                index,
-                capture_indices,
-            });
+            Ok(Some((index, regions)))
         }
     }
 }
@@ -133,7 +266,11 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::scanner::scie_scanner::{str_vec_to_string, ScieScanner};
+    use crate::scanner::scanner_limits::{ScannerLimits, LIMITS_LOCK};
+    use crate::scanner::scanner_options::{
+        ScannerOptions, ScannerSyntax, ONIG_OPTION_FIND_LONGEST, ONIG_OPTION_IGNORECASE,
+    };
+    use crate::scanner::scie_scanner::{str_vec_to_string, MatchAborted, ScanResult, ScieScanner};
 
     #[test]
     fn should_init_onig_scanner() {
@@ -511,4 +648,83 @@ mod tests {
         assert_eq!(onig_match.capture_indices[0].length, 0);
         scanner.dispose();
     }
+
+    #[test]
+    fn should_abort_catastrophic_backtracking() {
+        let _lock = LIMITS_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        let origin = ScieScanner::limits();
+        ScieScanner::set_limits(&ScannerLimits {
+            retry_limit_in_match: 100000,
+            ..origin
+        });
+
+        let mut scanner = ScieScanner::new(vec![String::from("^(\\w+\\s?)*$")]);
+        let result = scanner.find_next_match("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa!", 0);
+        ScieScanner::set_limits(&origin);
+
+        assert!(result.is_aborted());
+        if let ScanResult::Aborted(reason) = result {
+            assert_eq!(MatchAborted::RetryLimitInMatch, reason);
+        }
+        assert!(scanner.find_next_match_sync("aaaa!", 0).is_none());
+
+        scanner.dispose();
+    }
+
+    #[test]
+    fn should_match_with_ignore_case_option() {
+        let options = ScannerOptions::default().with_option(ONIG_OPTION_IGNORECASE);
+        let mut scanner = ScieScanner::new_with_options(vec![String::from("world")], options);
+        let result = scanner.find_next_match_sync("Hello WORLD!", 0).unwrap();
+        assert_eq!(6, result.capture_indices[0].start);
+        assert_eq!(11, result.capture_indices[0].end);
+        scanner.dispose();
+
+        let mut default_scanner = ScieScanner::new(vec![String::from("world")]);
+        assert!(default_scanner
+            .find_next_match_sync("Hello WORLD!", 0)
+            .is_none());
+        default_scanner.dispose();
+    }
+
+    #[test]
+    fn should_compile_with_perl_ng_syntax() {
+        let options = ScannerOptions::default().with_syntax(ScannerSyntax::PerlNg);
+        let mut scanner = ScieScanner::new_with_options(vec![String::from("(?<word>b+)")], options);
+        let result = scanner.find_next_match_sync("abbc", 0).unwrap();
+        assert_eq!(1, result.capture_indices[0].start);
+        assert_eq!(3, result.capture_indices[0].end);
+        scanner.dispose();
+    }
+
+    #[test]
+    fn should_leave_out_find_longest_only() {
+        let options = ScannerOptions::default()
+            .with_option(ONIG_OPTION_FIND_LONGEST)
+            .with_option(ONIG_OPTION_IGNORECASE);
+        let error = ScieScanner::try_new_with_options(vec![String::from("a+")], options);
+        assert!(error.is_err());
+
+        let mut scanner = ScieScanner::new_with_options(vec![String::from("a+")], options);
+        assert!(!scanner._ptr.is_null());
+        assert_eq!(
+            ScannerOptions::default().with_option(ONIG_OPTION_IGNORECASE),
+            scanner.options
+        );
+        let result = scanner.find_next_match_sync("bAa", 0).unwrap();
+        assert_eq!(1, result.capture_indices[0].start);
+        assert_eq!(3, result.capture_indices[0].end);
+        scanner.dispose();
+    }
+
+    #[test]
+    fn should_not_change_options_of_invalid_patterns() {
+        let options = ScannerOptions::default().with_syntax(ScannerSyntax::Java);
+        let error = ScieScanner::try_new_with_options(vec![String::from("[a")], options);
+        assert!(error.is_err());
+
+        let scanner = ScieScanner::new_with_options(vec![String::from("[a")], options);
+        assert!(scanner._ptr.is_null());
+        assert_eq!(options, scanner.options);
+    }
 }